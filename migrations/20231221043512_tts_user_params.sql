@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE users ADD COLUMN tts_voice TEXT NOT NULL DEFAULT 'en';
+ALTER TABLE users ADD COLUMN tts_speed SMALLINT NOT NULL DEFAULT 175;
+ALTER TABLE users ADD COLUMN tts_pitch SMALLINT NOT NULL DEFAULT 50;
+ALTER TABLE users ADD COLUMN tts_amplitude SMALLINT NOT NULL DEFAULT 100;
+ALTER TABLE users ADD COLUMN tts_gap SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE speech_command_integrations (
+    id SERIAL PRIMARY KEY,
+    owner_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    webhook_url TEXT NOT NULL,
+    signing_secret TEXT NOT NULL,
+    api_token_hash BYTEA NOT NULL UNIQUE,
+    commands TEXT[] NOT NULL DEFAULT '{}',
+    tts_voice TEXT NOT NULL DEFAULT 'en',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN live_transcripts BOOLEAN NOT NULL DEFAULT FALSE;
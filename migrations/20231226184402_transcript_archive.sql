@@ -0,0 +1,22 @@
+-- Add migration script here
+CREATE TABLE transcript_sessions (
+    id SERIAL PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    voice_channel_id BIGINT NOT NULL,
+    -- scripty_audio_handler::TranscriptFormat
+    format SMALLINT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at TIMESTAMPTZ
+);
+CREATE INDEX transcript_sessions_guild_id_idx ON transcript_sessions (guild_id);
+
+CREATE TABLE transcript_entries (
+    id BIGSERIAL PRIMARY KEY,
+    session_id INTEGER NOT NULL REFERENCES transcript_sessions (id) ON DELETE CASCADE,
+    -- offset from the start of the session, kept in the clear so entries can be ordered
+    start_ms BIGINT NOT NULL,
+    -- JSON-encoded scripty_audio_handler::TranscriptEntry, encrypted
+    entry BYTEA NOT NULL,
+    nonce BYTEA NOT NULL
+);
+CREATE INDEX transcript_entries_session_id_idx ON transcript_entries (session_id);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN session_summaries BOOLEAN NOT NULL DEFAULT FALSE;
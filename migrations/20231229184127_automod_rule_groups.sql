@@ -0,0 +1,20 @@
+-- Add migration script here
+-- language: postgresql
+
+CREATE TABLE automod_rule_groups (
+    item_id SERIAL PRIMARY KEY,
+    source_id integer NOT NULL REFERENCES automod_config (item_id) ON DELETE CASCADE,
+    group_name TEXT NOT NULL,
+
+    -- action every rule in the group takes when triggered, same values as automod_rules.rule_action
+    default_action SMALLINT NOT NULL,
+
+    -- rules in a disabled group are not checked
+    enabled boolean NOT NULL DEFAULT true,
+
+    CONSTRAINT unique_group_name UNIQUE (source_id, group_name)
+);
+
+-- rules that aren't in any group have a NULL group_id
+-- deleting a group deletes all of its rules
+ALTER TABLE automod_rules ADD COLUMN group_id integer REFERENCES automod_rule_groups (item_id) ON DELETE CASCADE;
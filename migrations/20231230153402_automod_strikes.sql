@@ -0,0 +1,27 @@
+-- Add migration script here
+-- language: postgresql
+
+ALTER TABLE automod_config
+    -- count violations per user, and escalate the action taken as they add up?
+    ADD COLUMN strikes_enabled boolean NOT NULL DEFAULT false,
+    -- strikes older than this many hours no longer count
+    ADD COLUMN strike_decay_hours integer NOT NULL DEFAULT 24,
+    -- number of strikes at which a user is muted, removed from voice, and timed out
+    -- 0 to never escalate to that action
+    ADD COLUMN strike_mute_threshold integer NOT NULL DEFAULT 2,
+    ADD COLUMN strike_kick_threshold integer NOT NULL DEFAULT 3,
+    ADD COLUMN strike_timeout_threshold integer NOT NULL DEFAULT 5,
+    -- how long a Discord timeout lasts
+    ADD COLUMN timeout_minutes integer NOT NULL DEFAULT 10,
+    -- how long until a muted user is automatically unmuted
+    -- 0 to keep them muted until someone unmutes them
+    ADD COLUMN mute_minutes integer NOT NULL DEFAULT 0;
+
+CREATE TABLE automod_strikes (
+    item_id SERIAL PRIMARY KEY,
+    source_id integer NOT NULL REFERENCES automod_config (item_id) ON DELETE CASCADE,
+    user_id bigint NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX automod_strikes_user_idx ON automod_strikes (source_id, user_id, created_at);
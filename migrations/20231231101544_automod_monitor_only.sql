@@ -0,0 +1,5 @@
+-- Add migration script here
+-- language: postgresql
+
+-- log what automod would have done, without actually deleting messages or punishing anyone?
+ALTER TABLE automod_config ADD COLUMN monitor_only boolean NOT NULL DEFAULT false;
@@ -0,0 +1,24 @@
+-- Add migration script here
+-- language: postgresql
+
+-- users with any of these roles are never actioned by automod
+CREATE TABLE automod_exempt_roles (
+    source_id integer NOT NULL REFERENCES automod_config (item_id) ON DELETE CASCADE,
+    role_id bigint NOT NULL,
+    PRIMARY KEY (source_id, role_id)
+);
+
+-- automod is off entirely in these channels
+CREATE TABLE automod_exempt_channels (
+    source_id integer NOT NULL REFERENCES automod_config (item_id) ON DELETE CASCADE,
+    channel_id bigint NOT NULL,
+    PRIMARY KEY (source_id, channel_id)
+);
+
+-- a rule with any rows here only applies in those channels
+-- rules without any rows apply everywhere
+CREATE TABLE automod_rule_channels (
+    rule_id integer NOT NULL REFERENCES automod_rules (item_id) ON DELETE CASCADE,
+    channel_id bigint NOT NULL,
+    PRIMARY KEY (rule_id, channel_id)
+);
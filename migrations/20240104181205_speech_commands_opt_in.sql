@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE guilds ADD COLUMN speech_commands BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,23 @@
+-- Add migration script here
+-- language: postgresql
+
+-- keep in sync with the ranges in scripty_automod::strikes
+UPDATE automod_config SET
+    strike_decay_hours = LEAST(GREATEST(strike_decay_hours, 1), 720),
+    strike_mute_threshold = LEAST(GREATEST(strike_mute_threshold, 0), 100),
+    strike_kick_threshold = LEAST(GREATEST(strike_kick_threshold, 0), 100),
+    strike_timeout_threshold = LEAST(GREATEST(strike_timeout_threshold, 0), 100),
+    timeout_minutes = LEAST(GREATEST(timeout_minutes, 1), 40320),
+    mute_minutes = LEAST(GREATEST(mute_minutes, 0), 40320);
+
+ALTER TABLE automod_config
+    ADD CONSTRAINT automod_config_strike_decay_hours_check
+        CHECK (strike_decay_hours BETWEEN 1 AND 720),
+    ADD CONSTRAINT automod_config_strike_thresholds_check
+        CHECK (strike_mute_threshold BETWEEN 0 AND 100
+            AND strike_kick_threshold BETWEEN 0 AND 100
+            AND strike_timeout_threshold BETWEEN 0 AND 100),
+    ADD CONSTRAINT automod_config_timeout_minutes_check
+        CHECK (timeout_minutes BETWEEN 1 AND 40320),
+    ADD CONSTRAINT automod_config_mute_minutes_check
+        CHECK (mute_minutes BETWEEN 0 AND 40320);
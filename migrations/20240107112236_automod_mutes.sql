@@ -0,0 +1,13 @@
+-- Add migration script here
+-- language: postgresql
+
+-- users automod muted for mute_minutes, and when to unmute them
+-- kept in the database so pending unmutes survive a restart
+CREATE TABLE automod_mutes (
+    source_id integer NOT NULL REFERENCES automod_config (item_id) ON DELETE CASCADE,
+    user_id bigint NOT NULL,
+    unmute_at timestamptz NOT NULL,
+    PRIMARY KEY (source_id, user_id)
+);
+
+CREATE INDEX automod_mutes_unmute_at_idx ON automod_mutes (unmute_at);
@@ -21,16 +21,22 @@ use serenity::{
 use songbird::{Event, EventContext, EventHandler};
 
 use crate::{
+	call_stats::{register_call_stats, CallStats},
 	events::*,
+	transcript::SessionTranscript,
 	types::{
 		ActiveUserSet,
+		CallSession,
 		NextUserList,
 		SeenUsers,
+		SessionState,
 		SsrcIgnoredMap,
+		SsrcLivePartialMap,
 		SsrcSpeakingSet,
 		SsrcStreamMap,
 		SsrcUserDataMap,
 		SsrcUserIdMap,
+		SsrcUtteranceMap,
 		SsrcVoiceIngestMap,
 		TranscriptResults,
 	},
@@ -42,6 +48,8 @@ pub struct SsrcMaps {
 	pub ssrc_user_data_map:    SsrcUserDataMap,
 	pub ssrc_ignored_map:      SsrcIgnoredMap,
 	pub ssrc_voice_ingest_map: SsrcVoiceIngestMap,
+	pub ssrc_live_partial_map: SsrcLivePartialMap,
+	pub ssrc_utterance_map:    SsrcUtteranceMap,
 	pub ssrc_speaking_set:     SsrcSpeakingSet,
 	pub active_user_set:       ActiveUserSet,
 	pub next_user_list:        NextUserList,
@@ -66,6 +74,12 @@ pub struct AudioHandler {
 	auto_detect_lang:     Arc<AtomicBool>,
 	transcribe_only_role: Arc<RwLock<Option<RoleId>>>,
 	translate:            Arc<AtomicBool>,
+	live_transcripts:     Arc<AtomicBool>,
+	session_summaries:    Arc<AtomicBool>,
+	speech_commands:      Arc<AtomicBool>,
+	call_stats:           Arc<CallStats>,
+	/// Set once a reconnect has taken over this session, so this handler leaves it alone.
+	session_handed_off:   Arc<AtomicBool>,
 }
 
 impl AudioHandler {
@@ -76,7 +90,7 @@ impl AudioHandler {
 		channel_id: ChannelId,
 		voice_channel_id: ChannelId,
 		thread_id: Option<ChannelId>,
-		record_transcriptions: bool,
+		session: CallSession,
 		automod_server_cfg: AutomodServerConfig,
 	) -> Result<Self, sqlx::Error> {
 		let maps = SsrcMaps {
@@ -85,11 +99,32 @@ impl AudioHandler {
 			ssrc_user_data_map:    DashMap::with_hasher(RandomState::new()),
 			ssrc_ignored_map:      DashMap::with_hasher(RandomState::new()),
 			ssrc_voice_ingest_map: DashMap::with_hasher(RandomState::new()),
+			ssrc_live_partial_map: DashMap::with_hasher(RandomState::new()),
+			ssrc_utterance_map:    DashMap::with_hasher(RandomState::new()),
 			ssrc_speaking_set:     DashSet::with_hasher(RandomState::new()),
 			active_user_set:       DashSet::with_hasher(RandomState::new()),
 			next_user_list:        RwLock::new(VecDeque::with_capacity(10)),
 		};
 
+		let (transcript_results, seen_users, call_stats) = match session {
+			CallSession::New(record_transcriptions) => {
+				let transcript_results = match record_transcriptions {
+					Some(format) => Some(Arc::new(
+						SessionTranscript::start(guild_id, voice_channel_id, format).await?,
+					)),
+					None => None,
+				};
+				let seen_users = record_transcriptions
+					.map(|_| Arc::new(DashSet::with_hasher(RandomState::new())));
+				(transcript_results, seen_users, Arc::new(CallStats::new()))
+			}
+			CallSession::Resumed(SessionState {
+				transcript_results,
+				seen_users,
+				call_stats,
+			}) => (transcript_results, seen_users, call_stats),
+		};
+
 		let this = Self {
 			ssrc_state: Arc::new(maps),
 			guild_id,
@@ -101,14 +136,19 @@ impl AudioHandler {
 			premium_level: Arc::new(AtomicU8::new(0)),
 			verbose: Arc::new(AtomicBool::new(false)),
 			language: Arc::new(Default::default()),
-			transcript_results: record_transcriptions.then(|| Arc::new(RwLock::new(Vec::new()))),
-			seen_users: record_transcriptions
-				.then(|| Arc::new(DashSet::with_hasher(RandomState::new()))),
+			transcript_results,
+			seen_users,
 			automod_server_cfg: Arc::new(automod_server_cfg),
 			auto_detect_lang: Arc::new(AtomicBool::new(false)),
 			transcribe_only_role: Arc::new(RwLock::new(None)),
 			translate: Arc::new(AtomicBool::new(false)),
+			live_transcripts: Arc::new(AtomicBool::new(false)),
+			session_summaries: Arc::new(AtomicBool::new(false)),
+			speech_commands: Arc::new(AtomicBool::new(false)),
+			call_stats,
+			session_handed_off: Arc::new(AtomicBool::new(false)),
 		};
+		register_call_stats(guild_id, Arc::clone(&this.call_stats));
 		this.reload_config().await?;
 
 		let t2 = this.clone();
@@ -134,8 +174,8 @@ impl AudioHandler {
 	pub async fn reload_config(&self) -> Result<(), sqlx::Error> {
 		let db = scripty_db::get_db();
 		let mut guild_res = sqlx::query!(
-			"SELECT be_verbose, language, auto_detect_lang, transcript_only_role, translate FROM \
-			 guilds WHERE guild_id = $1",
+			"SELECT be_verbose, language, auto_detect_lang, transcript_only_role, translate, \
+			 live_transcripts, session_summaries, speech_commands FROM guilds WHERE guild_id = $1",
 			self.guild_id.get() as i64
 		)
 		.fetch_one(db)
@@ -152,6 +192,12 @@ impl AudioHandler {
 			self.auto_detect_lang.store(false, Ordering::Relaxed);
 		}
 		self.translate.store(guild_res.translate, Ordering::Relaxed);
+		self.live_transcripts
+			.store(guild_res.live_transcripts, Ordering::Relaxed);
+		self.session_summaries
+			.store(guild_res.session_summaries, Ordering::Relaxed);
+		self.speech_commands
+			.store(guild_res.speech_commands, Ordering::Relaxed);
 		std::mem::swap(&mut *self.language.write(), &mut guild_res.language);
 		std::mem::swap(
 			&mut *self.transcribe_only_role.write(),
@@ -175,11 +221,13 @@ impl EventHandler for AudioHandler {
 				self.seen_users.clone(),
 				self.guild_id,
 				*self.transcribe_only_role.read(),
+				Arc::clone(&self.automod_server_cfg),
 			)),
 			EventContext::VoiceTick(voice_data) => tokio::spawn(voice_tick(
 				voice_data.clone(),
 				Arc::clone(&self.ssrc_state),
 				self.guild_id,
+				self.voice_channel_id,
 				self.language.clone(),
 				self.verbose.clone(),
 				self.context.clone(),
@@ -189,6 +237,9 @@ impl EventHandler for AudioHandler {
 				Arc::clone(&self.automod_server_cfg),
 				Arc::clone(&self.auto_detect_lang),
 				Arc::clone(&self.translate),
+				Arc::clone(&self.live_transcripts),
+				Arc::clone(&self.speech_commands),
+				Arc::clone(&self.call_stats),
 			)),
 			EventContext::ClientDisconnect(client_disconnect_data) => {
 				tokio::spawn(client_disconnect(
@@ -218,6 +269,9 @@ impl EventHandler for AudioHandler {
 				self.thread_id,
 				self.transcript_results.clone(),
 				self.seen_users.clone(),
+				self.session_summaries.load(Ordering::Relaxed),
+				Arc::clone(&self.call_stats),
+				Arc::clone(&self.session_handed_off),
 			)),
 			_ => return None,
 		};
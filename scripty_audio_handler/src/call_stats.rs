@@ -0,0 +1,142 @@
+use std::{
+	sync::{Arc, OnceLock as OnceCell},
+	time::{Duration, Instant},
+};
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use scripty_i18n::LanguageIdentifier;
+use serenity::all::GuildId;
+
+/// Most speakers [`CallStats::render`] will list.
+pub const MAX_RENDERED_SPEAKERS: usize = 40;
+
+/// How much each user has taken part in a call.
+#[derive(Debug, Clone, Default)]
+pub struct SpeakerStats {
+	pub username:      String,
+	/// Total time spent speaking.
+	pub speaking_time: Duration,
+	/// How many utterances were transcribed.
+	pub utterances:    u64,
+	/// How many words were transcribed.
+	pub words:         u64,
+}
+
+/// Participation statistics for everyone in a call, since Scripty joined.
+pub struct CallStats {
+	started:  Instant,
+	speakers: DashMap<u64, SpeakerStats, RandomState>,
+}
+
+impl CallStats {
+	pub fn new() -> Self {
+		Self {
+			started:  Instant::now(),
+			speakers: DashMap::with_hasher(RandomState::new()),
+		}
+	}
+
+	/// How long the call has been going on for.
+	pub fn duration(&self) -> Duration {
+		self.started.elapsed()
+	}
+
+	/// Count some audio received from a user.
+	pub fn add_speaking_time(&self, user_id: u64, username: &str, time: Duration) {
+		let mut stats = self.speakers.entry(user_id).or_default();
+		if stats.username != username {
+			stats.username = username.to_string();
+		}
+		stats.speaking_time += time;
+	}
+
+	/// Count a finished utterance from a user.
+	pub fn add_utterance(&self, user_id: u64, transcript: &str) {
+		let mut stats = self.speakers.entry(user_id).or_default();
+		stats.utterances += 1;
+		stats.words += transcript.split_whitespace().count() as u64;
+	}
+
+	/// Statistics for every user that has spoken, most talkative first.
+	pub fn speakers(&self) -> Vec<(u64, SpeakerStats)> {
+		let mut speakers = self
+			.speakers
+			.iter()
+			.map(|x| (*x.key(), x.value().clone()))
+			.collect::<Vec<_>>();
+		speakers.sort_by(|a, b| b.1.speaking_time.cmp(&a.1.speaking_time));
+		speakers
+	}
+
+	/// Render the statistics as one line per speaker, for use in messages.
+	///
+	/// If nobody has spoken yet, this says so instead.
+	///
+	/// Only the [`MAX_RENDERED_SPEAKERS`] most talkative speakers are listed, to fit in an embed.
+	pub fn render(&self, language: &LanguageIdentifier) -> String {
+		let speakers = self.speakers();
+		if speakers.is_empty() {
+			return format_message!(language, "stats-call-no-speakers");
+		}
+		let total_time: Duration = speakers.iter().map(|(_, s)| s.speaking_time).sum();
+		let hidden_speakers = speakers.len().saturating_sub(MAX_RENDERED_SPEAKERS);
+
+		let mut lines = speakers
+			.into_iter()
+			.take(MAX_RENDERED_SPEAKERS)
+			.map(|(user_id, stats)| {
+				format_message!(
+					language,
+					"stats-call-speaker",
+					userMention: format!("<@{}>", user_id),
+					speakingSeconds: stats.speaking_time.as_secs(),
+					speakingShare: (stats.speaking_time.as_secs_f64()
+						/ total_time.as_secs_f64().max(f64::EPSILON)
+						* 100.0)
+						.round(),
+					utterances: stats.utterances,
+					words: stats.words
+				)
+			})
+			.collect::<Vec<_>>();
+		if hidden_speakers > 0 {
+			lines.push(format_message!(
+				language,
+				"stats-call-more-speakers",
+				count: hidden_speakers
+			));
+		}
+		lines.join("\n")
+	}
+}
+
+impl Default for CallStats {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+static CALL_STATS: OnceCell<DashMap<GuildId, Arc<CallStats>, RandomState>> = OnceCell::new();
+
+fn get_all_call_stats() -> &'static DashMap<GuildId, Arc<CallStats>, RandomState> {
+	CALL_STATS.get_or_init(|| DashMap::with_hasher(RandomState::default()))
+}
+
+/// Get the statistics for the call Scripty is currently in, in this guild.
+///
+/// Returns `None` if Scripty is not currently in a call in this guild.
+pub fn get_call_stats(guild_id: GuildId) -> Option<Arc<CallStats>> {
+	get_all_call_stats()
+		.get(&guild_id)
+		.map(|x| Arc::clone(x.value()))
+}
+
+pub(crate) fn register_call_stats(guild_id: GuildId, stats: Arc<CallStats>) {
+	get_all_call_stats().insert(guild_id, stats);
+}
+
+/// Remove the statistics for a call, if they haven't been replaced by a newer call's already.
+pub(crate) fn remove_call_stats(guild_id: GuildId, stats: &Arc<CallStats>) {
+	get_all_call_stats().remove_if(&guild_id, |_, x| Arc::ptr_eq(x, stats));
+}
@@ -8,10 +8,9 @@ use serenity::{
 };
 use songbird::{error::JoinError, events::Event, CoreEvent};
 
-use crate::Error;
+use crate::{types::CallSession, Error, TranscriptFormat};
 
 // TODO: implement `force`
-#[allow(clippy::let_unit_value)]
 pub async fn connect_to_vc(
 	ctx: Context,
 	guild_id: GuildId,
@@ -19,7 +18,30 @@ pub async fn connect_to_vc(
 	voice_channel_id: ChannelId,
 	thread_id: Option<ChannelId>,
 	_force: bool,
-	record_transcriptions: bool,
+	record_transcriptions: Option<TranscriptFormat>,
+) -> Result<(), Error> {
+	connect_with_session(
+		ctx,
+		guild_id,
+		channel_id,
+		voice_channel_id,
+		thread_id,
+		CallSession::New(record_transcriptions),
+	)
+	.await
+}
+
+/// Connect to a voice chat, as part of `session`.
+///
+/// Reconnects use this to carry on the session of the connection that dropped.
+#[allow(clippy::let_unit_value)]
+pub(crate) async fn connect_with_session(
+	ctx: Context,
+	guild_id: GuildId,
+	channel_id: ChannelId,
+	voice_channel_id: ChannelId,
+	thread_id: Option<ChannelId>,
+	session: CallSession,
 ) -> Result<(), Error> {
 	debug!(%guild_id, "fetching webhook");
 	// thanks to Discord undocumented breaking changes, we have to do this
@@ -93,7 +115,7 @@ pub async fn connect_to_vc(
 		channel_id,
 		voice_channel_id,
 		thread_id,
-		record_transcriptions,
+		session,
 		automod_server_cfg,
 	)
 	.await?;
@@ -1 +1,23 @@
 pub const SIZE_OF_I16: usize = std::mem::size_of::<i16>();
+
+/// Messages longer than this many characters are cut off before being read aloud.
+pub const MAX_TTS_MESSAGE_LENGTH: usize = 300;
+
+/// How many messages can wait to be read aloud in one guild before new ones are dropped.
+pub const MAX_TTS_QUEUE_LENGTH: usize = 16;
+
+/// How often a new live partial transcript is requested for each user while they're speaking.
+pub const LIVE_PARTIAL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// Maximum length of one utterance, in seconds, if the config doesn't set one.
+pub const DEFAULT_MAX_UTTERANCE_LENGTH: u64 = 20;
+
+/// Once an utterance is within this many seconds of the maximum length,
+/// it is cut at the next quiet packet rather than waiting to be cut mid-word.
+pub const UTTERANCE_QUIET_CUT_WINDOW: u64 = 5;
+
+/// Packets with an RMS amplitude below this are quiet enough to cut an utterance at.
+pub const QUIET_PACKET_RMS: f64 = 300.0;
+
+/// Words the model is less confident than this in are highlighted in verbose transcripts.
+pub const LOW_CONFIDENCE_WORD: f64 = 0.5;
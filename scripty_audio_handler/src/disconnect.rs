@@ -12,6 +12,8 @@ pub async fn disconnect_from_vc(ctx: &Context, guild_id: GuildId) -> Result<bool
 		Err(e) => Err(e.into()),
 	};
 
+	crate::tts::remove_queue(guild_id);
+
 	let existing = super::AUTO_LEAVE_TASKS
 		.get_or_init(|| DashMap::with_hasher(ahash::RandomState::default()))
 		.remove(&guild_id);
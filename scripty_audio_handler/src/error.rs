@@ -5,6 +5,7 @@ use std::{
 
 use backtrace::Backtrace;
 use scripty_db::sqlx;
+use scripty_tts::EspeakNgError;
 use songbird::error::JoinError;
 
 pub struct Error {
@@ -17,6 +18,9 @@ pub enum ErrorKind {
 	Join(JoinError),
 	Database(sqlx::Error),
 	Serenity(serenity::Error),
+	Tts(EspeakNgError),
+	/// A TTS synthesis task panicked.
+	TtsTask(tokio::task::JoinError),
 }
 
 impl Error {
@@ -59,12 +63,34 @@ impl From<serenity::Error> for Error {
 	}
 }
 
+impl From<EspeakNgError> for Error {
+	#[inline]
+	fn from(e: EspeakNgError) -> Self {
+		Self {
+			kind:      ErrorKind::Tts(e),
+			backtrace: Backtrace::new_unresolved(),
+		}
+	}
+}
+
+impl From<tokio::task::JoinError> for Error {
+	#[inline]
+	fn from(e: tokio::task::JoinError) -> Self {
+		Self {
+			kind:      ErrorKind::TtsTask(e),
+			backtrace: Backtrace::new_unresolved(),
+		}
+	}
+}
+
 impl Display for Error {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
 		match &self.kind {
 			ErrorKind::Join(e) => write!(f, "JoinError: {}", e),
 			ErrorKind::Database(e) => write!(f, "DatabaseError: {}", e),
 			ErrorKind::Serenity(e) => write!(f, "SerenityError: {}", e),
+			ErrorKind::Tts(e) => write!(f, "TtsError: {}", e),
+			ErrorKind::TtsTask(e) => write!(f, "TtsTaskError: {}", e),
 		}
 	}
 }
@@ -1,6 +1,9 @@
-use std::sync::{
-	atomic::{AtomicU8, Ordering},
-	Arc,
+use std::{
+	sync::{
+		atomic::{AtomicU8, Ordering},
+		Arc,
+	},
+	time::Instant,
 };
 
 use serenity::{
@@ -9,7 +12,11 @@ use serenity::{
 };
 use songbird::model::payload::ClientDisconnect;
 
-use crate::{audio_handler::ArcSsrcMaps, types::TranscriptResults};
+use crate::{
+	audio_handler::ArcSsrcMaps,
+	transcript::{TranscriptEntry, TranscriptEntryKind},
+	types::TranscriptResults,
+};
 
 pub async fn client_disconnect(
 	client_disconnect_data: ClientDisconnect,
@@ -42,7 +49,9 @@ pub async fn client_disconnect(
 	ssrc_state.ssrc_stream_map.remove(&ssrc);
 	ssrc_state.ssrc_ignored_map.remove(&ssrc);
 	ssrc_state.ssrc_voice_ingest_map.remove(&ssrc);
-	let Some((_, (username, avatar_url, _))) = ssrc_state.ssrc_user_data_map.remove(&ssrc) else {
+	ssrc_state.ssrc_live_partial_map.remove(&ssrc);
+	ssrc_state.ssrc_utterance_map.remove(&ssrc);
+	let Some((_, (username, avatar_url, ..))) = ssrc_state.ssrc_user_data_map.remove(&ssrc) else {
 		warn!(%ssrc, "got no user data for ssrc");
 		return;
 	};
@@ -80,7 +89,13 @@ pub async fn client_disconnect(
 	}
 
 	if let Some(transcript_results) = transcript_results {
-		let mut transcript_results = transcript_results.write();
-		transcript_results.push(format!("[{}] - event: disconnected", username));
+		let offset = transcript_results.offset(Instant::now());
+		transcript_results.push(TranscriptEntry {
+			start: offset,
+			end: offset,
+			user_id: user_id.0,
+			username,
+			kind: TranscriptEntryKind::Disconnected,
+		});
 	}
 }
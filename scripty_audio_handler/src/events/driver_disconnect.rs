@@ -1,17 +1,25 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{
+	borrow::Cow,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
 
 use serenity::{
 	all::UserId,
-	builder::{CreateAttachment, CreateMessage, ExecuteWebhook},
+	builder::{CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage, ExecuteWebhook},
 	client::Context,
 	model::{id::ChannelId, webhook::Webhook},
 };
 use songbird::{events::context_data::DisconnectReason, id::GuildId, model::CloseCode};
 
 use crate::{
-	connect_to_vc,
+	call_stats::{remove_call_stats, CallStats},
+	connect::connect_with_session,
 	error::ErrorKind,
-	types::{SeenUsers, TranscriptResults},
+	summary::{get_summarizer, SessionSummary},
+	types::{CallSession, SeenUsers, SessionState, TranscriptResults},
 };
 
 pub async fn driver_disconnect(
@@ -24,8 +32,19 @@ pub async fn driver_disconnect(
 	thread_id: Option<ChannelId>,
 	transcript_results: TranscriptResults,
 	seen_users: SeenUsers,
+	session_summaries: bool,
+	call_stats: Arc<CallStats>,
+	session_handed_off: Arc<AtomicBool>,
 ) {
 	debug!(?guild_id, "handler disconnected");
+	if session_handed_off.load(Ordering::Relaxed) {
+		// a reconnect already took this session over, and will finish it when it's done
+		debug!(
+			?guild_id,
+			"session was handed off to a reconnect, ignoring disconnect"
+		);
+		return;
+	}
 	let (should_reconnect, reason) = match reason {
 		Some(DisconnectReason::AttemptDiscarded) => {
 			warn!(?guild_id, "reconnection failed due to another request");
@@ -71,48 +90,6 @@ pub async fn driver_disconnect(
 		}
 	};
 
-	if should_reconnect {
-		debug!(?guild_id, "scheduling reconnect");
-		// retry connection in 30 seconds
-		let record_transcriptions = transcript_results.is_some();
-		let webhook2 = webhook.clone();
-		let ctx2 = ctx.clone();
-		let ctx3 = ctx.clone();
-		tokio::spawn(async move {
-			debug!(?guild_id, "sleeping 30 seconds");
-			tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-			debug!(?guild_id, "attempting reconnect");
-
-			if let Err(ErrorKind::Join(e)) = connect_to_vc(
-				ctx2,
-				serenity::all::GuildId::new(guild_id.0.get()),
-				channel_id,
-				voice_channel_id,
-				thread_id,
-				false,
-				record_transcriptions,
-			)
-			.await
-			.map_err(|x| x.kind)
-			{
-				if let Err(e) = webhook2
-					.execute(
-						ctx3,
-						false,
-						ExecuteWebhook::default()
-							.content(format!("Failed to reconnect due to: {}", e)),
-					)
-					.await
-				{
-					debug!(
-						?guild_id,
-						"failed to notify user about reconnect failure: {}", e
-					);
-				}
-			}
-		});
-	}
-
 	if let Some(reason) = reason {
 		debug!(?guild_id, "giving user reason for disconnection");
 		if let Err(e) = webhook
@@ -138,17 +115,110 @@ pub async fn driver_disconnect(
 		}
 	}
 
+	let session = SessionState {
+		transcript_results,
+		seen_users,
+		call_stats,
+	};
+	if !should_reconnect {
+		finish_session(&ctx, guild_id, &webhook, session, session_summaries).await;
+		return;
+	}
+
+	debug!(?guild_id, "scheduling reconnect");
+	// the session carries on after reconnecting, so only the new connection should finish it
+	session_handed_off.store(true, Ordering::Relaxed);
+	// retry connection in 30 seconds
+	let webhook2 = webhook.clone();
+	let ctx2 = ctx.clone();
+	tokio::spawn(async move {
+		debug!(?guild_id, "sleeping 30 seconds");
+		tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+		debug!(?guild_id, "attempting reconnect");
+
+		let Err(e) = connect_with_session(
+			ctx2.clone(),
+			serenity::all::GuildId::new(guild_id.0.get()),
+			channel_id,
+			voice_channel_id,
+			thread_id,
+			CallSession::Resumed(session.clone()),
+		)
+		.await
+		else {
+			return;
+		};
+
+		if let ErrorKind::Join(e) = e.kind {
+			if let Err(e) = webhook2
+				.execute(
+					&ctx2,
+					false,
+					ExecuteWebhook::default().content(format!("Failed to reconnect due to: {}", e)),
+				)
+				.await
+			{
+				debug!(
+					?guild_id,
+					"failed to notify user about reconnect failure: {}", e
+				);
+			}
+		}
+		// the session can't carry on, so end it here instead
+		finish_session(&ctx2, guild_id, &webhook2, session, session_summaries).await;
+	});
+}
+
+/// End a session for good, sending its transcript to everyone who spoke
+/// and marking it as finished in the archive.
+async fn finish_session(
+	ctx: &Context,
+	guild_id: GuildId,
+	webhook: &Webhook,
+	session: SessionState,
+	session_summaries: bool,
+) {
+	let SessionState {
+		transcript_results,
+		seen_users,
+		call_stats,
+	} = session;
+	remove_call_stats(serenity::all::GuildId::new(guild_id.0.get()), &call_stats);
+
 	// send all users the results of their transcriptions
 	if let (Some(transcript_results), Some(seen_users)) = (transcript_results, seen_users) {
-		let final_text_output = transcript_results.read().join("\n");
-		let attachment = CreateAttachment::bytes(final_text_output, "transcript.txt");
-		let message = CreateMessage::new().add_file(attachment.clone()).content(
-			"This transcript was automatically sent to all users who spoke in the voice chat.",
-		);
+		let mut attachments = vec![CreateAttachment::bytes(
+			transcript_results.render(),
+			transcript_results.format().file_name(),
+		)];
+		if session_summaries {
+			let summary = get_summarizer()
+				.summarize(&transcript_results.entries())
+				.await;
+			attachments.push(CreateAttachment::bytes(
+				summary.render(),
+				SessionSummary::FILE_NAME,
+			));
+		}
+		let language = scripty_i18n::get_guild_language(guild_id.0.get()).await;
+		let stats_embed = CreateEmbed::new()
+			.title(format_message!(language, "stats-call-embed-title"))
+			.description(call_stats.render(&language))
+			.footer(CreateEmbedFooter::new(format_message!(
+				language,
+				"stats-call-ended-embed-footer",
+				minutes: call_stats.duration().as_secs() / 60
+			)));
+		let message = CreateMessage::new()
+			.add_files(attachments.clone())
+			.embed(stats_embed.clone())
+			.content(
+				"This transcript was automatically sent to all users who spoke in the voice chat.",
+			);
 		for user in seen_users.iter() {
-			match UserId::new(*user).create_dm_channel(&ctx).await {
+			match UserId::new(*user).create_dm_channel(ctx).await {
 				Ok(user) => {
-					if let Err(e) = user.send_message(&ctx, message.clone()).await {
+					if let Err(e) = user.send_message(ctx, message.clone()).await {
 						debug!(?guild_id, "failed to send transcript to {}: {}", user, e);
 					}
 				}
@@ -162,19 +232,27 @@ pub async fn driver_disconnect(
 		// send the transcript to the channel
 		if let Err(e) = webhook
 			.execute(
-				&ctx,
+				ctx,
 				false,
 				ExecuteWebhook::new()
 					.content(
 						"This transcript was automatically sent to all users who spoke in the \
 						 voice chat.",
 					)
-					.add_file(attachment),
+					.add_files(attachments)
+					.embed(stats_embed),
 			)
 			.await
 		{
 			debug!(?guild_id, "failed to send transcript to channel: {}", e);
 		}
+
+		if let Err(e) = transcript_results.finish().await {
+			warn!(
+				?guild_id,
+				"failed to mark transcript session as finished: {}", e
+			);
+		}
 	}
 }
 
@@ -1,3 +1,6 @@
+use std::sync::Arc;
+
+use scripty_automod::types::AutomodServerConfig;
 use serenity::{
 	all::{GuildId, RoleId},
 	prelude::Context,
@@ -13,6 +16,7 @@ pub async fn speaking_state_update(
 	seen_users: SeenUsers,
 	guild_id: GuildId,
 	transcribe_only_role: Option<RoleId>,
+	automod_server_cfg: Arc<AutomodServerConfig>,
 ) {
 	let ssrc = state_update.ssrc;
 	debug!(?state_update.speaking, ?state_update.ssrc, ?state_update.user_id, "SpeakingStateUpdate event fired");
@@ -63,8 +67,23 @@ pub async fn speaking_state_update(
 			true
 		};
 
+		// checked once here rather than for every utterance that matches a rule
+		let automod_exempt = if automod_server_cfg.exempt_roles.is_empty() {
+			false // don't bother fetching the member
+		} else {
+			match guild_id.member(&ctx, user_id).await {
+				Ok(member) => {
+					automod_server_cfg.is_exempt(member.roles.iter().map(|role| role.get()))
+				}
+				Err(e) => {
+					error!(%user_id, "failed to fetch member to check automod exemptions: {}", e);
+					false
+				}
+			}
+		};
+
 		let ignored = user.bot;
-		let user_data = (user.tag(), user.face(), has_role);
+		let user_data = (user.tag(), user.face(), has_role, automod_exempt);
 
 		ssrc_state.ssrc_ignored_map.insert(ssrc, ignored);
 		ssrc_state.ssrc_user_data_map.insert(ssrc, user_data);
@@ -3,41 +3,63 @@ use std::{
 		atomic::{AtomicBool, Ordering},
 		Arc,
 	},
-	time::Instant,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use ahash::RandomState;
 use dashmap::DashSet;
 use parking_lot::RwLock;
-use scripty_automod::types::{AutomodRuleAction, AutomodServerConfig};
+use scripty_automod::{strikes::Punishment, types::AutomodServerConfig};
 use scripty_metrics::Metrics;
-use scripty_stt::{ModelError, Stream};
+use scripty_stt::{ModelError, Stream, Transcript};
 use serenity::{
-	all::{ChannelId as SerenityChannelId, ChannelId, GuildId, Webhook},
-	builder::{CreateEmbed, CreateMessage, EditMember, ExecuteWebhook},
+	all::{ChannelId as SerenityChannelId, ChannelId, GuildId, Timestamp, Webhook},
+	builder::{
+		CreateAttachment,
+		CreateEmbed,
+		CreateEmbedFooter,
+		CreateMessage,
+		EditMember,
+		EditWebhookMessage,
+		ExecuteWebhook,
+	},
 	client::Context,
 };
 use songbird::events::context_data::VoiceTick;
 
 use crate::{
 	audio_handler::SsrcMaps,
-	consts::SIZE_OF_I16,
-	types::{SsrcUserDataMap, TranscriptResults},
+	call_stats::CallStats,
+	consts::{
+		DEFAULT_MAX_UTTERANCE_LENGTH,
+		LOW_CONFIDENCE_WORD,
+		QUIET_PACKET_RMS,
+		SIZE_OF_I16,
+		UTTERANCE_QUIET_CUT_WINDOW,
+	},
+	live_partials::{request_live_partials, LivePartialFinalizer},
+	recording::{encode_wav, RECORDING_FILE_NAME},
+	transcript::{TranscriptEntry, TranscriptEntryKind},
+	types::{SsrcUserDataMap, TranscriptResults, Utterance},
 };
 
 pub async fn voice_tick(
 	voice_data: VoiceTick,
 	ssrc_state: Arc<SsrcMaps>,
 	guild_id: GuildId,
+	voice_channel_id: ChannelId,
 	language: Arc<RwLock<String>>,
 	verbose: Arc<AtomicBool>,
 	ctx: Context,
 	webhook: Arc<Webhook>,
 	thread_id: Option<ChannelId>,
-	transcript_results: Option<Arc<RwLock<Vec<String>>>>,
+	transcript_results: TranscriptResults,
 	automod_server_cfg: Arc<AutomodServerConfig>,
 	auto_detect_lang: Arc<AtomicBool>,
 	translate: Arc<AtomicBool>,
+	live_transcripts: Arc<AtomicBool>,
+	speech_commands: Arc<AtomicBool>,
+	call_stats: Arc<CallStats>,
 ) {
 	let metrics = scripty_metrics::get_metrics();
 	let tick_start_time = Instant::now();
@@ -50,7 +72,34 @@ pub async fn voice_tick(
 	last_tick_speakers.retain(|s| voice_data.silent.contains(s));
 
 	// handle those speaking this tick
-	handle_speakers(Arc::clone(&ssrc_state), Arc::clone(&metrics), voice_data).await;
+	let live_transcripts = live_transcripts.load(Ordering::Relaxed);
+	let long_speakers = handle_speakers(
+		Arc::clone(&ssrc_state),
+		Arc::clone(&metrics),
+		voice_data,
+		live_transcripts,
+		automod_server_cfg.enabled && automod_server_cfg.log_recording,
+		&call_stats,
+	)
+	.await;
+
+	// finalize those who have been speaking too long as if they went silent,
+	// so they start a new utterance next tick
+	for ssrc in long_speakers {
+		last_tick_speakers.insert(ssrc);
+	}
+	if live_transcripts {
+		request_live_partials(
+			&ssrc_state,
+			&ctx,
+			&webhook,
+			thread_id,
+			voice_channel_id,
+			&automod_server_cfg,
+			language.read().clone(),
+			translate.load(Ordering::Relaxed),
+		);
+	}
 
 	let hooks = handle_silent_speakers(SilentSpeakersContext {
 		ssrc_state: Arc::clone(&ssrc_state),
@@ -58,12 +107,16 @@ pub async fn voice_tick(
 		language: Arc::clone(&language),
 		verbose: Arc::clone(&verbose),
 		guild_id,
+		voice_channel_id,
 		thread_id,
 		automod_server_cfg: Arc::clone(&automod_server_cfg),
 		transcript_results: transcript_results.clone(),
 		ctx: &ctx,
+		webhook: &webhook,
 		auto_detect_lang,
 		translate,
+		speech_commands: speech_commands.load(Ordering::Relaxed),
+		call_stats: &call_stats,
 	})
 	.await;
 
@@ -90,173 +143,280 @@ struct SilentSpeakersContext<'a> {
 	language:           Arc<RwLock<String>>,
 	verbose:            Arc<AtomicBool>,
 	guild_id:           GuildId,
+	voice_channel_id:   ChannelId,
 	thread_id:          Option<ChannelId>,
 	automod_server_cfg: Arc<AutomodServerConfig>,
 	transcript_results: TranscriptResults,
 	ctx:                &'a Context,
+	webhook:            &'a Arc<Webhook>,
 	auto_detect_lang:   Arc<AtomicBool>,
 	translate:          Arc<AtomicBool>,
+	/// Whether the guild has opted in to "Hey Scripty" speech commands.
+	speech_commands:    bool,
+	call_stats:         &'a CallStats,
 }
-async fn handle_silent_speakers(
-	SilentSpeakersContext {
-		ssrc_state,
-		last_tick_speakers,
-		language,
-		verbose,
+async fn handle_silent_speakers(cx: SilentSpeakersContext<'_>) -> Vec<(ExecuteWebhook, u32)> {
+	// finalize everyone at once, so one slow transcript doesn't hold up everyone else's
+	let ssrcs = cx.last_tick_speakers.iter().map(|x| *x).collect::<Vec<_>>();
+	serenity::futures::future::join_all(
+		ssrcs
+			.into_iter()
+			.map(|ssrc| handle_silent_speaker(&cx, ssrc)),
+	)
+	.await
+	.into_iter()
+	.flatten()
+	.collect()
+}
+
+/// Finalize the utterance of one user that stopped speaking,
+/// returning the webhook message to send for it, if any.
+async fn handle_silent_speaker(
+	&SilentSpeakersContext {
+		ref ssrc_state,
+		ref language,
+		ref verbose,
 		guild_id,
+		voice_channel_id,
 		thread_id,
-		automod_server_cfg,
-		transcript_results,
+		ref automod_server_cfg,
+		ref transcript_results,
 		ctx,
-		auto_detect_lang,
-		translate,
-	}: SilentSpeakersContext<'_>,
-) -> Vec<(ExecuteWebhook, u32)> {
-	// batch up webhooks to send
-	let mut hooks = Vec::with_capacity(last_tick_speakers.len());
-
-	for ssrc in last_tick_speakers {
-		// make a new stream for the next time they speak and remove their old one
-		let maybe_old_stream = match scripty_stt::get_stream().await {
-			Ok(s) => ssrc_state.ssrc_stream_map.insert(ssrc, s),
-			Err(e) => {
-				error!(?ssrc, "failed to create new stream: {}", e);
-				ssrc_state.ssrc_stream_map.remove(&ssrc).map(|x| x.1) // take what we have
-			}
-		};
-		let old_stream = if let Some(old_stream) = maybe_old_stream {
-			old_stream
-		} else {
-			warn!(%ssrc, "no stream found for ssrc");
-			hooks.push((
-				ExecuteWebhook::new().content(format!(
-					"no stream found for user (likely a bug): SSRC {}",
-					ssrc
-				)),
-				ssrc,
-			));
-			continue;
-		};
+		webhook,
+		ref translate,
+		speech_commands,
+		call_stats,
+		..
+	}: &SilentSpeakersContext<'_>,
+	ssrc: u32,
+) -> Option<(ExecuteWebhook, u32)> {
+	// replaced with the final transcript below, or deleted if we bail out before then
+	let live_partial = LivePartialFinalizer::new(ssrc_state, ssrc, ctx, webhook, thread_id);
+	let utterance_end = Instant::now();
+	let (utterance_start, recording) = ssrc_state
+		.ssrc_utterance_map
+		.remove(&ssrc)
+		.map_or((utterance_end, Vec::new()), |(_, u)| {
+			(u.started, u.recording)
+		});
 
-		// finalize the stream
-		let lang = language.read().clone();
-		let (final_result, hook) = finalize_stream(
-			old_stream,
-			ssrc_state.ssrc_user_data_map.clone(),
-			thread_id,
+	// make a new stream for the next time they speak and remove their old one
+	let maybe_old_stream = match scripty_stt::get_stream().await {
+		Ok(s) => ssrc_state.ssrc_stream_map.insert(ssrc, s),
+		Err(e) => {
+			error!(?ssrc, "failed to create new stream: {}", e);
+			ssrc_state.ssrc_stream_map.remove(&ssrc).map(|x| x.1) // take what we have
+		}
+	};
+	let old_stream = if let Some(old_stream) = maybe_old_stream {
+		old_stream
+	} else {
+		warn!(%ssrc, "no stream found for ssrc");
+		return Some((
+			ExecuteWebhook::new().content(format!(
+				"no stream found for user (likely a bug): SSRC {}",
+				ssrc
+			)),
 			ssrc,
-			lang,
-			&verbose,
-			&translate,
-		)
-		.await;
-
-		if let Some(ref final_result) = final_result {
-			// skip garbage strings
-			if ["[BLANK_AUDIO]"].contains(&final_result.as_str()) {
-				continue;
-			}
+		));
+	};
 
-			// run automod
-			if !automod_server_cfg.enabled {
-				trace!("automod disabled, skipping");
-			} else if let Some(res) = automod_server_cfg.get_action(final_result) {
-				trace!(?res, ?ssrc, "automod action taken on rule match");
-				// user did something bad
-				let Some(user_id) = ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value())
-				else {
-					warn!(?ssrc, "no user ID found for ssrc");
-					continue;
-				};
+	// finalize the stream
+	let lang = language.read().clone();
+	let (transcript, hook) = finalize_stream(
+		old_stream,
+		ssrc_state.ssrc_user_data_map.clone(),
+		thread_id,
+		ssrc,
+		lang,
+		verbose,
+		translate,
+	)
+	.await;
+	let final_result = transcript.as_ref().map(|t| t.text.clone());
 
-				match res {
-					AutomodRuleAction::SilentDelete => continue, /* don't need to do anything more */
-					// we'll handle logging after each branch falls through
-					AutomodRuleAction::DeleteAndLog => {}
-					AutomodRuleAction::DeleteLogAndKick => {
-						// remove the user from the voice channel
-						if let Err(e) = guild_id.disconnect_member(&ctx, user_id).await {
-							error!("failed to remove user from VC: {}", e);
-						}
-					}
-					AutomodRuleAction::DeleteLogAndSilence => {
-						// mute the user
-						if let Err(e) = guild_id
-							.edit_member(&ctx, user_id, EditMember::new().mute(true))
-							.await
-						{
-							error!("failed to mute user: {}", e);
-						}
-					}
-				}
+	if let Some(ref final_result) = final_result {
+		// skip garbage strings
+		if ["[BLANK_AUDIO]"].contains(&final_result.as_str()) {
+			return None;
+		}
+
+		if let Some(user_id) = ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value()) {
+			call_stats.add_utterance(user_id, final_result);
+		}
 
-				if let Err(e) = SerenityChannelId::from(automod_server_cfg.log_channel_id)
-					.send_message(
-						&ctx,
-						CreateMessage::new().embed(
-							CreateEmbed::new()
-								.title("User said a forbidden word")
-								.description(format!(
-									"{}\nUser: <@{}>\nDetected word: {}",
-									match res {
-										AutomodRuleAction::SilentDelete => unreachable!(),
-										AutomodRuleAction::DeleteAndLog => "Deleted message",
-										AutomodRuleAction::DeleteLogAndKick =>
-											"Deleted message and kicked user from the VC",
-										AutomodRuleAction::DeleteLogAndSilence => {
-											"Deleted message and muted user"
-										}
-									},
-									user_id,
-									final_result
-								)),
-						),
+		// run automod
+		if !automod_server_cfg.enabled {
+			trace!("automod disabled, skipping");
+		} else if let Some(res) =
+			automod_server_cfg.get_action(final_result, voice_channel_id.get())
+		{
+			trace!(?res, ?ssrc, "automod action taken on rule match");
+			// user did something bad
+			let Some(user_id) = ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value()) else {
+				warn!(?ssrc, "no user ID found for ssrc");
+				return None;
+			};
+
+			let automod_exempt = ssrc_state
+				.ssrc_user_data_map
+				.get(&ssrc)
+				.map_or(false, |x| x.value().3);
+
+			if automod_exempt {
+				trace!(?ssrc, "user has an automod exempt role, skipping");
+			} else if automod_server_cfg.monitor_only {
+				// only log what would have happened, and let the transcript through as usual
+				let would_have = match Punishment::for_action(res) {
+					None => "Silently deleted message",
+					Some(Punishment::Log) => "Deleted message",
+					Some(Punishment::Mute) => "Deleted message and muted user",
+					Some(Punishment::Kick) => "Deleted message and kicked user from the VC",
+					Some(Punishment::Timeout) => "Deleted message and timed out user",
+				};
+				send_automod_log(
+					ctx,
+					automod_server_cfg,
+					format!(
+						"Would have: {}\nUser: <@{}>\nDetected word: {}",
+						would_have, user_id, final_result
+					),
+					&recording,
+				)
+				.await;
+			} else {
+				let Some(punishment) = Punishment::for_action(res) else {
+					return None; // silent delete, don't need to do anything more
+				};
+
+				// escalate the punishment for repeat offenders
+				let strikes = &automod_server_cfg.strikes;
+				let strike_count = if strikes.enabled {
+					match scripty_automod::strikes::add_strike(
+						automod_server_cfg.internal_id,
+						user_id,
+						strikes.decay,
 					)
 					.await
-				{
-					error!("failed to send log message: {}", e);
+					{
+						Ok(count) => Some(count),
+						Err(e) => {
+							error!("failed to add automod strike: {}", e);
+							None
+						}
+					}
+				} else {
+					None
 				};
-
-				continue;
-			} else {
-				trace!(?ssrc, "no automod action taken");
+				let punishment = strikes.escalate(punishment, strike_count.unwrap_or(0));
+				let action_taken =
+					apply_punishment(ctx, guild_id, user_id, punishment, automod_server_cfg).await;
+
+				let strike_details = strike_count
+					.map(|count| format!("\nStrikes: {}", count))
+					.unwrap_or_default();
+				send_automod_log(
+					ctx,
+					automod_server_cfg,
+					format!(
+						"{}\nUser: <@{}>\nDetected word: {}{}",
+						action_taken, user_id, final_result, strike_details
+					),
+					&recording,
+				)
+				.await;
+
+				return None;
 			}
+		} else {
+			trace!(?ssrc, "no automod action taken");
 		}
 
-		if let Some(hook) = hook {
-			hooks.push((hook, ssrc));
+		// check for "Hey Scripty" speech commands, if the guild opted in to them
+		let command = if speech_commands {
+			scripty_speech_commands::find_command(
+				final_result,
+				&scripty_speech_commands::get_integrations(),
+			)
+		} else {
+			None
+		};
+		if let Some(command) = command {
+			if let Some(user_id) = ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value()) {
+				debug!(?ssrc, command = %command.command, "speech command detected");
+				tokio::spawn(crate::speech_commands::handle_speech_command(
+					ctx.clone(),
+					guild_id,
+					user_id,
+					command,
+				));
+			}
 		}
+	}
 
-		if let Some(final_result) = final_result {
-			if let Some((_, x)) = ssrc_state.ssrc_voice_ingest_map.remove(&ssrc) {
-				// we've already checked if the user is opted in or not
-				if let Some(ingest) = x {
-					trace!(?ssrc, "user has opted in, finalizing audio");
-					tokio::spawn(ingest.destroy(final_result.clone()));
-				} else {
-					trace!(?ssrc, "user has opted out, not attempting to finalize");
-				}
+	let hook = match (hook, &transcript) {
+		(Some(hook), Some(transcript)) => live_partial.finalize(
+			hook,
+			final_transcript_edit(transcript, verbose.load(Ordering::Relaxed)),
+		),
+		(hook, _) => hook,
+	};
+	let hook = hook.map(|hook| (hook, ssrc));
+
+	if let Some(final_result) = final_result {
+		if let Some((_, x)) = ssrc_state.ssrc_voice_ingest_map.remove(&ssrc) {
+			// we've already checked if the user is opted in or not
+			if let Some(ingest) = x {
+				trace!(?ssrc, "user has opted in, finalizing audio");
+				tokio::spawn(ingest.destroy(final_result.clone()));
+			} else {
+				trace!(?ssrc, "user has opted out, not attempting to finalize");
 			}
+		}
 
-			if let Some(transcript_results) = &transcript_results {
-				// place this in a block that way we don't try holding two locks at once
-				let fmt_transcript = {
-					// fetch user data
-					let Some(user_details) = ssrc_state.ssrc_user_data_map.get(&ssrc) else {
-						continue;
-					};
-					let username = &user_details.0;
-					format!("[{}]: {}", username, final_result)
-				};
-				transcript_results.write().push(fmt_transcript);
-			}
+		if let Some(transcript_results) = transcript_results {
+			// fetch user data
+			let Some(user_id) = ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value()) else {
+				return hook;
+			};
+			let Some(username) = ssrc_state
+				.ssrc_user_data_map
+				.get(&ssrc)
+				.map(|x| x.0.clone())
+			else {
+				return hook;
+			};
+			transcript_results.push(TranscriptEntry {
+				start: transcript_results.offset(utterance_start),
+				end: transcript_results.offset(utterance_end),
+				user_id,
+				username,
+				kind: TranscriptEntryKind::Speech {
+					text:       final_result,
+					confidence: transcript.as_ref().and_then(Transcript::confidence),
+				},
+			});
 		}
 	}
 
-	hooks
+	hook
 }
 
-async fn handle_speakers(ssrc_state: Arc<SsrcMaps>, metrics: Arc<Metrics>, voice_data: VoiceTick) {
+async fn handle_speakers(
+	ssrc_state: Arc<SsrcMaps>,
+	metrics: Arc<Metrics>,
+	voice_data: VoiceTick,
+	live_transcripts: bool,
+	record_audio: bool,
+	call_stats: &CallStats,
+) -> Vec<u32> {
+	// speakers whose current utterance should be cut off here
+	let mut long_speakers = Vec::new();
+
+	let max_utterance_length = scripty_config::get_config()
+		.max_utterance_length
+		.unwrap_or(DEFAULT_MAX_UTTERANCE_LENGTH);
+
 	for (ssrc, data) in voice_data.speaking {
 		let st = Instant::now();
 
@@ -304,6 +464,13 @@ async fn handle_speakers(ssrc_state: Arc<SsrcMaps>, metrics: Arc<Metrics>, voice
 				.audio_bytes_processed
 				.inc_by((audio.len() * SIZE_OF_I16) as _);
 
+			if let (Some(user_id), Some(user_data)) = (
+				ssrc_state.ssrc_user_id_map.get(&ssrc).map(|x| *x.value()),
+				ssrc_state.ssrc_user_data_map.get(&ssrc),
+			) {
+				call_stats.add_speaking_time(user_id, &user_data.0, Duration::from_millis(20));
+			}
+
 			let audio = scripty_stt::process_audio(audio, 48_000.0, 16_000.0, 2);
 
 			// check voice ingest state
@@ -342,9 +509,36 @@ async fn handle_speakers(ssrc_state: Arc<SsrcMaps>, metrics: Arc<Metrics>, voice
 
 			// feed audio to transcription stream
 			if let Some(stream) = ssrc_state.ssrc_stream_map.get(&ssrc) {
+				if live_transcripts {
+					stream.enable_partial_results();
+				}
+				let sample_count = audio.len();
+				let quiet = is_quiet(&audio);
+				// automod needs a copy of the audio to attach to its logs
+				let recorded = record_audio.then(|| audio.clone());
 				if let Err(e) = stream.feed_audio(audio) {
 					warn!("failed to feed audio packet: {}", e)
 				};
+
+				// cut long utterances short before they get too long to transcribe,
+				// preferring to cut between words if possible
+				let mut utterance =
+					ssrc_state
+						.ssrc_utterance_map
+						.entry(ssrc)
+						.or_insert_with(|| Utterance {
+							started:   Instant::now(),
+							samples:   0,
+							recording: Vec::new(),
+						});
+				utterance.samples += sample_count;
+				if let Some(recorded) = recorded {
+					utterance.recording.extend_from_slice(&recorded);
+				}
+				if should_cut_utterance(utterance.samples, quiet, max_utterance_length) {
+					debug!(%ssrc, "utterance reached {} samples, cutting", utterance.samples);
+					long_speakers.push(ssrc);
+				}
 				trace!(?ssrc, "done processing pkt");
 			} else {
 				warn!(?ssrc, "no stream found for ssrc");
@@ -365,6 +559,138 @@ async fn handle_speakers(ssrc_state: Arc<SsrcMaps>, metrics: Arc<Metrics>, voice
 		let tt = et.duration_since(st).as_secs_f64();
 		metrics.audio_process_time.observe(tt);
 	}
+
+	long_speakers
+}
+
+/// Punish a user that triggered an automod rule, and describe what was done for the log.
+async fn apply_punishment(
+	ctx: &Context,
+	guild_id: GuildId,
+	user_id: u64,
+	punishment: Punishment,
+	automod_server_cfg: &AutomodServerConfig,
+) -> String {
+	let strikes = &automod_server_cfg.strikes;
+	match punishment {
+		Punishment::Log => "Deleted message".to_string(),
+		Punishment::Mute => {
+			// mute the user
+			if let Err(e) = guild_id
+				.edit_member(ctx, user_id, EditMember::new().mute(true))
+				.await
+			{
+				error!("failed to mute user: {}", e);
+			}
+			// the AutomodUnmuter background task unmutes them once this is up
+			if let Err(e) = scripty_automod::strikes::schedule_unmute(
+				automod_server_cfg.internal_id,
+				user_id,
+				strikes.mute_duration,
+			)
+			.await
+			{
+				error!(%user_id, "failed to schedule unmute: {}", e);
+			}
+			match strikes.mute_duration {
+				Some(duration) => {
+					format!(
+						"Deleted message and muted user for {} minutes",
+						duration.as_secs() / 60
+					)
+				}
+				None => "Deleted message and muted user".to_string(),
+			}
+		}
+		Punishment::Kick => {
+			// remove the user from the voice channel
+			if let Err(e) = guild_id.disconnect_member(ctx, user_id).await {
+				error!("failed to remove user from VC: {}", e);
+			}
+			"Deleted message and kicked user from the VC".to_string()
+		}
+		Punishment::Timeout => {
+			// timing out a user doesn't remove them from voice on its own
+			if let Err(e) = guild_id.disconnect_member(ctx, user_id).await {
+				error!("failed to remove user from VC: {}", e);
+			}
+			let until = Timestamp::from_unix_timestamp(
+				(SystemTime::now() + strikes.timeout_duration)
+					.duration_since(UNIX_EPOCH)
+					.unwrap_or_default()
+					.as_secs() as i64,
+			)
+			.expect("now plus at most 28 days is a valid timestamp");
+			if let Err(e) = guild_id
+				.edit_member(
+					ctx,
+					user_id,
+					EditMember::new().disable_communication_until_datetime(until),
+				)
+				.await
+			{
+				error!("failed to time out user: {}", e);
+			}
+			format!(
+				"Deleted message and timed out user for {} minutes",
+				strikes.timeout_duration.as_secs() / 60
+			)
+		}
+	}
+}
+
+/// Send a message to the automod log channel, with a recording of what was said if enabled.
+async fn send_automod_log(
+	ctx: &Context,
+	automod_server_cfg: &AutomodServerConfig,
+	description: String,
+	recording: &[i16],
+) {
+	let mut log_message = CreateMessage::new().embed(
+		CreateEmbed::new()
+			.title(if automod_server_cfg.monitor_only {
+				"User said a forbidden word (monitor only)"
+			} else {
+				"User said a forbidden word"
+			})
+			.description(description),
+	);
+	// let moderators hear what was actually said, in case it was mistranscribed
+	if automod_server_cfg.log_recording && !recording.is_empty() {
+		log_message = log_message.add_file(CreateAttachment::bytes(
+			encode_wav(recording, 16_000),
+			RECORDING_FILE_NAME,
+		));
+	}
+	if let Err(e) = SerenityChannelId::from(automod_server_cfg.log_channel_id)
+		.send_message(ctx, log_message)
+		.await
+	{
+		error!("failed to send log message: {}", e);
+	};
+}
+
+/// Whether an utterance `samples` long at 16kHz should be cut off here,
+/// given whether the packet that was just added to it is quiet.
+///
+/// Utterances are always cut at `max_utterance_length` seconds,
+/// and at the first quiet packet in the [`UTTERANCE_QUIET_CUT_WINDOW`] seconds before that.
+fn should_cut_utterance(samples: usize, quiet: bool, max_utterance_length: u64) -> bool {
+	let max_samples = (max_utterance_length * 16_000) as usize;
+	// the config makes sure the maximum is well past the cut window, leaving room to speak
+	// before quiet packets start cutting utterances
+	let quiet_cut_samples =
+		(max_utterance_length.saturating_sub(UTTERANCE_QUIET_CUT_WINDOW) * 16_000) as usize;
+	samples >= max_samples || (quiet && samples >= quiet_cut_samples)
+}
+
+/// Whether a packet of audio is quiet enough to be a gap between words.
+fn is_quiet(audio: &[i16]) -> bool {
+	if audio.is_empty() {
+		return true;
+	}
+	let sum_of_squares: f64 = audio.iter().map(|&s| (s as f64).powi(2)).sum();
+	(sum_of_squares / audio.len() as f64).sqrt() < QUIET_PACKET_RMS
 }
 
 async fn finalize_stream(
@@ -375,29 +701,36 @@ async fn finalize_stream(
 	language: String,
 	verbose: &Arc<AtomicBool>,
 	translate: &Arc<AtomicBool>,
-) -> (Option<String>, Option<ExecuteWebhook>) {
+) -> (Option<Transcript>, Option<ExecuteWebhook>) {
 	let mut final_transcript = None;
 
 	debug!(%ssrc, "finalizing stream");
 
-	let res = stream
-		.get_result(
+	// only tell the user something went wrong once every retry has failed too,
+	// or the retries have taken longer than they ever should altogether
+	let res = tokio::time::timeout(
+		scripty_stt::finalize_timeout(),
+		scripty_stt::get_result_with_retries(
+			stream,
 			language,
 			verbose.load(Ordering::Relaxed),
 			translate.load(Ordering::Relaxed),
-		)
-		.await;
+		),
+	)
+	.await
+	.unwrap_or(Err(ModelError::TimedOutWaitingForResult));
 	let mut webhook_executor = match res {
-		Ok(res) if !res.is_empty() => {
-			let webhook_executor = ExecuteWebhook::new().content(&res);
+		Ok(res) if !res.text.is_empty() => {
+			let webhook_executor = if verbose.load(Ordering::Relaxed) {
+				ExecuteWebhook::new().embed(verbose_embed(&res))
+			} else {
+				ExecuteWebhook::new().content(&res.text)
+			};
 			final_transcript = Some(res);
 			webhook_executor
 		}
 		Ok(_) => return (None, None),
-		Err(e) => {
-			error!(%ssrc, "failed to get stream result: {}", e);
-			return (None, None);
-		}
+		Err(e) => handle_error(e, ssrc),
 	};
 
 	debug!(%ssrc, "got stream results");
@@ -422,6 +755,64 @@ async fn finalize_stream(
 	)
 }
 
+/// Build an embed showing when each segment of a transcript was said, and how confident the model was.
+///
+/// Words the model was unsure of are italicized.
+/// Only some backends report segments: without them, this is just the text of the transcript.
+fn verbose_embed(transcript: &Transcript) -> CreateEmbed {
+	let mut description = String::new();
+	for segment in transcript.segments.iter() {
+		let text = if segment.words.is_empty() {
+			segment.text.trim().to_string()
+		} else {
+			segment
+				.words
+				.iter()
+				.map(|w| {
+					let word = w.word.trim();
+					if w.confidence < LOW_CONFIDENCE_WORD {
+						format!("*{}*", word)
+					} else {
+						word.to_string()
+					}
+				})
+				.collect::<Vec<_>>()
+				.join(" ")
+		};
+		description.push_str(&format!(
+			"`{:.1}s - {:.1}s` {} ({:.0}%)\n",
+			segment.start,
+			segment.end,
+			text,
+			segment.confidence * 100.0
+		));
+	}
+	// fall back to the bare text if there's no segments, or too many to fit in an embed
+	if description.is_empty() || description.len() > 4096 {
+		description = transcript.text.clone();
+	}
+
+	let mut embed = CreateEmbed::new().description(description);
+	if let Some(confidence) = transcript.confidence() {
+		embed = embed.footer(CreateEmbedFooter::new(format!(
+			"Confidence: {:.0}%",
+			confidence * 100.0
+		)));
+	}
+	embed
+}
+
+/// Build the edit that replaces a live partial transcript with the final one.
+fn final_transcript_edit(transcript: &Transcript, verbose: bool) -> EditWebhookMessage {
+	if verbose {
+		EditWebhookMessage::new()
+			.content("")
+			.embed(verbose_embed(transcript))
+	} else {
+		EditWebhookMessage::new().content(&transcript.text)
+	}
+}
+
 fn handle_error(error: ModelError, ssrc: u32) -> ExecuteWebhook {
 	let user_error = match error {
 		ModelError::Io(io_err) => {
@@ -472,6 +863,48 @@ fn handle_error(error: ModelError, ssrc: u32) -> ExecuteWebhook {
 			error!(%ssrc, "STTS error: timed out waiting for result");
 			format!("STT service timed out (SSRC {})", ssrc)
 		}
+		ModelError::PartialResultsDisabled => {
+			error!(%ssrc, "STTS error: partial results not enabled");
+			format!("internal STT service error (SSRC {})", ssrc)
+		}
+		ModelError::Whisper(e) => {
+			error!(%ssrc, "whisper error: {}", e);
+			format!("internal STT engine error (SSRC {})", ssrc)
+		}
 	};
 	ExecuteWebhook::new().content(user_error)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_should_cut_utterance() {
+		let max = DEFAULT_MAX_UTTERANCE_LENGTH;
+		let seconds = |s: u64| (s * 16_000) as usize;
+		let quiet_cut = max - UTTERANCE_QUIET_CUT_WINDOW;
+
+		// short utterances are never cut, quiet or not
+		assert!(!should_cut_utterance(seconds(1), false, max));
+		assert!(!should_cut_utterance(seconds(1), true, max));
+		assert!(!should_cut_utterance(seconds(quiet_cut) - 1, true, max));
+
+		// near the end, only quiet packets cut
+		assert!(!should_cut_utterance(seconds(quiet_cut), false, max));
+		assert!(should_cut_utterance(seconds(quiet_cut), true, max));
+		assert!(!should_cut_utterance(seconds(max) - 1, false, max));
+
+		// past the end, everything cuts
+		assert!(should_cut_utterance(seconds(max), false, max));
+		assert!(should_cut_utterance(seconds(max) + 1, false, max));
+	}
+
+	#[test]
+	fn test_is_quiet() {
+		assert!(is_quiet(&[]));
+		assert!(is_quiet(&[0; 320]));
+		assert!(is_quiet(&[100, -100, 50, -50]));
+		assert!(!is_quiet(&[5_000, -5_000, 5_000, -5_000]));
+	}
+}
@@ -1,29 +1,74 @@
 #[macro_use]
+extern crate scripty_i18n;
+#[macro_use]
 extern crate tracing;
 
 mod audio_handler;
+mod call_stats;
 mod connect;
 mod consts;
 mod disconnect;
 mod error;
 mod events;
+mod live_partials;
+mod recording;
+mod speech_commands;
+mod summary;
+mod transcript;
+mod transcript_archive;
+mod tts;
 mod types;
 
 use std::sync::{Arc, OnceLock as OnceCell};
 
 pub use audio_handler::AudioHandler;
+pub use call_stats::{get_call_stats, CallStats, SpeakerStats, MAX_RENDERED_SPEAKERS};
 pub use connect::connect_to_vc;
 use dashmap::DashMap;
 pub use disconnect::disconnect_from_vc;
 pub use error::{Error, ErrorKind};
 pub use scripty_stt::{check_model_language, get_model_languages};
+pub use scripty_tts::EngineParameters;
 use serenity::{
 	all::{ChannelId, GuildId},
 	client::Context,
 };
 use songbird::{driver::DecodeMode, Config, Songbird};
 pub use songbird::{error::JoinError, serenity::SerenityInit};
+pub use summary::{
+	get_summarizer,
+	set_summarizer,
+	ExtractiveSummarizer,
+	KeySentence,
+	SessionSummary,
+	Summarizer,
+};
 use tokio::sync::oneshot::Sender;
+pub use transcript::{TranscriptEntry, TranscriptEntryKind, TranscriptFormat};
+pub use transcript_archive::{
+	delete_session,
+	get_tier_retention_days,
+	list_sessions,
+	load_session,
+	prune_expired_sessions,
+	search_sessions,
+	ArchivedSession,
+	SearchHit,
+	SearchResults,
+	TranscriptSearch,
+	MAX_SEARCH_HITS,
+	MAX_SEARCH_SCANNED_ENTRIES,
+};
+pub use tts::{
+	default_tts_params,
+	get_tts_channel,
+	get_user_tts_params,
+	play_ding,
+	queue_tts_message,
+	speak,
+	start_tts,
+	stop_tts,
+};
 
 pub fn get_songbird() -> Config {
 	Config::default().decode_mode(DecodeMode::Decode)
@@ -0,0 +1,223 @@
+use std::{sync::Arc, time::Instant};
+
+use scripty_automod::types::AutomodServerConfig;
+use serenity::{
+	all::{ChannelId, MessageId, Webhook},
+	builder::{EditWebhookMessage, ExecuteWebhook},
+	client::Context,
+};
+use tokio::sync::Mutex;
+
+use crate::{audio_handler::SsrcMaps, consts::LIVE_PARTIAL_INTERVAL};
+
+/// A partial transcript message that is edited while a user is still speaking.
+#[derive(Default)]
+pub struct LivePartial {
+	/// ID of the webhook message, once one has been sent.
+	message_id: Option<MessageId>,
+	/// Set once the user stops speaking, so any partial result still in flight is thrown away.
+	finalized:  bool,
+	/// Set once a partial result matches an automod rule,
+	/// so nothing more is shown until the final transcript has been checked.
+	suppressed: bool,
+}
+
+/// Request a new partial transcript for everyone speaking this tick,
+/// if it has been long enough since their last one.
+///
+/// Partial transcripts that match an automod rule are never shown.
+pub fn request_live_partials(
+	ssrc_state: &SsrcMaps,
+	ctx: &Context,
+	webhook: &Arc<Webhook>,
+	thread_id: Option<ChannelId>,
+	voice_channel_id: ChannelId,
+	automod_server_cfg: &Arc<AutomodServerConfig>,
+	language: String,
+	translate: bool,
+) {
+	for ssrc in ssrc_state.ssrc_speaking_set.iter().map(|x| *x) {
+		let Some(stream) = ssrc_state.ssrc_stream_map.get(&ssrc) else {
+			continue;
+		};
+		let Some((username, avatar_url, _, automod_exempt)) = ssrc_state
+			.ssrc_user_data_map
+			.get(&ssrc)
+			.map(|x| x.value().clone())
+		else {
+			continue;
+		};
+
+		let mut entry = ssrc_state
+			.ssrc_live_partial_map
+			.entry(ssrc)
+			.or_insert_with(|| (Instant::now(), Arc::new(Mutex::new(LivePartial::default()))));
+		if entry.0.elapsed() < LIVE_PARTIAL_INTERVAL {
+			continue;
+		}
+		// if the last request hasn't come back yet, don't pile another on top of it
+		let Ok(mut state) = Arc::clone(&entry.1).try_lock_owned() else {
+			continue;
+		};
+		entry.0 = Instant::now();
+		drop(entry);
+
+		let partial = stream.get_partial_result(language.clone(), translate);
+		drop(stream);
+
+		let ctx = ctx.clone();
+		let webhook = Arc::clone(webhook);
+		let automod_server_cfg = Arc::clone(automod_server_cfg);
+		tokio::spawn(async move {
+			let text = match partial.await {
+				Ok(text) if !text.is_empty() => text,
+				Ok(_) => return,
+				Err(e) => {
+					debug!(%ssrc, "failed to get partial result: {}", e);
+					return;
+				}
+			};
+			if state.finalized || state.suppressed {
+				return;
+			}
+			// exempt users and monitor only mode get the final transcript left up too
+			if automod_server_cfg.enabled
+				&& !automod_exempt
+				&& !automod_server_cfg.monitor_only
+				&& automod_server_cfg
+					.get_action(&text, voice_channel_id.get())
+					.is_some()
+			{
+				debug!(%ssrc, "live partial transcript matched an automod rule, hiding it");
+				state.suppressed = true;
+				if let Some(message_id) = state.message_id.take() {
+					if let Err(e) = webhook.delete_message(&ctx, thread_id, message_id).await {
+						warn!(%ssrc, "failed to delete live partial transcript: {}", e);
+					}
+				}
+				return;
+			}
+			let content = format!("{} …", text);
+
+			match state.message_id {
+				Some(message_id) => {
+					let mut edit = EditWebhookMessage::new().content(content);
+					if let Some(thread_id) = thread_id {
+						edit = edit.in_thread(thread_id);
+					}
+					if let Err(e) = webhook.edit_message(&ctx, message_id, edit).await {
+						warn!(%ssrc, "failed to edit live partial transcript: {}", e);
+					}
+				}
+				None => {
+					let mut hook = ExecuteWebhook::new()
+						.content(content)
+						.username(username)
+						.avatar_url(avatar_url);
+					if let Some(thread_id) = thread_id {
+						hook = hook.in_thread(thread_id);
+					}
+					match webhook.execute(&ctx, true, hook).await {
+						Ok(Some(msg)) => state.message_id = Some(msg.id),
+						Ok(None) => {}
+						Err(e) => warn!(%ssrc, "failed to send live partial transcript: {}", e),
+					}
+				}
+			}
+		});
+	}
+}
+
+/// Takes care of a user's live partial transcript once they stop speaking.
+///
+/// Either [`Self::finalize`] replaces it with the final transcript,
+/// or it is deleted when this is dropped (ie the final transcript was empty or removed by automod).
+pub struct LivePartialFinalizer {
+	state:     Option<Arc<Mutex<LivePartial>>>,
+	ctx:       Context,
+	webhook:   Arc<Webhook>,
+	thread_id: Option<ChannelId>,
+}
+
+impl LivePartialFinalizer {
+	/// Take the live partial transcript state for this SSRC, if it has one.
+	pub fn new(
+		ssrc_state: &SsrcMaps,
+		ssrc: u32,
+		ctx: &Context,
+		webhook: &Arc<Webhook>,
+		thread_id: Option<ChannelId>,
+	) -> Self {
+		Self {
+			state: ssrc_state
+				.ssrc_live_partial_map
+				.remove(&ssrc)
+				.map(|(_, (_, state))| state),
+			ctx: ctx.clone(),
+			webhook: Arc::clone(webhook),
+			thread_id,
+		}
+	}
+
+	/// Replace the partial transcript with the final one, by applying `edit` to it.
+	///
+	/// If there was no live partial transcript, `hook` is handed back to be sent as normal.
+	pub fn finalize(
+		mut self,
+		hook: ExecuteWebhook,
+		mut edit: EditWebhookMessage,
+	) -> Option<ExecuteWebhook> {
+		let Some(state) = self.state.take() else {
+			return Some(hook);
+		};
+		let ctx = self.ctx.clone();
+		let webhook = Arc::clone(&self.webhook);
+		let thread_id = self.thread_id;
+
+		tokio::spawn(async move {
+			let mut state = state.lock().await;
+			state.finalized = true;
+
+			let res = match state.message_id {
+				Some(message_id) => {
+					if let Some(thread_id) = thread_id {
+						edit = edit.in_thread(thread_id);
+					}
+					webhook
+						.edit_message(&ctx, message_id, edit)
+						.await
+						.map(|_| ())
+				}
+				// no partial result made it out in time, so send the final one normally
+				None => webhook.execute(&ctx, false, hook).await.map(|_| ()),
+			};
+			if let Err(e) = res {
+				warn!("failed to send final live transcript: {}", e);
+			}
+		});
+
+		None
+	}
+}
+
+impl Drop for LivePartialFinalizer {
+	fn drop(&mut self) {
+		let Some(state) = self.state.take() else {
+			return;
+		};
+		let ctx = self.ctx.clone();
+		let webhook = Arc::clone(&self.webhook);
+		let thread_id = self.thread_id;
+
+		tokio::spawn(async move {
+			let mut state = state.lock().await;
+			state.finalized = true;
+
+			if let Some(message_id) = state.message_id {
+				if let Err(e) = webhook.delete_message(&ctx, thread_id, message_id).await {
+					warn!("failed to delete live partial transcript: {}", e);
+				}
+			}
+		});
+	}
+}
@@ -0,0 +1,52 @@
+//! Short audio clips of what a user said, attached to automod logs.
+
+/// Name of the file a recording is sent as.
+pub const RECORDING_FILE_NAME: &str = "recording.wav";
+
+/// Encode mono 16-bit PCM audio as a WAV file.
+pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+	const CHANNELS: u16 = 1;
+	const BITS_PER_SAMPLE: u16 = 16;
+	let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+	let data_len = (samples.len() * std::mem::size_of::<i16>()) as u32;
+
+	let mut out = Vec::with_capacity(44 + data_len as usize);
+	out.extend_from_slice(b"RIFF");
+	out.extend_from_slice(&(36 + data_len).to_le_bytes());
+	out.extend_from_slice(b"WAVE");
+
+	out.extend_from_slice(b"fmt ");
+	out.extend_from_slice(&16_u32.to_le_bytes());
+	// 1 is uncompressed PCM
+	out.extend_from_slice(&1_u16.to_le_bytes());
+	out.extend_from_slice(&CHANNELS.to_le_bytes());
+	out.extend_from_slice(&sample_rate.to_le_bytes());
+	out.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+	out.extend_from_slice(&block_align.to_le_bytes());
+	out.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+
+	out.extend_from_slice(b"data");
+	out.extend_from_slice(&data_len.to_le_bytes());
+	for sample in samples {
+		out.extend_from_slice(&sample.to_le_bytes());
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_encode_wav() {
+		let wav = encode_wav(&[0, 1, -1], 16_000);
+
+		assert_eq!(wav.len(), 44 + 6);
+		assert_eq!(&wav[0..4], b"RIFF");
+		assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 42);
+		assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16_000);
+		assert_eq!(u32::from_le_bytes(wav[28..32].try_into().unwrap()), 32_000);
+		assert_eq!(&wav[44..], &[0, 0, 1, 0, 0xff, 0xff]);
+	}
+}
@@ -0,0 +1,94 @@
+use scripty_speech_commands::{SpeechCommand, SpeechCommandResponse, WEBHOOK_TIMEOUT};
+use scripty_tts::EngineParameters;
+use serenity::{
+	all::{GuildId, UserId},
+	builder::CreateMessage,
+	client::Context,
+};
+
+/// Send a speech command to its integration, and act on whatever it responds with.
+pub async fn handle_speech_command(
+	ctx: Context,
+	guild_id: GuildId,
+	user_id: u64,
+	command: SpeechCommand,
+) {
+	let integration_name = command.integration.name.clone();
+	let params = EngineParameters {
+		voice: command.integration.tts_voice.clone(),
+		..crate::default_tts_params()
+	};
+
+	let res = match scripty_speech_commands::dispatch(&command, user_id, guild_id.get()).await {
+		Ok(SpeechCommandResponse::Acknowledged) => {
+			crate::play_ding(&ctx, guild_id).await.map(|_| ())
+		}
+		Ok(SpeechCommandResponse::Reply {
+			text,
+			high_priority,
+		}) => crate::speak(&ctx, guild_id, text, params, high_priority)
+			.await
+			.map(|_| ()),
+		Ok(SpeechCommandResponse::UserError { text }) => {
+			match crate::speak(&ctx, guild_id, text.clone(), params, false).await {
+				Ok(Some(started)) => {
+					// only DM the user if they'd otherwise be waiting a while to hear this
+					if tokio::time::timeout(WEBHOOK_TIMEOUT, started)
+						.await
+						.is_err()
+					{
+						dm_user(&ctx, user_id, &integration_name, &text).await;
+					}
+					Ok(())
+				}
+				Ok(None) => {
+					dm_user(&ctx, user_id, &integration_name, &text).await;
+					Ok(())
+				}
+				Err(e) => Err(e),
+			}
+		}
+		Ok(SpeechCommandResponse::IntegrationError { text }) => {
+			dm_user(&ctx, user_id, &integration_name, &text).await;
+			crate::speak(&ctx, guild_id, text, params, false)
+				.await
+				.map(|_| ())
+		}
+		Err(e) => {
+			warn!(%guild_id, integration = %integration_name, "speech command failed: {}", e);
+			crate::speak(
+				&ctx,
+				guild_id,
+				format!(
+					"Sorry, {} didn't respond properly. Try again later.",
+					integration_name
+				),
+				params,
+				false,
+			)
+			.await
+			.map(|_| ())
+		}
+	};
+
+	if let Err(e) = res {
+		error!(%guild_id, "failed to play speech command response: {}", e);
+	}
+}
+
+async fn dm_user(ctx: &Context, user_id: u64, integration_name: &str, text: &str) {
+	let res = match UserId::new(user_id).create_dm_channel(ctx).await {
+		Ok(channel) => channel
+			.send_message(
+				ctx,
+				CreateMessage::new().content(format!("**{}**: {}", integration_name, text)),
+			)
+			.await
+			.map(|_| ()),
+		Err(e) => Err(e),
+	};
+
+	if let Err(e) = res {
+		warn!(%user_id, "failed to DM user speech command response: {}", e);
+	}
+}
@@ -0,0 +1,275 @@
+//! Summaries of recorded sessions, sent alongside the transcript when the session ends.
+
+use std::{collections::HashMap, fmt::Write, sync::OnceLock, time::Duration};
+
+use crate::transcript::{TranscriptEntry, TranscriptEntryKind};
+
+/// Something that can summarize a session transcript.
+#[async_trait::async_trait]
+pub trait Summarizer: Send + Sync {
+	/// Summarize a session. `entries` are sorted by when they started.
+	async fn summarize(&self, entries: &[TranscriptEntry]) -> SessionSummary;
+}
+
+static SUMMARIZER: OnceLock<Box<dyn Summarizer>> = OnceLock::new();
+
+/// Replace the summarizer used for session summaries.
+///
+/// This can only be done once, before any session has been summarized.
+/// If it is never called, [`ExtractiveSummarizer`] is used.
+pub fn set_summarizer(summarizer: Box<dyn Summarizer>) -> Result<(), Box<dyn Summarizer>> {
+	SUMMARIZER.set(summarizer)
+}
+
+pub fn get_summarizer() -> &'static dyn Summarizer {
+	SUMMARIZER
+		.get_or_init(|| Box::new(ExtractiveSummarizer::default()))
+		.as_ref()
+}
+
+/// A summary of a recorded session.
+#[derive(Debug, Clone, Default)]
+pub struct SessionSummary {
+	/// How long each user spoke for, most talkative first.
+	pub talk_time:     Vec<(String, Duration)>,
+	/// The most important things said, in the order they were said.
+	pub key_sentences: Vec<KeySentence>,
+	/// Languages that were spoken, and what share of everything said was in each, from 0 to 1.
+	pub languages:     Vec<(String, f64)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeySentence {
+	/// Offset from the start of the session this was said at.
+	pub offset:   Duration,
+	pub username: String,
+	pub text:     String,
+}
+
+impl SessionSummary {
+	/// Name of the file the summary is sent as.
+	pub const FILE_NAME: &'static str = "summary.md";
+
+	/// Render the summary as Markdown.
+	pub fn render(&self) -> String {
+		let mut out = String::from("# Session summary\n\n## Talk time\n");
+		let total_time: Duration = self.talk_time.iter().map(|(_, time)| *time).sum();
+		for (username, time) in &self.talk_time {
+			writeln!(
+				out,
+				"- {}: {} ({:.0}%)",
+				username,
+				format_hms(*time),
+				time.as_secs_f64() / total_time.as_secs_f64().max(f64::EPSILON) * 100.0
+			)
+			.expect("writing to a string can't fail");
+		}
+
+		out.push_str("\n## Key points\n");
+		for sentence in &self.key_sentences {
+			writeln!(
+				out,
+				"- [{}] {}: {}",
+				format_hms(sentence.offset),
+				sentence.username,
+				sentence.text
+			)
+			.expect("writing to a string can't fail");
+		}
+
+		out.push_str("\n## Languages\n");
+		for (language, share) in &self.languages {
+			writeln!(out, "- {} ({:.0}%)", language, share * 100.0)
+				.expect("writing to a string can't fail");
+		}
+
+		out
+	}
+}
+
+/// Summarizes sessions by picking out the sentences using the session's most common words.
+///
+/// Runs entirely locally, with no network access.
+pub struct ExtractiveSummarizer {
+	/// Maximum number of key sentences to pick.
+	pub max_sentences: usize,
+}
+
+impl Default for ExtractiveSummarizer {
+	fn default() -> Self {
+		Self { max_sentences: 10 }
+	}
+}
+
+/// Words this short are almost always filler ("the", "and", "but"), so they don't count towards
+/// how important a sentence is.
+const MIN_KEYWORD_LENGTH: usize = 4;
+
+/// Sentences with fewer words than this rarely say anything on their own.
+const MIN_SENTENCE_WORDS: usize = 4;
+
+#[async_trait::async_trait]
+impl Summarizer for ExtractiveSummarizer {
+	async fn summarize(&self, entries: &[TranscriptEntry]) -> SessionSummary {
+		self.extract(entries)
+	}
+}
+
+impl ExtractiveSummarizer {
+	fn extract(&self, entries: &[TranscriptEntry]) -> SessionSummary {
+		let mut talk_time: HashMap<&str, Duration> = HashMap::new();
+		let mut language_chars: HashMap<&'static str, usize> = HashMap::new();
+		let mut sentences = Vec::new();
+		for entry in entries {
+			let TranscriptEntryKind::Speech { text, .. } = &entry.kind else {
+				continue;
+			};
+			*talk_time.entry(&entry.username).or_default() += entry.end.saturating_sub(entry.start);
+
+			if let Some(info) = whatlang::detect(text).filter(whatlang::Info::is_reliable) {
+				*language_chars.entry(info.lang().eng_name()).or_default() += text.len();
+			}
+
+			sentences.extend(
+				split_sentences(text)
+					.map(|sentence| (entry.start, entry.username.as_str(), sentence)),
+			);
+		}
+
+		let mut talk_time = talk_time
+			.into_iter()
+			.map(|(username, time)| (username.to_string(), time))
+			.collect::<Vec<_>>();
+		talk_time.sort_by(|a, b| b.1.cmp(&a.1));
+
+		let total_chars: usize = language_chars.values().sum();
+		let mut languages = language_chars
+			.into_iter()
+			.map(|(language, chars)| (language.to_string(), chars as f64 / total_chars as f64))
+			.collect::<Vec<_>>();
+		languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+		SessionSummary {
+			talk_time,
+			key_sentences: self.pick_key_sentences(sentences),
+			languages,
+		}
+	}
+
+	fn pick_key_sentences(&self, sentences: Vec<(Duration, &str, &str)>) -> Vec<KeySentence> {
+		let mut word_counts: HashMap<String, usize> = HashMap::new();
+		for (_, _, sentence) in &sentences {
+			for word in keywords(sentence) {
+				*word_counts.entry(word).or_default() += 1;
+			}
+		}
+
+		// a sentence scores the average of how often its keywords are used throughout the session
+		let mut scored = sentences
+			.into_iter()
+			.enumerate()
+			.filter(|(_, (_, _, sentence))| {
+				sentence.split_whitespace().count() >= MIN_SENTENCE_WORDS
+			})
+			.map(|(idx, (offset, username, sentence))| {
+				let keywords = keywords(sentence).collect::<Vec<_>>();
+				let score = keywords.iter().map(|w| word_counts[w]).sum::<usize>() as f64
+					/ keywords.len().max(1) as f64;
+				(idx, score, offset, username, sentence)
+			})
+			.collect::<Vec<_>>();
+		scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+		scored.truncate(self.max_sentences);
+		scored.sort_by_key(|(idx, ..)| *idx);
+
+		scored
+			.into_iter()
+			.map(|(_, _, offset, username, text)| KeySentence {
+				offset,
+				username: username.to_string(),
+				text: text.to_string(),
+			})
+			.collect()
+	}
+}
+
+/// `HH:MM:SS`
+fn format_hms(duration: Duration) -> String {
+	let secs = duration.as_secs();
+	format!(
+		"{:02}:{:02}:{:02}",
+		secs / 3600,
+		(secs / 60) % 60,
+		secs % 60
+	)
+}
+
+fn split_sentences(text: &str) -> impl Iterator<Item = &str> {
+	text.split_inclusive(['.', '!', '?'])
+		.map(str::trim)
+		.filter(|s| !s.is_empty())
+}
+
+fn keywords(sentence: &str) -> impl Iterator<Item = String> + '_ {
+	sentence
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|w| w.chars().count() >= MIN_KEYWORD_LENGTH)
+		.map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn speech(start: u64, end: u64, username: &str, text: &str) -> TranscriptEntry {
+		TranscriptEntry {
+			start:    Duration::from_secs(start),
+			end:      Duration::from_secs(end),
+			user_id:  0,
+			username: username.to_string(),
+			kind:     TranscriptEntryKind::Speech {
+				text:       text.to_string(),
+				confidence: None,
+			},
+		}
+	}
+
+	#[test]
+	fn test_extractive_summary() {
+		let entries = vec![
+			speech(
+				0,
+				4,
+				"alice",
+				"We need to ship the deploy script today. Hi.",
+			),
+			speech(
+				5,
+				15,
+				"bob",
+				"The deploy script is broken on staging right now.",
+			),
+			speech(16, 18, "alice", "Did anyone eat lunch yet?"),
+		];
+		let summary = ExtractiveSummarizer { max_sentences: 2 }.extract(&entries);
+
+		assert_eq!(
+			summary.talk_time,
+			vec![
+				("bob".to_string(), Duration::from_secs(10)),
+				("alice".to_string(), Duration::from_secs(6)),
+			]
+		);
+		assert_eq!(
+			summary
+				.key_sentences
+				.iter()
+				.map(|s| s.text.as_str())
+				.collect::<Vec<_>>(),
+			vec![
+				"We need to ship the deploy script today.",
+				"The deploy script is broken on staging right now."
+			]
+		);
+	}
+}
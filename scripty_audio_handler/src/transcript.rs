@@ -0,0 +1,300 @@
+use std::{
+	fmt::Write,
+	time::{Duration, Instant},
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId};
+
+use crate::transcript_archive;
+
+/// Format a session transcript is exported in when Scripty leaves the call.
+#[repr(i16)]
+#[derive(Debug, poise::ChoiceParameter, Copy, Clone, Default, PartialEq, Eq)]
+pub enum TranscriptFormat {
+	#[default]
+	#[name = "Plain text"]
+	Txt    = 1,
+	#[name = "SRT subtitles"]
+	Srt    = 2,
+	#[name = "WebVTT subtitles"]
+	WebVtt = 3,
+	#[name = "JSON"]
+	Json   = 4,
+}
+
+impl From<i16> for TranscriptFormat {
+	fn from(value: i16) -> Self {
+		match value {
+			2 => TranscriptFormat::Srt,
+			3 => TranscriptFormat::WebVtt,
+			4 => TranscriptFormat::Json,
+			_ => TranscriptFormat::Txt,
+		}
+	}
+}
+
+impl TranscriptFormat {
+	/// Name of the file the transcript is sent as.
+	pub fn file_name(&self) -> &'static str {
+		match self {
+			TranscriptFormat::Txt => "transcript.txt",
+			TranscriptFormat::Srt => "transcript.srt",
+			TranscriptFormat::WebVtt => "transcript.vtt",
+			TranscriptFormat::Json => "transcript.json",
+		}
+	}
+
+	/// Render a transcript in this format.
+	pub fn render(&self, entries: &[TranscriptEntry]) -> String {
+		let mut entries = entries.to_vec();
+		// entries are stored as utterances finish, which isn't always the order they started in
+		entries.sort_by_key(|e| e.start);
+
+		match self {
+			TranscriptFormat::Txt => render_txt(&entries),
+			TranscriptFormat::Srt => render_srt(&entries),
+			TranscriptFormat::WebVtt => render_webvtt(&entries),
+			TranscriptFormat::Json => {
+				serde_json::to_string_pretty(&entries).expect("transcripts are always valid JSON")
+			}
+		}
+	}
+}
+
+/// One entry in a session transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+	/// Offset from the start of the session this entry starts at.
+	#[serde(with = "secs")]
+	pub start:    Duration,
+	/// Offset from the start of the session this entry ends at.
+	#[serde(with = "secs")]
+	pub end:      Duration,
+	pub user_id:  u64,
+	pub username: String,
+	#[serde(flatten)]
+	pub kind:     TranscriptEntryKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntryKind {
+	/// The user said something.
+	Speech {
+		text:       String,
+		/// How confident the model was in the transcript, from 0 to 1, if known.
+		confidence: Option<f64>,
+	},
+	/// The user left the voice chat.
+	Disconnected,
+}
+
+/// Everything said in a session where transcripts are being recorded.
+///
+/// Entries are kept in memory for sending when the session ends,
+/// and also written to the transcript archive as they come in.
+pub struct SessionTranscript {
+	id:      i32,
+	started: Instant,
+	format:  TranscriptFormat,
+	entries: RwLock<Vec<TranscriptEntry>>,
+}
+
+impl SessionTranscript {
+	/// Start recording a new session, creating it in the transcript archive.
+	pub async fn start(
+		guild_id: GuildId,
+		voice_channel_id: ChannelId,
+		format: TranscriptFormat,
+	) -> Result<Self, sqlx::Error> {
+		let id = transcript_archive::create_session(guild_id, voice_channel_id, format).await?;
+
+		Ok(Self {
+			id,
+			started: Instant::now(),
+			format,
+			entries: RwLock::new(Vec::new()),
+		})
+	}
+
+	/// Mark the session as finished in the transcript archive.
+	pub async fn finish(&self) -> Result<(), sqlx::Error> {
+		transcript_archive::end_session(self.id).await
+	}
+
+	pub fn format(&self) -> TranscriptFormat {
+		self.format
+	}
+
+	/// Offset of `instant` from the start of the session.
+	pub fn offset(&self, instant: Instant) -> Duration {
+		instant.saturating_duration_since(self.started)
+	}
+
+	pub fn push(&self, entry: TranscriptEntry) {
+		tokio::spawn(transcript_archive::store_entry(self.id, entry.clone()));
+		self.entries.write().push(entry);
+	}
+
+	/// Render the transcript in the format chosen for this session.
+	pub fn render(&self) -> String {
+		self.format.render(&self.entries.read())
+	}
+
+	/// Every entry so far, sorted by when they started.
+	pub fn entries(&self) -> Vec<TranscriptEntry> {
+		let mut entries = self.entries.read().clone();
+		entries.sort_by_key(|e| e.start);
+		entries
+	}
+}
+
+fn render_txt(entries: &[TranscriptEntry]) -> String {
+	let mut out = String::new();
+	for entry in entries {
+		match &entry.kind {
+			TranscriptEntryKind::Speech {
+				text,
+				confidence: Some(confidence),
+			} => writeln!(
+				out,
+				"[{}] ({:.0}% confident): {}",
+				entry.username,
+				confidence * 100.0,
+				text
+			),
+			TranscriptEntryKind::Speech {
+				text,
+				confidence: None,
+			} => writeln!(out, "[{}]: {}", entry.username, text),
+			TranscriptEntryKind::Disconnected => {
+				writeln!(out, "[{}] - event: disconnected", entry.username)
+			}
+		}
+		.expect("writing to a string can't fail");
+	}
+	out
+}
+
+/// Subtitle formats only have room for what was said, so events are left out.
+fn render_srt(entries: &[TranscriptEntry]) -> String {
+	let mut out = String::new();
+	for (idx, (entry, text)) in speech_entries(entries).enumerate() {
+		writeln!(
+			out,
+			"{}\n{} --> {}\n{}: {}\n",
+			idx + 1,
+			format_timestamp(entry.start, ','),
+			format_timestamp(entry.end, ','),
+			entry.username,
+			text
+		)
+		.expect("writing to a string can't fail");
+	}
+	out
+}
+
+fn render_webvtt(entries: &[TranscriptEntry]) -> String {
+	let mut out = String::from("WEBVTT\n\n");
+	for (entry, text) in speech_entries(entries) {
+		writeln!(
+			out,
+			"{} --> {}\n<v {}>{}\n",
+			format_timestamp(entry.start, '.'),
+			format_timestamp(entry.end, '.'),
+			entry.username,
+			text
+		)
+		.expect("writing to a string can't fail");
+	}
+	out
+}
+
+fn speech_entries(entries: &[TranscriptEntry]) -> impl Iterator<Item = (&TranscriptEntry, &str)> {
+	entries.iter().filter_map(|e| match &e.kind {
+		TranscriptEntryKind::Speech { text, .. } => Some((e, text.as_str())),
+		TranscriptEntryKind::Disconnected => None,
+	})
+}
+
+/// `HH:MM:SS<sep>mmm`, as used by both SRT (`,`) and WebVTT (`.`).
+fn format_timestamp(offset: Duration, millis_separator: char) -> String {
+	let secs = offset.as_secs();
+	format!(
+		"{:02}:{:02}:{:02}{}{:03}",
+		secs / 3600,
+		(secs / 60) % 60,
+		secs % 60,
+		millis_separator,
+		offset.subsec_millis()
+	)
+}
+
+/// (De)serializes a [`Duration`] as a number of seconds.
+mod secs {
+	use std::time::Duration;
+
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(duration.as_secs_f64())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+		let secs = f64::deserialize(deserializer)?;
+		Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entries() -> Vec<TranscriptEntry> {
+		vec![
+			TranscriptEntry {
+				start:    Duration::from_millis(1_500),
+				end:      Duration::from_millis(3_250),
+				user_id:  1,
+				username: "alice".to_string(),
+				kind:     TranscriptEntryKind::Speech {
+					text:       "hello there".to_string(),
+					confidence: Some(0.9),
+				},
+			},
+			TranscriptEntry {
+				start:    Duration::from_secs(3_661),
+				end:      Duration::from_secs(3_661),
+				user_id:  2,
+				username: "bob".to_string(),
+				kind:     TranscriptEntryKind::Disconnected,
+			},
+		]
+	}
+
+	#[test]
+	fn test_render_srt() {
+		assert_eq!(
+			render_srt(&entries()),
+			"1\n00:00:01,500 --> 00:00:03,250\nalice: hello there\n\n"
+		);
+	}
+
+	#[test]
+	fn test_render_webvtt() {
+		assert_eq!(
+			render_webvtt(&entries()),
+			"WEBVTT\n\n00:00:01.500 --> 00:00:03.250\n<v alice>hello there\n\n"
+		);
+	}
+
+	#[test]
+	fn test_format_timestamp() {
+		assert_eq!(
+			format_timestamp(Duration::from_millis(3_661_007), ','),
+			"01:01:01,007"
+		);
+	}
+}
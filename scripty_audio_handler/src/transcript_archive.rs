@@ -0,0 +1,382 @@
+//! Long-term storage of recorded session transcripts.
+//!
+//! Each entry is encrypted before it is stored, and sessions are deleted after a number of days
+//! that depends on the guild's premium tier.
+
+use scripty_premium::PremiumTierList;
+use serde::Deserialize;
+use serenity::all::{ChannelId, GuildId};
+
+use crate::transcript::{TranscriptEntry, TranscriptEntryKind, TranscriptFormat};
+
+/// A past session in the transcript archive.
+pub struct ArchivedSession {
+	pub id:               i32,
+	pub voice_channel_id: ChannelId,
+	pub format:           TranscriptFormat,
+	/// Unix timestamp of when the session started.
+	pub started_at:       i64,
+	/// Unix timestamp of when the session ended.
+	///
+	/// `None` if it is still going, or Scripty went down before it could finish.
+	pub ended_at:         Option<i64>,
+	pub entry_count:      i64,
+}
+
+/// How many days transcripts are kept for at each premium tier.
+pub fn get_tier_retention_days(tier: PremiumTierList) -> i32 {
+	match tier {
+		PremiumTierList::None => 7,
+		PremiumTierList::Tier1 => 14,
+		PremiumTierList::Tier2 => 30,
+		PremiumTierList::Tier3 => 60,
+		PremiumTierList::Tier4 => 90,
+		PremiumTierList::Tier5 => 180,
+		PremiumTierList::Tier6 => 365,
+	}
+}
+
+pub(crate) async fn create_session(
+	guild_id: GuildId,
+	voice_channel_id: ChannelId,
+	format: TranscriptFormat,
+) -> Result<i32, sqlx::Error> {
+	Ok(sqlx::query!(
+		"INSERT INTO transcript_sessions (guild_id, voice_channel_id, format) VALUES ($1, $2, $3) \
+		 RETURNING id",
+		guild_id.get() as i64,
+		voice_channel_id.get() as i64,
+		format as i16
+	)
+	.fetch_one(scripty_db::get_db())
+	.await?
+	.id)
+}
+
+pub(crate) async fn end_session(session_id: i32) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"UPDATE transcript_sessions SET ended_at = NOW() WHERE id = $1",
+		session_id
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	Ok(())
+}
+
+pub(crate) async fn store_entry(session_id: i32, entry: TranscriptEntry) {
+	let json = serde_json::to_vec(&entry).expect("transcript entries are always valid JSON");
+	let nonce = scripty_data_storage::generate_nonce();
+	let encrypted_entry = match scripty_data_storage::encrypt_bytes(&json, nonce) {
+		Ok(encrypted_entry) => encrypted_entry,
+		Err(e) => {
+			error!(%session_id, "failed to encrypt transcript entry: {}", e);
+			return;
+		}
+	};
+
+	if let Err(e) = sqlx::query!(
+		"INSERT INTO transcript_entries (session_id, start_ms, entry, nonce) VALUES ($1, $2, $3, \
+		 $4)",
+		session_id,
+		entry.start.as_millis() as i64,
+		encrypted_entry,
+		nonce.as_ref()
+	)
+	.execute(scripty_db::get_db())
+	.await
+	{
+		warn!(%session_id, "failed to store transcript entry: {}", e);
+	}
+}
+
+/// List all archived sessions in a guild, newest first.
+pub async fn list_sessions(guild_id: GuildId) -> Result<Vec<ArchivedSession>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"SELECT s.id, s.voice_channel_id, s.format, s.started_at, s.ended_at, COUNT(e.id) AS \
+		 \"entry_count!\" FROM transcript_sessions s LEFT JOIN transcript_entries e ON \
+		 e.session_id = s.id WHERE s.guild_id = $1 GROUP BY s.id ORDER BY s.started_at DESC",
+		guild_id.get() as i64
+	)
+	.fetch_all(scripty_db::get_db())
+	.await?
+	.into_iter()
+	.map(|row| ArchivedSession {
+		id:               row.id,
+		voice_channel_id: ChannelId::new(row.voice_channel_id as u64),
+		format:           TranscriptFormat::from(row.format),
+		started_at:       row.started_at.unix_timestamp(),
+		ended_at:         row.ended_at.map(|t| t.unix_timestamp()),
+		entry_count:      row.entry_count,
+	})
+	.collect())
+}
+
+/// Load and decrypt every entry of an archived session.
+///
+/// Returns `None` if there is no session with this ID in the guild.
+pub async fn load_session(
+	guild_id: GuildId,
+	session_id: i32,
+) -> Result<Option<(TranscriptFormat, Vec<TranscriptEntry>)>, sqlx::Error> {
+	let db = scripty_db::get_db();
+
+	let Some(session) = sqlx::query!(
+		"SELECT format FROM transcript_sessions WHERE id = $1 AND guild_id = $2",
+		session_id,
+		guild_id.get() as i64
+	)
+	.fetch_optional(db)
+	.await?
+	else {
+		return Ok(None);
+	};
+
+	let entries = sqlx::query!(
+		"SELECT entry, nonce FROM transcript_entries WHERE session_id = $1 ORDER BY start_ms",
+		session_id
+	)
+	.fetch_all(db)
+	.await?
+	.into_iter()
+	.filter_map(|row| decrypt_entry(session_id, &row.entry, &row.nonce))
+	.collect();
+
+	Ok(Some((TranscriptFormat::from(session.format), entries)))
+}
+
+/// Maximum number of hits [`search_sessions`] returns.
+pub const MAX_SEARCH_HITS: usize = 250;
+/// Maximum number of entries [`search_sessions`] decrypts in one search.
+///
+/// Every entry has to be decrypted to be matched, so this bounds how long one search can take.
+pub const MAX_SEARCH_SCANNED_ENTRIES: i64 = 20_000;
+/// How many sessions [`search_sessions`] fetches from the database at a time.
+const SEARCH_SESSION_BATCH_SIZE: i64 = 25;
+
+/// What to look for with [`search_sessions`].
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct TranscriptSearch {
+	/// Words that must all be said in an utterance for it to match, in any order.
+	pub query:            String,
+	/// Only match utterances by this user.
+	pub user_id:          Option<u64>,
+	/// Only match utterances in this voice channel.
+	pub voice_channel_id: Option<u64>,
+	/// Only match utterances at or after this Unix timestamp.
+	pub after:            Option<i64>,
+	/// Only match utterances before this Unix timestamp.
+	pub before:           Option<i64>,
+}
+
+/// The outcome of [`search_sessions`].
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+	/// Matching utterances, newest first.
+	pub hits:     Vec<SearchHit>,
+	/// `false` if the search stopped before looking through every session,
+	/// because it found [`MAX_SEARCH_HITS`] hits or decrypted [`MAX_SEARCH_SCANNED_ENTRIES`] entries.
+	pub complete: bool,
+}
+
+/// An utterance that matched a [`TranscriptSearch`].
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+	pub session_id:       i32,
+	pub voice_channel_id: ChannelId,
+	/// Unix timestamp of when the utterance was said.
+	pub timestamp:        i64,
+	pub user_id:          u64,
+	pub username:         String,
+	pub text:             String,
+}
+
+/// Search the utterances in the archived sessions of a guild, newest first.
+///
+/// Entries are encrypted at rest, so the database can only narrow down which sessions to look at:
+/// the text itself is matched after decrypting. The search gives up once it has found
+/// [`MAX_SEARCH_HITS`] hits or decrypted [`MAX_SEARCH_SCANNED_ENTRIES`] entries.
+pub async fn search_sessions(
+	guild_id: GuildId,
+	search: &TranscriptSearch,
+) -> Result<SearchResults, sqlx::Error> {
+	let db = scripty_db::get_db();
+	let query_words = normalized_words(&search.query);
+	let mut results = SearchResults {
+		hits:     Vec::new(),
+		complete: true,
+	};
+	if query_words.is_empty() {
+		return Ok(results);
+	}
+
+	let mut scan_budget = MAX_SEARCH_SCANNED_ENTRIES;
+	// (started_at, id) of the last session looked at, to fetch the next batch after it
+	let mut cursor: Option<(time::OffsetDateTime, i32)> = None;
+	loop {
+		let sessions = sqlx::query!(
+			"SELECT id, voice_channel_id, started_at FROM transcript_sessions WHERE guild_id = $1 \
+			 AND ($2::BIGINT IS NULL OR voice_channel_id = $2) AND ($3::BIGINT IS NULL OR \
+			 ended_at IS NULL OR ended_at >= to_timestamp($3)) AND ($4::BIGINT IS NULL OR \
+			 started_at < to_timestamp($4)) AND ($5::TIMESTAMPTZ IS NULL OR (started_at, id) < \
+			 ($5, $6)) ORDER BY started_at DESC, id DESC LIMIT $7",
+			guild_id.get() as i64,
+			search.voice_channel_id.map(|id| id as i64),
+			search.after,
+			search.before,
+			cursor.map(|(started_at, _)| started_at),
+			cursor.map(|(_, id)| id),
+			SEARCH_SESSION_BATCH_SIZE
+		)
+		.fetch_all(db)
+		.await?;
+		let Some(last) = sessions.last() else {
+			return Ok(results);
+		};
+		cursor = Some((last.started_at, last.id));
+
+		for session in sessions {
+			let started_at = session.started_at.unix_timestamp();
+			// entries only store their offset into the session, so the date range maps onto that
+			let entries = sqlx::query!(
+				"SELECT entry, nonce FROM transcript_entries WHERE session_id = $1 AND \
+				 ($2::BIGINT IS NULL OR start_ms >= $2) AND ($3::BIGINT IS NULL OR start_ms < $3) \
+				 ORDER BY start_ms DESC LIMIT $4",
+				session.id,
+				search.after.map(|after| (after - started_at) * 1000),
+				search.before.map(|before| (before - started_at) * 1000),
+				scan_budget
+			)
+			.fetch_all(db)
+			.await?;
+			scan_budget -= entries.len() as i64;
+
+			for row in entries {
+				let Some(entry) = decrypt_entry(session.id, &row.entry, &row.nonce) else {
+					continue;
+				};
+				let TranscriptEntryKind::Speech { text, .. } = entry.kind else {
+					continue;
+				};
+				let timestamp = started_at + entry.start.as_secs() as i64;
+
+				if search.user_id.map_or(false, |id| id != entry.user_id)
+					|| search.after.map_or(false, |after| timestamp < after)
+					|| search.before.map_or(false, |before| timestamp >= before)
+					|| !matches_query(&query_words, &text)
+				{
+					continue;
+				}
+
+				results.hits.push(SearchHit {
+					session_id: session.id,
+					voice_channel_id: ChannelId::new(session.voice_channel_id as u64),
+					timestamp,
+					user_id: entry.user_id,
+					username: entry.username,
+					text,
+				});
+				if results.hits.len() >= MAX_SEARCH_HITS {
+					results.complete = false;
+					return Ok(results);
+				}
+			}
+
+			if scan_budget <= 0 {
+				results.complete = false;
+				return Ok(results);
+			}
+		}
+	}
+}
+
+/// Delete an archived session. Returns `false` if there was no session with this ID in the guild.
+pub async fn delete_session(guild_id: GuildId, session_id: i32) -> Result<bool, sqlx::Error> {
+	let res = sqlx::query!(
+		"DELETE FROM transcript_sessions WHERE id = $1 AND guild_id = $2",
+		session_id,
+		guild_id.get() as i64
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	Ok(res.rows_affected() != 0)
+}
+
+/// Delete all sessions older than their guild's retention period.
+///
+/// Returns how many sessions were deleted.
+pub async fn prune_expired_sessions() -> Result<u64, sqlx::Error> {
+	let db = scripty_db::get_db();
+
+	// anything younger than the shortest retention period can't have expired yet
+	let guild_ids = sqlx::query!(
+		"SELECT DISTINCT guild_id FROM transcript_sessions WHERE started_at < NOW() - \
+		 make_interval(days => $1)",
+		get_tier_retention_days(PremiumTierList::None)
+	)
+	.fetch_all(db)
+	.await?;
+
+	let mut deleted = 0;
+	for row in guild_ids {
+		let tier = scripty_premium::get_guild(row.guild_id as u64)
+			.await
+			.unwrap_or_default();
+		deleted += sqlx::query!(
+			"DELETE FROM transcript_sessions WHERE guild_id = $1 AND started_at < NOW() - \
+			 make_interval(days => $2)",
+			row.guild_id,
+			get_tier_retention_days(tier)
+		)
+		.execute(db)
+		.await?
+		.rows_affected();
+	}
+
+	Ok(deleted)
+}
+
+fn decrypt_entry(session_id: i32, entry: &[u8], nonce: &[u8]) -> Option<TranscriptEntry> {
+	let nonce = <[u8; 12]>::try_from(nonce).ok()?;
+	let json = match scripty_data_storage::decrypt_bytes(entry, nonce) {
+		Ok(json) => json,
+		Err(e) => {
+			warn!(%session_id, "failed to decrypt transcript entry: {}", e);
+			return None;
+		}
+	};
+	match serde_json::from_slice(&json) {
+		Ok(entry) => Some(entry),
+		Err(e) => {
+			warn!(%session_id, "failed to parse transcript entry: {}", e);
+			None
+		}
+	}
+}
+
+/// Lowercase words in `text`, with punctuation stripped.
+fn normalized_words(text: &str) -> Vec<String> {
+	text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+		.map(|w| w.trim_matches('\'').to_lowercase())
+		.filter(|w| !w.is_empty())
+		.collect()
+}
+
+/// Whether every word of the query is somewhere in `text`.
+fn matches_query(query_words: &[String], text: &str) -> bool {
+	let words = normalized_words(text);
+	query_words.iter().all(|q| words.contains(q))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_matches_query() {
+		let query = normalized_words("Deploy friday");
+		assert!(matches_query(&query, "we can't deploy on a Friday!"));
+		assert!(!matches_query(&query, "the deployment is on friday"));
+		assert!(!matches_query(&query, "deploy it now"));
+	}
+}
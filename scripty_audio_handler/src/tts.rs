@@ -0,0 +1,410 @@
+use std::{
+	io::Cursor,
+	sync::{Arc, OnceLock as OnceCell},
+};
+
+use ahash::RandomState;
+use dashmap::DashMap;
+use scripty_tts::{EngineParameters, EspeakNgEngine, TtsEngine, TtsEngineOutput};
+use serenity::{
+	all::{ChannelId, GuildId, UserId},
+	client::Context,
+};
+use songbird::{
+	events::{Event, EventContext, EventHandler, TrackEvent},
+	input::{Input, RawAdapter},
+	tracks::TrackHandle,
+	Call,
+};
+use tokio::{
+	sync::{
+		mpsc::{channel, error::TrySendError, Receiver, Sender},
+		oneshot,
+		Mutex,
+	},
+	task::JoinHandle,
+};
+
+use crate::{
+	consts::{MAX_TTS_MESSAGE_LENGTH, MAX_TTS_QUEUE_LENGTH},
+	Error,
+};
+
+/// A guild's playback queue, along with the text channel being read aloud in it, if any.
+struct TtsQueue {
+	text_channel_id: Option<ChannelId>,
+	tx:              Sender<TtsRequest>,
+	/// Aborting this drops everything still queued, and stops the message currently playing.
+	worker:          JoinHandle<()>,
+}
+
+struct TtsRequest {
+	text:    String,
+	params:  EngineParameters,
+	/// Fired when this request starts playing.
+	started: Option<oneshot::Sender<()>>,
+}
+
+static TTS_QUEUES: OnceCell<DashMap<GuildId, TtsQueue, RandomState>> = OnceCell::new();
+
+fn get_queues() -> &'static DashMap<GuildId, TtsQueue, RandomState> {
+	TTS_QUEUES.get_or_init(|| DashMap::with_hasher(RandomState::default()))
+}
+
+/// Get a sender for this guild's playback queue, starting one up if there isn't one already.
+///
+/// Returns `None` if Scripty is not currently in a call in this guild.
+async fn get_or_start_queue(
+	ctx: &Context,
+	guild_id: GuildId,
+) -> Result<Option<Sender<TtsRequest>>, Error> {
+	if let Some(queue) = get_queues().get(&guild_id) {
+		if !queue.tx.is_closed() {
+			return Ok(Some(queue.tx.clone()));
+		}
+	}
+
+	let engine = EspeakNgEngine::new()?;
+	let Some(call_lock) = get_unmuted_call(ctx, guild_id).await? else {
+		return Ok(None);
+	};
+
+	let (tx, rx) = channel(MAX_TTS_QUEUE_LENGTH);
+	let worker = tokio::spawn(tts_worker(guild_id, call_lock, engine, rx));
+	if let Some(old_queue) = get_queues().insert(
+		guild_id,
+		TtsQueue {
+			text_channel_id: None,
+			tx: tx.clone(),
+			worker,
+		},
+	) {
+		old_queue.worker.abort();
+	}
+
+	Ok(Some(tx))
+}
+
+/// Drop this guild's playback queue, along with anything still waiting to be played.
+pub(crate) fn remove_queue(guild_id: GuildId) {
+	if let Some((_, queue)) = get_queues().remove(&guild_id) {
+		queue.worker.abort();
+	}
+}
+
+/// Fetch the call for this guild, unmuting it if needed.
+async fn get_unmuted_call(
+	ctx: &Context,
+	guild_id: GuildId,
+) -> Result<Option<Arc<Mutex<Call>>>, Error> {
+	let sb = songbird::get(ctx).await.expect("songbird not initialized");
+	let Some(call_lock) = sb.get(guild_id) else {
+		return Ok(None);
+	};
+
+	let mut call = call_lock.lock().await;
+	if call.is_mute() {
+		debug!(%guild_id, "unmuting call for tts");
+		call.mute(false).await?;
+	}
+	drop(call);
+
+	Ok(Some(call_lock))
+}
+
+/// Start reading messages from `text_channel_id` aloud in the current call for this guild.
+///
+/// Replaces any channel already being read aloud in the guild.
+///
+/// Returns `false` if Scripty is not currently in a call in this guild.
+pub async fn start_tts(
+	ctx: &Context,
+	guild_id: GuildId,
+	text_channel_id: ChannelId,
+) -> Result<bool, Error> {
+	if get_or_start_queue(ctx, guild_id).await?.is_none() {
+		return Ok(false);
+	}
+
+	match get_queues().get_mut(&guild_id) {
+		Some(mut queue) => {
+			queue.text_channel_id = Some(text_channel_id);
+			Ok(true)
+		}
+		// the call went away in between
+		None => Ok(false),
+	}
+}
+
+/// Stop reading messages aloud in this guild.
+///
+/// Anything still waiting to be played is dropped, the current message is cut off,
+/// and Scripty is muted again.
+///
+/// Returns `false` if TTS was not running in this guild.
+pub async fn stop_tts(ctx: &Context, guild_id: GuildId) -> Result<bool, Error> {
+	let Some((_, queue)) = get_queues().remove_if(&guild_id, |_, q| q.text_channel_id.is_some())
+	else {
+		return Ok(false);
+	};
+	queue.worker.abort();
+
+	let sb = songbird::get(ctx).await.expect("songbird not initialized");
+	if let Some(call_lock) = sb.get(guild_id) {
+		let mut call = call_lock.lock().await;
+		if !call.is_mute() {
+			debug!(%guild_id, "muting call now tts has stopped");
+			call.mute(true).await?;
+		}
+	}
+
+	Ok(true)
+}
+
+/// Get the text channel currently being read aloud in this guild, if any.
+pub fn get_tts_channel(guild_id: GuildId) -> Option<ChannelId> {
+	get_queues().get(&guild_id).and_then(|q| q.text_channel_id)
+}
+
+/// Queue a message to be read aloud, if it was sent in the guild's TTS channel.
+///
+/// Returns `false` if the message was not queued.
+pub async fn queue_tts_message(
+	guild_id: GuildId,
+	channel_id: ChannelId,
+	user_id: UserId,
+	mut text: String,
+) -> Result<bool, Error> {
+	if get_tts_channel(guild_id) != Some(channel_id) || text.trim().is_empty() {
+		return Ok(false);
+	}
+
+	truncate_tts_text(&mut text);
+	let params = get_user_tts_params(user_id.get()).await?;
+
+	// re-fetch the queue, as the session may have stopped while we hit the database
+	let Some(queue) = get_queues().get(&guild_id) else {
+		return Ok(false);
+	};
+	match queue.tx.try_send(TtsRequest {
+		text,
+		params,
+		started: None,
+	}) {
+		Ok(()) => Ok(true),
+		Err(TrySendError::Full(_)) => {
+			debug!(%guild_id, "tts queue is full, dropping message");
+			Ok(false)
+		}
+		Err(TrySendError::Closed(_)) => Ok(false),
+	}
+}
+
+/// Speak `text` in this guild's call.
+///
+/// Normal messages are queued behind anything else waiting to be spoken.
+/// High priority messages are played immediately, mixed over anything already playing.
+/// Either way, `text` is cut off at [`MAX_TTS_MESSAGE_LENGTH`] characters.
+///
+/// Returns a receiver that fires once the message starts playing,
+/// or `None` if Scripty is not currently in a call in this guild, or its queue is full.
+pub async fn speak(
+	ctx: &Context,
+	guild_id: GuildId,
+	mut text: String,
+	params: EngineParameters,
+	high_priority: bool,
+) -> Result<Option<oneshot::Receiver<()>>, Error> {
+	let (started_tx, started_rx) = oneshot::channel();
+	truncate_tts_text(&mut text);
+
+	if high_priority {
+		let engine = EspeakNgEngine::new()?;
+		let Some(call_lock) = get_unmuted_call(ctx, guild_id).await? else {
+			return Ok(None);
+		};
+		let sample_rate = engine.sample_rate();
+		let output =
+			tokio::task::spawn_blocking(move || engine.get_waveform(&text, &params)).await??;
+
+		call_lock
+			.lock()
+			.await
+			.play_input(output_to_input(output, sample_rate));
+		let _ = started_tx.send(());
+	} else {
+		let Some(tx) = get_or_start_queue(ctx, guild_id).await? else {
+			return Ok(None);
+		};
+		if let Err(e) = tx.try_send(TtsRequest {
+			text,
+			params,
+			started: Some(started_tx),
+		}) {
+			if matches!(e, TrySendError::Full(_)) {
+				debug!(%guild_id, "tts queue is full, dropping message");
+			}
+			return Ok(None);
+		}
+	}
+
+	Ok(Some(started_rx))
+}
+
+/// Cut `text` off at [`MAX_TTS_MESSAGE_LENGTH`] characters.
+fn truncate_tts_text(text: &mut String) {
+	if let Some((idx, _)) = text.char_indices().nth(MAX_TTS_MESSAGE_LENGTH) {
+		text.truncate(idx);
+	}
+}
+
+/// Play a short "ding" in this guild's call, mixed over anything already playing.
+///
+/// Returns `false` if Scripty is not currently in a call in this guild.
+pub async fn play_ding(ctx: &Context, guild_id: GuildId) -> Result<bool, Error> {
+	const SAMPLE_RATE: u32 = 48_000;
+	const FREQUENCY: f32 = 880.0;
+	const LENGTH_SECS: f32 = 0.25;
+
+	let Some(call_lock) = get_unmuted_call(ctx, guild_id).await? else {
+		return Ok(false);
+	};
+
+	// a sine wave that decays exponentially, so it sounds like a bell rather than a beep
+	let bytes = (0..(SAMPLE_RATE as f32 * LENGTH_SECS) as u32)
+		.flat_map(|i| {
+			let t = i as f32 / SAMPLE_RATE as f32;
+			let sample = (t * FREQUENCY * std::f32::consts::TAU).sin() * (-t * 16.0).exp() * 0.5;
+			sample.to_le_bytes()
+		})
+		.collect::<Vec<u8>>();
+	call_lock
+		.lock()
+		.await
+		.play_input(RawAdapter::new(Cursor::new(bytes), SAMPLE_RATE, 1).into());
+
+	Ok(true)
+}
+
+/// Fetch a user's TTS voice parameters, falling back to the defaults if they have none set.
+pub async fn get_user_tts_params(user_id: u64) -> Result<EngineParameters, Error> {
+	let hashed_user_id = scripty_utils::hash_user_id(user_id);
+	let params = sqlx::query!(
+		"SELECT tts_voice, tts_speed, tts_pitch, tts_amplitude, tts_gap FROM users WHERE user_id \
+		 = $1",
+		hashed_user_id
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	.map_or_else(default_tts_params, |row| EngineParameters {
+		voice:     row.tts_voice,
+		amplitude: row.tts_amplitude.clamp(0, 200) as u8,
+		gap:       row.tts_gap.clamp(0, 255) as u8,
+		pitch:     row.tts_pitch.clamp(0, 99) as u8,
+		speed:     row.tts_speed.clamp(80, 450) as u16,
+	});
+
+	Ok(params)
+}
+
+/// The voice parameters used for anyone who hasn't set their own.
+pub fn default_tts_params() -> EngineParameters {
+	EngineParameters {
+		voice:     "en".to_string(),
+		amplitude: 100,
+		gap:       0,
+		pitch:     50,
+		speed:     175,
+	}
+}
+
+fn output_to_input(output: TtsEngineOutput, sample_rate: u32) -> Input {
+	match output {
+		TtsEngineOutput::Wav(wav) => Input::from(wav),
+		TtsEngineOutput::RawPcm(pcm) => {
+			// songbird's raw adapter wants little-endian f32 samples, and resamples to 48kHz itself
+			let bytes = pcm
+				.into_iter()
+				.flat_map(|s| (s as f32 / i16::MAX as f32).to_le_bytes())
+				.collect::<Vec<u8>>();
+			RawAdapter::new(Cursor::new(bytes), sample_rate, 1).into()
+		}
+	}
+}
+
+async fn tts_worker(
+	guild_id: GuildId,
+	call: Arc<Mutex<Call>>,
+	engine: EspeakNgEngine,
+	mut rx: Receiver<TtsRequest>,
+) {
+	let engine = Arc::new(engine);
+	let sample_rate = engine.sample_rate();
+
+	while let Some(TtsRequest {
+		text,
+		params,
+		started,
+	}) = rx.recv().await
+	{
+		let engine2 = Arc::clone(&engine);
+		let output =
+			match tokio::task::spawn_blocking(move || engine2.get_waveform(&text, &params)).await {
+				Ok(Ok(output)) => output,
+				Ok(Err(e)) => {
+					warn!(%guild_id, "failed to synthesize tts message: {}", e);
+					continue;
+				}
+				Err(e) => {
+					error!(%guild_id, "tts synthesis task panicked: {}", e);
+					continue;
+				}
+			};
+
+		// play one message at a time, waiting for each to finish before starting the next
+		let (done_tx, done_rx) = oneshot::channel();
+		let notifier = TrackDoneNotifier(Arc::new(parking_lot::Mutex::new(Some(done_tx))));
+		let track = StopTrackOnDrop(
+			call.lock()
+				.await
+				.play_input(output_to_input(output, sample_rate)),
+		);
+		if let Some(started) = started {
+			let _ = started.send(());
+		}
+		for event in [TrackEvent::End, TrackEvent::Error] {
+			if let Err(e) = track.0.add_event(Event::Track(event), notifier.clone()) {
+				debug!(%guild_id, "tts track ended before events were added: {}", e);
+			}
+		}
+		// only the copies attached to the track should keep the sender alive,
+		// so this errors out instead of hanging if the track or call is dropped
+		drop(notifier);
+		let _ = done_rx.await;
+	}
+
+	debug!(%guild_id, "tts worker exiting");
+}
+
+/// Stops a track once dropped, so aborting the worker cuts off whatever it is playing.
+struct StopTrackOnDrop(TrackHandle);
+
+impl Drop for StopTrackOnDrop {
+	fn drop(&mut self) {
+		// errors if the track already ended, which is fine
+		let _ = self.0.stop();
+	}
+}
+
+#[derive(Clone)]
+struct TrackDoneNotifier(Arc<parking_lot::Mutex<Option<oneshot::Sender<()>>>>);
+
+#[async_trait::async_trait]
+impl EventHandler for TrackDoneNotifier {
+	async fn act(&self, _: &EventContext<'_>) -> Option<Event> {
+		if let Some(tx) = self.0.lock().take() {
+			let _ = tx.send(());
+		}
+		Some(Event::Cancel)
+	}
+}
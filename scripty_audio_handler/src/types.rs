@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, sync::Arc};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
 
 use ahash::RandomState;
 use dashmap::{DashMap, DashSet};
@@ -6,6 +6,12 @@ use parking_lot::RwLock;
 use scripty_data_storage::VoiceIngest;
 use scripty_stt::Stream;
 
+use crate::{
+	call_stats::CallStats,
+	live_partials::LivePartial,
+	transcript::{SessionTranscript, TranscriptFormat},
+};
+
 /// Type alias for a `DashMap` containing SSRCs mapped to `UserId`s.
 pub type SsrcUserIdMap = DashMap<u32, u64, RandomState>;
 
@@ -19,7 +25,9 @@ pub type SsrcStreamMap = DashMap<u32, Stream, RandomState>;
 /// Field 1 of the internal tuple is the user's avatar URL
 ///
 /// Field 2 of the internal tuple is whether the user has the transcribe-only role
-pub type SsrcUserDataMap = DashMap<u32, (String, String, bool), RandomState>;
+///
+/// Field 3 of the internal tuple is whether the user has a role exempt from automod
+pub type SsrcUserDataMap = DashMap<u32, (String, String, bool, bool), RandomState>;
 
 /// Type alias for a `DashMap` containing SSRCs mapped to whether they should be ignored
 pub type SsrcIgnoredMap = DashMap<u32, bool, RandomState>;
@@ -27,6 +35,29 @@ pub type SsrcIgnoredMap = DashMap<u32, bool, RandomState>;
 /// Type alias for a `DashMap` containing SSRCs mapped to a voice audio ingest struct.
 pub type SsrcVoiceIngestMap = DashMap<u32, Option<VoiceIngest>, RandomState>;
 
+/// Type alias for a `DashMap` containing SSRCs mapped to their live partial transcript.
+///
+/// Field 0 of the internal tuple is when a partial result was last requested
+///
+/// Field 1 of the internal tuple is the state of the live transcript message
+pub type SsrcLivePartialMap =
+	DashMap<u32, (Instant, Arc<tokio::sync::Mutex<LivePartial>>), RandomState>;
+
+/// The utterance a user is currently speaking.
+pub struct Utterance {
+	/// When the first audio of this utterance was received.
+	pub started:   Instant,
+	/// How many samples of audio this utterance has, at 16kHz.
+	pub samples:   usize,
+	/// The audio of this utterance, at 16kHz.
+	///
+	/// Only kept if automod attaches recordings to its logs, otherwise this is always empty.
+	pub recording: Vec<i16>,
+}
+
+/// Type alias for a `DashMap` containing SSRCs mapped to the utterance they are currently speaking.
+pub type SsrcUtteranceMap = DashMap<u32, Utterance, RandomState>;
+
 /// Type alias for a `DashSet` containing the SSRCs that were speaking this tick.
 pub type SsrcSpeakingSet = DashSet<u32, RandomState>;
 
@@ -36,9 +67,25 @@ pub type ActiveUserSet = DashSet<u32, RandomState>;
 /// Type alias for a `RwLock<Vec>` containing the next users to be added
 pub type NextUserList = RwLock<VecDeque<u32>>;
 
-/// Type alias for a `Arc<RwLock<Vec<String>>>` containing the transcript results
-pub type TranscriptResults = Option<Arc<RwLock<Vec<String>>>>;
+/// Type alias for a `Arc<SessionTranscript>` containing the transcript results
+pub type TranscriptResults = Option<Arc<SessionTranscript>>;
 
 /// Type alias for a `Arc<DashSet<u64>>` containing the users that have been seen and who should
 /// get a transcript at the end of the session.
 pub type SeenUsers = Option<Arc<DashSet<u64, RandomState>>>;
+
+/// The session a new connection to a voice chat belongs to.
+pub enum CallSession {
+	/// Start a new session, recording transcripts in this format if one is set.
+	New(Option<TranscriptFormat>),
+	/// Carry on the session of a connection that dropped, after reconnecting.
+	Resumed(SessionState),
+}
+
+/// Everything a session keeps track of, handed over from a dropped connection to the next one.
+#[derive(Clone)]
+pub struct SessionState {
+	pub transcript_results: TranscriptResults,
+	pub seen_users:         SeenUsers,
+	pub call_stats:         Arc<CallStats>,
+}
@@ -0,0 +1,106 @@
+//! Per-utterance cost of checking automod rules, at each premium tier's rule limit.
+//!
+//! Run with `cargo bench -p scripty_automod`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use scripty_automod::{
+	rule_set::RuleSet,
+	types::{AutomodRule, AutomodRuleAction, AutomodRuleType},
+	utils::get_tier_rule_count,
+};
+use scripty_premium::PremiumTierList;
+
+/// A typical utterance, which doesn't match any rule, so every rule has to be considered.
+const UTTERANCE: &str = "Alright so I think we should push the release back until Thursday, the \
+                         migration still needs another look and I don't want to rush it";
+
+const TIERS: [PremiumTierList; 7] = [
+	PremiumTierList::None,
+	PremiumTierList::Tier1,
+	PremiumTierList::Tier2,
+	PremiumTierList::Tier3,
+	PremiumTierList::Tier4,
+	PremiumTierList::Tier5,
+	PremiumTierList::Tier6,
+];
+
+/// Generate `count` made up rules, deterministically, that won't match [`UTTERANCE`].
+///
+/// Every tenth rule is a whole word rule, the rest are regular rules.
+fn generate_rules(count: usize) -> Vec<AutomodRule> {
+	// simple LCG, so the rules are the same every run without pulling in a RNG
+	let mut state: u64 = 0x5c21_97e3;
+	let mut next = move || {
+		state = state
+			.wrapping_mul(6_364_136_223_846_793_005)
+			.wrapping_add(1_442_695_040_888_963_407);
+		(state >> 33) as usize
+	};
+
+	(0..count)
+		.map(|i| {
+			let len = 5 + next() % 6;
+			let rule_data = (0..len)
+				.map(|_| (b'q' + (next() % 10) as u8) as char)
+				.collect::<String>();
+			AutomodRule {
+				rule_id: i as i32,
+				rule_type: if i % 10 == 0 {
+					AutomodRuleType::WholeWord
+				} else {
+					AutomodRuleType::Regular
+				},
+				rule_data,
+				rule_action: AutomodRuleAction::DeleteAndLog,
+				channel_ids: vec![],
+			}
+		})
+		.collect()
+}
+
+fn bench_rule_set(c: &mut Criterion) {
+	let mut group = c.benchmark_group("automod_first_match");
+	for tier in TIERS {
+		let count = get_tier_rule_count(tier) as usize;
+		let rule_set = RuleSet::compile(&generate_rules(count));
+		group.bench_with_input(
+			BenchmarkId::new("aho_corasick", count),
+			&rule_set,
+			|b, rule_set| b.iter(|| rule_set.first_match(black_box(UTTERANCE), 0)),
+		);
+	}
+	group.finish();
+}
+
+/// What checking rules used to cost: lowercase the message, then `contains` for every rule.
+fn bench_linear_scan(c: &mut Criterion) {
+	let mut group = c.benchmark_group("automod_first_match");
+	for tier in TIERS {
+		let count = get_tier_rule_count(tier) as usize;
+		let rules = generate_rules(count)
+			.into_iter()
+			.map(|r| r.rule_data)
+			.collect::<Vec<_>>();
+		group.bench_with_input(
+			BenchmarkId::new("linear_scan", count),
+			&rules,
+			|b, rules| {
+				b.iter(|| {
+					let msg = black_box(UTTERANCE).to_lowercase();
+					rules.iter().position(|rule| msg.contains(rule.as_str()))
+				})
+			},
+		);
+	}
+	group.finish();
+}
+
+fn bench_compile(c: &mut Criterion) {
+	let rules = generate_rules(get_tier_rule_count(PremiumTierList::Tier6) as usize);
+	c.bench_function("automod_compile_tier6", |b| {
+		b.iter(|| RuleSet::compile(black_box(&rules)))
+	});
+}
+
+criterion_group!(benches, bench_rule_set, bench_linear_scan, bench_compile);
+criterion_main!(benches);
@@ -1,44 +1,198 @@
-use crate::types::{AutomodRule, AutomodServerConfig};
+use std::{
+	collections::{HashMap, HashSet},
+	ops::RangeInclusive,
+	sync::{Arc, OnceLock},
+	time::{Duration, Instant},
+};
+
+use ahash::RandomState;
+use dashmap::DashMap;
+
+use crate::{
+	strikes::{
+		StrikeConfig,
+		MUTE_MINUTES_RANGE,
+		STRIKE_DECAY_HOURS_RANGE,
+		STRIKE_THRESHOLD_RANGE,
+		TIMEOUT_MINUTES_RANGE,
+	},
+	types::{AutomodRule, AutomodRuleGroup, AutomodServerConfig},
+};
 
 pub async fn get_guild_config(guild_id: u64) -> Result<Option<AutomodServerConfig>, sqlx::Error> {
 	let db = scripty_db::get_db();
-	let mut cfg = match sqlx::query!(
+	let Some(cfg) = sqlx::query!(
 		"SELECT * FROM automod_config WHERE guild_id = $1",
 		guild_id as i64
 	)
 	.fetch_optional(db)
 	.await?
-	.map(|row| {
-		AutomodServerConfig::new(
-			row.guild_id as u64,
-			row.item_id,
-			row.enabled,
-			vec![],
-			vec![],
-			row.log_channel_id as u64,
-			row.log_recording,
-			row.auto_join_voice,
-		)
-	}) {
-		Some(cfg) => cfg,
-		None => return Ok(None),
+	else {
+		return Ok(None);
 	};
 
-	// fetch rules
-	// TODO: groups are currently not supported
-	let res = sqlx::query!(
-		"SELECT * FROM automod_rules WHERE source_id = $1",
-		cfg.internal_id
+	let mut groups = sqlx::query!(
+		"SELECT item_id, group_name, default_action, enabled FROM automod_rule_groups WHERE \
+		 source_id = $1 ORDER BY item_id",
+		cfg.item_id
 	)
 	.fetch_all(db)
-	.await?;
+	.await?
+	.into_iter()
+	.map(|group| AutomodRuleGroup {
+		group_id:       group.item_id,
+		group_name:     group.group_name,
+		default_action: group.default_action.into(),
+		enabled:        group.enabled,
+		rules:          vec![],
+	})
+	.collect::<Vec<_>>();
+	let group_idx: HashMap<i32, usize> = groups
+		.iter()
+		.enumerate()
+		.map(|(idx, group)| (group.group_id, idx))
+		.collect();
+
+	// channels each scoped rule applies in
+	let mut rule_channels: HashMap<i32, Vec<u64>> = HashMap::new();
+	for row in sqlx::query!(
+		"SELECT c.rule_id, c.channel_id FROM automod_rule_channels c INNER JOIN automod_rules r \
+		 ON r.item_id = c.rule_id WHERE r.source_id = $1",
+		cfg.item_id
+	)
+	.fetch_all(db)
+	.await?
+	{
+		rule_channels
+			.entry(row.rule_id)
+			.or_default()
+			.push(row.channel_id as u64);
+	}
 
-	for rule in res {
-		cfg.add_rule(AutomodRule {
-			rule_type:   rule.rule_type.into(),
-			rule_data:   rule.rule_data,
-			rule_action: rule.rule_action.into(),
+	// fetch rules
+	let mut rules = Vec::new();
+	for rule in sqlx::query!(
+		"SELECT item_id, rule_type, rule_data, rule_action, group_id FROM automod_rules WHERE \
+		 source_id = $1 ORDER BY item_id",
+		cfg.item_id
+	)
+	.fetch_all(db)
+	.await?
+	{
+		// rules in a group share the group's action, and are skipped if it is disabled
+		let rule_action = match rule.group_id.and_then(|id| group_idx.get(&id)) {
+			Some(&idx) => {
+				let group = &mut groups[idx];
+				group.rules.push(rule.rule_data.clone());
+				if !group.enabled {
+					continue;
+				}
+				group.default_action
+			}
+			None => rule.rule_action.into(),
+		};
+		rules.push(AutomodRule {
+			rule_id: rule.item_id,
+			rule_type: rule.rule_type.into(),
+			rule_data: rule.rule_data,
+			rule_action,
+			channel_ids: rule_channels.remove(&rule.item_id).unwrap_or_default(),
 		});
 	}
-	Ok(Some(cfg))
+
+	let exempt_roles = sqlx::query!(
+		"SELECT role_id FROM automod_exempt_roles WHERE source_id = $1",
+		cfg.item_id
+	)
+	.fetch_all(db)
+	.await?
+	.into_iter()
+	.map(|row| row.role_id as u64)
+	.collect::<HashSet<_>>();
+	let exempt_channels = sqlx::query!(
+		"SELECT channel_id FROM automod_exempt_channels WHERE source_id = $1",
+		cfg.item_id
+	)
+	.fetch_all(db)
+	.await?
+	.into_iter()
+	.map(|row| row.channel_id as u64)
+	.collect::<HashSet<_>>();
+
+	// compile all the rules at once, rather than rebuilding the rule set after each one
+	Ok(Some(
+		AutomodServerConfig {
+			guild_id: cfg.guild_id as u64,
+			internal_id: cfg.item_id,
+			enabled: cfg.enabled,
+			groups,
+			log_channel_id: cfg.log_channel_id as u64,
+			log_recording: cfg.log_recording,
+			auto_join_voice: cfg.auto_join_voice,
+			strikes: StrikeConfig {
+				enabled:           cfg.strikes_enabled,
+				decay:             Duration::from_secs(
+					clamp_to(cfg.strike_decay_hours, STRIKE_DECAY_HOURS_RANGE) as u64 * 60 * 60,
+				),
+				mute_threshold:    clamp_to(cfg.strike_mute_threshold, STRIKE_THRESHOLD_RANGE)
+					as u32,
+				kick_threshold:    clamp_to(cfg.strike_kick_threshold, STRIKE_THRESHOLD_RANGE)
+					as u32,
+				timeout_threshold: clamp_to(cfg.strike_timeout_threshold, STRIKE_THRESHOLD_RANGE)
+					as u32,
+				timeout_duration:  Duration::from_secs(
+					clamp_to(cfg.timeout_minutes, TIMEOUT_MINUTES_RANGE) as u64 * 60,
+				),
+				mute_duration:     (cfg.mute_minutes > 0).then(|| {
+					Duration::from_secs(clamp_to(cfg.mute_minutes, MUTE_MINUTES_RANGE) as u64 * 60)
+				}),
+			},
+			monitor_only: cfg.monitor_only,
+			exempt_roles,
+			exempt_channels,
+			..Default::default()
+		}
+		.with_rules(rules),
+	))
+}
+
+/// Clamp a value loaded from the database into its allowed range,
+/// so a bad row can't turn into a huge or wrapped-around duration.
+fn clamp_to(value: i32, range: RangeInclusive<i32>) -> i32 {
+	value.clamp(*range.start(), *range.end())
+}
+
+/// How long [`get_cached_guild_config`] keeps a config before loading it again.
+const CONFIG_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+type ConfigCache = DashMap<u64, (Instant, Option<Arc<AutomodServerConfig>>), RandomState>;
+
+static CONFIG_CACHE: OnceLock<ConfigCache> = OnceLock::new();
+
+/// Like [`get_guild_config`], but keeps configs for a few minutes,
+/// so rules aren't loaded and compiled again for every message.
+pub async fn get_cached_guild_config(
+	guild_id: u64,
+) -> Result<Option<Arc<AutomodServerConfig>>, sqlx::Error> {
+	let cache = CONFIG_CACHE.get_or_init(|| DashMap::with_hasher(RandomState::new()));
+	if let Some(cached) = cache
+		.get(&guild_id)
+		.filter(|x| x.value().0.elapsed() < CONFIG_CACHE_TTL)
+	{
+		return Ok(cached.value().1.clone());
+	}
+
+	let cfg = get_guild_config(guild_id).await?.map(Arc::new);
+	cache.insert(guild_id, (Instant::now(), cfg.clone()));
+	Ok(cfg)
+}
+
+/// Drop a guild's config from the cache used by [`get_cached_guild_config`],
+/// so changes apply to the next message rather than a few minutes later.
+///
+/// Call this after changing anything about a guild's automod setup.
+pub fn invalidate_guild_config(guild_id: u64) {
+	if let Some(cache) = CONFIG_CACHE.get() {
+		cache.remove(&guild_id);
+	}
 }
@@ -0,0 +1,304 @@
+//! Groups of automod rules that share one action, and bulk import and export of their rules.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AutomodRuleAction, AutomodRuleType};
+
+/// Largest rule file that can be imported at once.
+pub const MAX_IMPORT_SIZE: u32 = 8 * 1024 * 1024;
+
+/// Longest a group name can be.
+pub const MAX_GROUP_NAME_LENGTH: usize = 100;
+
+/// A group of rules in a server.
+#[derive(Debug, Clone)]
+pub struct RuleGroupInfo {
+	pub id:             i32,
+	pub name:           String,
+	pub default_action: AutomodRuleAction,
+	pub enabled:        bool,
+	pub rule_count:     i64,
+}
+
+/// A file format rules can be imported from and exported to.
+#[derive(Debug, poise::ChoiceParameter, Copy, Clone)]
+pub enum RuleFileFormat {
+	#[name = "CSV"]
+	Csv,
+	#[name = "JSON"]
+	Json,
+}
+
+impl RuleFileFormat {
+	/// Guess the format of a file from its extension.
+	pub fn from_file_name(file_name: &str) -> Option<Self> {
+		let (_, extension) = file_name.rsplit_once('.')?;
+		match extension.to_ascii_lowercase().as_str() {
+			"csv" => Some(Self::Csv),
+			"json" => Some(Self::Json),
+			_ => None,
+		}
+	}
+
+	pub fn file_name(self) -> &'static str {
+		match self {
+			Self::Csv => "rules.csv",
+			Self::Json => "rules.json",
+		}
+	}
+}
+
+/// One rule in an imported or exported file.
+///
+/// CSV files have a `rule_type,rule_data` header, and JSON files are an array of objects with
+/// those two keys. `rule_type` is one of `regular`, `whole_word`, `regex` or `fuzzy`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleFileEntry {
+	pub rule_type: AutomodRuleType,
+	pub rule_data: String,
+}
+
+/// Why a rule file couldn't be read.
+#[derive(Debug)]
+pub enum RuleFileError {
+	Csv(csv::Error),
+	Json(serde_json::Error),
+}
+
+impl std::fmt::Display for RuleFileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RuleFileError::Csv(e) => write!(f, "invalid CSV: {}", e),
+			RuleFileError::Json(e) => write!(f, "invalid JSON: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for RuleFileError {}
+
+/// Read every rule in a file.
+pub fn parse_rules(
+	data: &[u8],
+	format: RuleFileFormat,
+) -> Result<Vec<RuleFileEntry>, RuleFileError> {
+	match format {
+		RuleFileFormat::Csv => {
+			let mut reader = csv::ReaderBuilder::new()
+				.trim(csv::Trim::Headers)
+				.from_reader(data);
+			reader
+				.deserialize::<RuleFileEntry>()
+				.collect::<Result<_, _>>()
+				.map_err(RuleFileError::Csv)
+		}
+		RuleFileFormat::Json => serde_json::from_slice(data).map_err(RuleFileError::Json),
+	}
+}
+
+/// Write rules to a file, in a format [`parse_rules`] can read back.
+pub fn write_rules(rules: &[RuleFileEntry], format: RuleFileFormat) -> Vec<u8> {
+	match format {
+		RuleFileFormat::Csv => {
+			let mut writer = csv::Writer::from_writer(Vec::new());
+			for rule in rules {
+				writer
+					.serialize(rule)
+					.expect("writing rules to memory can't fail");
+			}
+			writer
+				.into_inner()
+				.expect("writing rules to memory can't fail")
+		}
+		RuleFileFormat::Json => {
+			serde_json::to_vec_pretty(rules).expect("rules are always valid JSON")
+		}
+	}
+}
+
+/// Create a group. Returns `None` if there's already a group with this name.
+pub async fn create_group(
+	source_id: i32,
+	name: &str,
+	default_action: AutomodRuleAction,
+) -> Result<Option<i32>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"INSERT INTO automod_rule_groups (source_id, group_name, default_action) VALUES ($1, $2, \
+		 $3) ON CONFLICT ON CONSTRAINT unique_group_name DO NOTHING RETURNING item_id",
+		source_id,
+		name,
+		default_action as i16
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	.map(|row| row.item_id))
+}
+
+/// Find a group by its name.
+pub async fn get_group(source_id: i32, name: &str) -> Result<Option<RuleGroupInfo>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"SELECT g.item_id, g.group_name, g.default_action, g.enabled, COUNT(r.item_id) AS \
+		 \"rule_count!\" FROM automod_rule_groups g LEFT JOIN automod_rules r ON r.group_id = \
+		 g.item_id WHERE g.source_id = $1 AND g.group_name = $2 GROUP BY g.item_id",
+		source_id,
+		name
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	.map(|row| RuleGroupInfo {
+		id:             row.item_id,
+		name:           row.group_name,
+		default_action: row.default_action.into(),
+		enabled:        row.enabled,
+		rule_count:     row.rule_count,
+	}))
+}
+
+/// List every group in a server, oldest first.
+pub async fn list_groups(source_id: i32) -> Result<Vec<RuleGroupInfo>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"SELECT g.item_id, g.group_name, g.default_action, g.enabled, COUNT(r.item_id) AS \
+		 \"rule_count!\" FROM automod_rule_groups g LEFT JOIN automod_rules r ON r.group_id = \
+		 g.item_id WHERE g.source_id = $1 GROUP BY g.item_id ORDER BY g.item_id",
+		source_id
+	)
+	.fetch_all(scripty_db::get_db())
+	.await?
+	.into_iter()
+	.map(|row| RuleGroupInfo {
+		id:             row.item_id,
+		name:           row.group_name,
+		default_action: row.default_action.into(),
+		enabled:        row.enabled,
+		rule_count:     row.rule_count,
+	})
+	.collect())
+}
+
+/// Rename a group. Returns `false` if there's already another group with the new name.
+pub async fn rename_group(source_id: i32, group_id: i32, name: &str) -> Result<bool, sqlx::Error> {
+	let res = sqlx::query!(
+		"UPDATE automod_rule_groups SET group_name = $3 WHERE item_id = $2 AND source_id = $1 AND \
+		 NOT EXISTS (SELECT 1 FROM automod_rule_groups WHERE source_id = $1 AND group_name = $3)",
+		source_id,
+		group_id,
+		name
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	Ok(res.rows_affected() != 0)
+}
+
+/// Change the action every rule in a group takes.
+pub async fn set_default_action(
+	group_id: i32,
+	default_action: AutomodRuleAction,
+) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"UPDATE automod_rule_groups SET default_action = $2 WHERE item_id = $1",
+		group_id,
+		default_action as i16
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	Ok(())
+}
+
+/// Turn every rule in a group on or off.
+pub async fn set_enabled(group_id: i32, enabled: bool) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"UPDATE automod_rule_groups SET enabled = $2 WHERE item_id = $1",
+		group_id,
+		enabled
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	Ok(())
+}
+
+/// Delete a group, and every rule in it.
+pub async fn delete_group(group_id: i32) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"DELETE FROM automod_rule_groups WHERE item_id = $1",
+		group_id
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	Ok(())
+}
+
+/// Add rules to a group, all at once.
+///
+/// Rules that already exist in the server with the same action are skipped.
+/// Returns how many rules were actually added.
+pub async fn import_rules(
+	source_id: i32,
+	group: &RuleGroupInfo,
+	rules: &[RuleFileEntry],
+) -> Result<u64, sqlx::Error> {
+	let rule_types = rules.iter().map(|r| r.rule_type as i16).collect::<Vec<_>>();
+	let rule_data = rules
+		.iter()
+		.map(|r| r.rule_data.clone())
+		.collect::<Vec<_>>();
+
+	let res = sqlx::query!(
+		"INSERT INTO automod_rules (source_id, group_id, rule_type, rule_data, rule_action) \
+		 SELECT $1, $2, r.rule_type, r.rule_data, $3 FROM UNNEST($4::SMALLINT[], $5::TEXT[]) AS \
+		 r(rule_type, rule_data) ON CONFLICT ON CONSTRAINT unique_rule DO NOTHING",
+		source_id,
+		group.id,
+		group.default_action as i16,
+		&rule_types,
+		&rule_data
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	Ok(res.rows_affected())
+}
+
+/// Every rule in a group, in the order they were added.
+pub async fn export_rules(group_id: i32) -> Result<Vec<RuleFileEntry>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"SELECT rule_type, rule_data FROM automod_rules WHERE group_id = $1 ORDER BY item_id",
+		group_id
+	)
+	.fetch_all(scripty_db::get_db())
+	.await?
+	.into_iter()
+	.map(|row| RuleFileEntry {
+		rule_type: row.rule_type.into(),
+		rule_data: row.rule_data,
+	})
+	.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rule_file_round_trip() {
+		let csv = b"rule_type,rule_data\nregular,bad word\nwhole_word,\"ass, again\"\n";
+		let rules = parse_rules(csv, RuleFileFormat::Csv).expect("valid CSV");
+		assert_eq!(rules.len(), 2);
+		assert!(matches!(rules[1].rule_type, AutomodRuleType::WholeWord));
+		assert_eq!(rules[1].rule_data, "ass, again");
+
+		for format in [RuleFileFormat::Csv, RuleFileFormat::Json] {
+			let written = write_rules(&rules, format);
+			let read = parse_rules(&written, format).expect("rules should read back");
+			assert_eq!(
+				read.iter().map(|r| &r.rule_data).collect::<Vec<_>>(),
+				rules.iter().map(|r| &r.rule_data).collect::<Vec<_>>()
+			);
+		}
+
+		assert!(parse_rules(
+			b"[{\"rule_type\": \"nope\", \"rule_data\": \"x\"}]",
+			RuleFileFormat::Json
+		)
+		.is_err());
+	}
+}
@@ -1,3 +1,10 @@
+#[macro_use]
+extern crate tracing;
+
 pub mod db;
+pub mod groups;
+pub mod matcher;
+pub mod rule_set;
+pub mod strikes;
 pub mod types;
 pub mod utils;
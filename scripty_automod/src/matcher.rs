@@ -0,0 +1,267 @@
+use std::cell::OnceCell;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{rule_set::pad_words, types::AutomodRuleType};
+
+/// Largest compiled size a regex rule may have, so one rule can't slow down every transcript.
+const MAX_REGEX_SIZE: usize = 1 << 16;
+
+/// Why a rule couldn't be compiled.
+#[derive(Debug)]
+pub enum InvalidRule {
+	/// The rule had no letters or numbers to match on,
+	/// or is a regex that matches an empty message, and so every message.
+	Empty,
+	/// A regex rule wasn't a valid regex, or was too large.
+	Regex(regex::Error),
+}
+
+impl std::fmt::Display for InvalidRule {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			InvalidRule::Empty => {
+				f.write_str("rule has no letters or numbers in it, or would match every message")
+			}
+			InvalidRule::Regex(e) => write!(f, "{}", e),
+		}
+	}
+}
+
+impl std::error::Error for InvalidRule {}
+
+/// A rule compiled so messages can be checked against it quickly.
+#[derive(Debug, Clone)]
+pub enum RuleMatcher {
+	/// Matches if the message contains this anywhere, ignoring case.
+	Substring(String),
+	/// Matches if the message contains these words one after another, ignoring case and
+	/// punctuation.
+	WholeWord(Vec<String>),
+	/// Matches if the regex matches anywhere in the message, ignoring case.
+	Regex(Regex),
+	/// Matches if the message contains words that sound like, or are spelled close to,
+	/// these words one after another.
+	///
+	/// Each word is stored alongside its phonetic key.
+	Fuzzy(Vec<(String, String)>),
+}
+
+impl RuleMatcher {
+	pub fn compile(rule_type: AutomodRuleType, rule_data: &str) -> Result<Self, InvalidRule> {
+		let matcher = match rule_type {
+			AutomodRuleType::Regular => {
+				let rule = rule_data.to_lowercase();
+				if rule.trim().is_empty() {
+					return Err(InvalidRule::Empty);
+				}
+				RuleMatcher::Substring(rule)
+			}
+			AutomodRuleType::WholeWord => RuleMatcher::WholeWord(normalized_words(rule_data)),
+			AutomodRuleType::Regex => RuleMatcher::Regex(
+				RegexBuilder::new(rule_data)
+					.case_insensitive(true)
+					.size_limit(MAX_REGEX_SIZE)
+					.build()
+					.map_err(InvalidRule::Regex)?,
+			),
+			AutomodRuleType::Fuzzy => RuleMatcher::Fuzzy(
+				normalized_words(rule_data)
+					.into_iter()
+					.map(|w| {
+						let key = phonetic_key(&w);
+						(w, key)
+					})
+					.collect(),
+			),
+		};
+
+		match &matcher {
+			RuleMatcher::WholeWord(words) if words.is_empty() => Err(InvalidRule::Empty),
+			RuleMatcher::Fuzzy(words) if words.is_empty() => Err(InvalidRule::Empty),
+			RuleMatcher::Regex(regex) if regex.is_match("") => Err(InvalidRule::Empty),
+			_ => Ok(matcher),
+		}
+	}
+
+	pub fn is_match(&self, msg: &PreparedMessage) -> bool {
+		match self {
+			RuleMatcher::Substring(rule) => msg.lowercase.contains(rule.as_str()),
+			RuleMatcher::WholeWord(rule) => msg
+				.words
+				.windows(rule.len())
+				.any(|window| window == rule.as_slice()),
+			RuleMatcher::Regex(rule) => rule.is_match(msg.original),
+			RuleMatcher::Fuzzy(rule) => {
+				let phonetic = msg.phonetic();
+				(0..msg.words.len().saturating_sub(rule.len() - 1)).any(|start| {
+					rule.iter().enumerate().all(|(idx, (word, key))| {
+						let i = start + idx;
+						// speech to text rarely gets the first sound of a word wrong,
+						// so typos there are much more likely to be a different word entirely
+						phonetic[i] == *key
+							|| (phonetic[i].chars().next() == key.chars().next()
+								&& levenshtein(&msg.words[i], word) <= max_typos(word))
+					})
+				})
+			}
+		}
+	}
+}
+
+/// A message, pre-processed once so it can be checked against many rules.
+pub struct PreparedMessage<'a> {
+	original:     &'a str,
+	lowercase:    String,
+	words:        Vec<String>,
+	/// `words`, joined and surrounded by spaces.
+	padded_words: String,
+	/// Phonetic keys of each word, only worked out if a fuzzy rule needs them.
+	phonetic:     OnceCell<Vec<String>>,
+}
+
+impl<'a> PreparedMessage<'a> {
+	pub fn new(msg: &'a str) -> Self {
+		let words = normalized_words(msg);
+		Self {
+			original: msg,
+			lowercase: msg.to_lowercase(),
+			padded_words: pad_words(&words),
+			words,
+			phonetic: OnceCell::new(),
+		}
+	}
+
+	pub fn lowercase(&self) -> &str {
+		&self.lowercase
+	}
+
+	pub fn padded_words(&self) -> &str {
+		&self.padded_words
+	}
+
+	fn phonetic(&self) -> &[String] {
+		self.phonetic
+			.get_or_init(|| self.words.iter().map(|w| phonetic_key(w)).collect())
+	}
+}
+
+/// Lowercase words with everything that isn't a letter or number stripped.
+fn normalized_words(text: &str) -> Vec<String> {
+	text.split_whitespace()
+		.map(|w| {
+			w.chars()
+				.filter(|c| c.is_alphanumeric())
+				.flat_map(char::to_lowercase)
+				.collect::<String>()
+		})
+		.filter(|w| !w.is_empty())
+		.collect()
+}
+
+/// How many typos a word can have and still fuzzily match.
+///
+/// Short words get none, as almost any other short word is one or two edits away.
+fn max_typos(word: &str) -> usize {
+	match word.chars().count() {
+		0..=3 => 0,
+		4..=7 => 1,
+		_ => 2,
+	}
+}
+
+/// A key that is the same for words that sound alike, based on Soundex.
+///
+/// Unlike Soundex, the first letter is encoded too (so "phudge" and "fudge" match),
+/// and keys aren't padded or cut to a fixed length.
+fn phonetic_key(word: &str) -> String {
+	let mut key = String::with_capacity(word.len());
+	let mut last = None;
+	for (idx, c) in word.chars().enumerate() {
+		let code = match c {
+			'b' | 'f' | 'p' | 'v' => Some('1'),
+			'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+			'd' | 't' => Some('3'),
+			'l' => Some('4'),
+			'm' | 'n' => Some('5'),
+			'r' => Some('6'),
+			// a leading vowel is kept, so "ass" and "sass" don't match
+			'a' | 'e' | 'i' | 'o' | 'u' | 'y' if idx == 0 => Some('0'),
+			// "h" and "w" don't separate letters with the same code
+			'h' | 'w' => continue,
+			// other vowels do, and anything else (digits, other scripts) is kept as-is
+			'a' | 'e' | 'i' | 'o' | 'u' | 'y' => None,
+			c => Some(c),
+		};
+		if code.is_some() && code != last {
+			key.extend(code);
+		}
+		last = code;
+	}
+	key
+}
+
+/// Number of single character insertions, deletions or substitutions to turn `a` into `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+	let mut prev = (0..=b.len()).collect::<Vec<_>>();
+	let mut cur = vec![0; b.len() + 1];
+	for (i, ca) in a.chars().enumerate() {
+		cur[0] = i + 1;
+		for (j, cb) in b.iter().enumerate() {
+			let substitution = prev[j] + usize::from(ca != *cb);
+			cur[j + 1] = substitution.min(prev[j + 1] + 1).min(cur[j] + 1);
+		}
+		std::mem::swap(&mut prev, &mut cur);
+	}
+	prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn matches(rule_type: AutomodRuleType, rule: &str, msg: &str) -> bool {
+		RuleMatcher::compile(rule_type, rule)
+			.expect("rule should compile")
+			.is_match(&PreparedMessage::new(msg))
+	}
+
+	#[test]
+	fn test_whole_word() {
+		assert!(matches(AutomodRuleType::WholeWord, "ass", "what an Ass!"));
+		assert!(!matches(
+			AutomodRuleType::WholeWord,
+			"ass",
+			"a classic pass"
+		));
+		assert!(matches(
+			AutomodRuleType::WholeWord,
+			"bad word",
+			"that's a BAD, word"
+		));
+	}
+
+	#[test]
+	fn test_regex() {
+		assert!(matches(AutomodRuleType::Regex, r"\bf+u+n+\b", "so FUUUN"));
+		assert!(!matches(AutomodRuleType::Regex, r"\bf+u+n+\b", "funny"));
+		assert!(RuleMatcher::compile(AutomodRuleType::Regex, "(unclosed").is_err());
+
+		// these match every message
+		for rule in ["", "a*", "x|", "^", ".*"] {
+			assert!(matches!(
+				RuleMatcher::compile(AutomodRuleType::Regex, rule),
+				Err(InvalidRule::Empty)
+			));
+		}
+	}
+
+	#[test]
+	fn test_fuzzy() {
+		assert!(matches(AutomodRuleType::Fuzzy, "fudge", "oh phudge"));
+		assert!(matches(AutomodRuleType::Fuzzy, "banana", "a bananna split"));
+		assert!(!matches(AutomodRuleType::Fuzzy, "fudge", "the judge said"));
+		assert!(!matches(AutomodRuleType::Fuzzy, "ass", "sass"));
+	}
+}
@@ -0,0 +1,222 @@
+//! Counting automod violations per user, so repeat offenders get harsher actions.
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use crate::types::AutomodRuleAction;
+
+/// What to do to a user that triggered a rule, from least to most severe.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Punishment {
+	/// Only log the message.
+	Log,
+	/// Server mute the user.
+	Mute,
+	/// Remove the user from the voice channel.
+	Kick,
+	/// Remove the user from the voice channel, and time them out.
+	Timeout,
+}
+
+impl Punishment {
+	/// The punishment a rule's action asks for on its own, without any strikes.
+	///
+	/// Returns `None` for [`AutomodRuleAction::SilentDelete`], which doesn't punish the user at all.
+	pub fn for_action(action: AutomodRuleAction) -> Option<Self> {
+		match action {
+			AutomodRuleAction::SilentDelete => None,
+			AutomodRuleAction::DeleteAndLog => Some(Self::Log),
+			AutomodRuleAction::DeleteLogAndSilence => Some(Self::Mute),
+			AutomodRuleAction::DeleteLogAndKick => Some(Self::Kick),
+		}
+	}
+}
+
+/// Allowed values of `strike_decay_hours`.
+pub const STRIKE_DECAY_HOURS_RANGE: RangeInclusive<i32> = 1..=720;
+/// Allowed values of the mute, kick and timeout strike thresholds.
+pub const STRIKE_THRESHOLD_RANGE: RangeInclusive<i32> = 0..=100;
+/// Allowed values of `timeout_minutes`. Discord doesn't allow timeouts longer than 28 days.
+pub const TIMEOUT_MINUTES_RANGE: RangeInclusive<i32> = 1..=40_320;
+/// Allowed values of `mute_minutes`.
+pub const MUTE_MINUTES_RANGE: RangeInclusive<i32> = 0..=40_320;
+
+/// How a server escalates the action taken against repeat offenders.
+#[derive(Debug, Clone)]
+pub struct StrikeConfig {
+	/// If disabled, the rule's action is always taken as-is.
+	pub enabled:           bool,
+	/// Strikes older than this no longer count.
+	pub decay:             Duration,
+	/// Number of strikes at which a user is muted. 0 to never escalate to a mute.
+	pub mute_threshold:    u32,
+	/// Number of strikes at which a user is removed from voice. 0 to never escalate to a kick.
+	pub kick_threshold:    u32,
+	/// Number of strikes at which a user is timed out. 0 to never escalate to a timeout.
+	pub timeout_threshold: u32,
+	/// How long a timeout lasts.
+	pub timeout_duration:  Duration,
+	/// How long until a muted user is unmuted. `None` to keep them muted until someone unmutes
+	/// them.
+	///
+	/// This applies to every mute, whether or not strikes are enabled.
+	/// See [`schedule_unmute`].
+	pub mute_duration:     Option<Duration>,
+}
+
+impl Default for StrikeConfig {
+	fn default() -> Self {
+		Self {
+			enabled:           false,
+			decay:             Duration::from_secs(24 * 60 * 60),
+			mute_threshold:    2,
+			kick_threshold:    3,
+			timeout_threshold: 5,
+			timeout_duration:  Duration::from_secs(10 * 60),
+			mute_duration:     None,
+		}
+	}
+}
+
+impl StrikeConfig {
+	/// The most severe punishment a user with this many strikes has earned.
+	pub fn punishment_for_strikes(&self, strikes: u32) -> Punishment {
+		let reached = |threshold: u32| threshold != 0 && strikes >= threshold;
+		if reached(self.timeout_threshold) {
+			Punishment::Timeout
+		} else if reached(self.kick_threshold) {
+			Punishment::Kick
+		} else if reached(self.mute_threshold) {
+			Punishment::Mute
+		} else {
+			Punishment::Log
+		}
+	}
+
+	/// The punishment for a user that triggered a rule with this action, and now has this many
+	/// strikes: whichever of the rule's action and the strikes is more severe.
+	pub fn escalate(&self, punishment: Punishment, strikes: u32) -> Punishment {
+		if self.enabled {
+			punishment.max(self.punishment_for_strikes(strikes))
+		} else {
+			punishment
+		}
+	}
+}
+
+/// Give a user a strike, and return how many strikes they now have.
+///
+/// Strikes older than `decay` are deleted, and don't count.
+pub async fn add_strike(source_id: i32, user_id: u64, decay: Duration) -> Result<u32, sqlx::Error> {
+	let db = scripty_db::get_db();
+	let decay_secs = decay.as_secs_f64();
+
+	sqlx::query!(
+		"DELETE FROM automod_strikes WHERE source_id = $1 AND user_id = $2 AND created_at < NOW() \
+		 - make_interval(secs => $3)",
+		source_id,
+		user_id as i64,
+		decay_secs
+	)
+	.execute(db)
+	.await?;
+
+	sqlx::query!(
+		"INSERT INTO automod_strikes (source_id, user_id) VALUES ($1, $2)",
+		source_id,
+		user_id as i64
+	)
+	.execute(db)
+	.await?;
+
+	let count = sqlx::query!(
+		r#"SELECT COUNT(*) AS "count!" FROM automod_strikes WHERE source_id = $1 AND user_id = $2"#,
+		source_id,
+		user_id as i64
+	)
+	.fetch_one(db)
+	.await?
+	.count;
+
+	Ok(count as u32)
+}
+
+/// Record when a user automod just muted should be unmuted, replacing any earlier mute's deadline.
+///
+/// With a `duration` of `None` the user stays muted until someone unmutes them,
+/// so any pending unmute from an earlier mute is cancelled.
+pub async fn schedule_unmute(
+	source_id: i32,
+	user_id: u64,
+	duration: Option<Duration>,
+) -> Result<(), sqlx::Error> {
+	let db = scripty_db::get_db();
+
+	match duration {
+		Some(duration) => {
+			sqlx::query!(
+				"INSERT INTO automod_mutes (source_id, user_id, unmute_at) VALUES ($1, $2, NOW() \
+				 + make_interval(secs => $3)) ON CONFLICT (source_id, user_id) DO UPDATE SET \
+				 unmute_at = EXCLUDED.unmute_at",
+				source_id,
+				user_id as i64,
+				duration.as_secs_f64()
+			)
+			.execute(db)
+			.await?;
+		}
+		None => {
+			sqlx::query!(
+				"DELETE FROM automod_mutes WHERE source_id = $1 AND user_id = $2",
+				source_id,
+				user_id as i64
+			)
+			.execute(db)
+			.await?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Remove every mute whose time is up, and return the `(guild_id, user_id)` of each user to unmute.
+pub async fn take_expired_mutes() -> Result<Vec<(u64, u64)>, sqlx::Error> {
+	let db = scripty_db::get_db();
+
+	let expired = sqlx::query!(
+		"DELETE FROM automod_mutes USING automod_config WHERE automod_mutes.source_id = \
+		 automod_config.item_id AND automod_mutes.unmute_at <= NOW() RETURNING \
+		 automod_config.guild_id, automod_mutes.user_id"
+	)
+	.fetch_all(db)
+	.await?;
+
+	Ok(expired
+		.into_iter()
+		.map(|row| (row.guild_id as u64, row.user_id as u64))
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_escalate() {
+		let cfg = StrikeConfig {
+			enabled: true,
+			kick_threshold: 0,
+			..Default::default()
+		};
+
+		assert_eq!(cfg.escalate(Punishment::Log, 1), Punishment::Log);
+		assert_eq!(cfg.escalate(Punishment::Log, 2), Punishment::Mute);
+		// kicks are disabled, so strikes skip straight from muting to timing out
+		assert_eq!(cfg.escalate(Punishment::Log, 4), Punishment::Mute);
+		assert_eq!(cfg.escalate(Punishment::Log, 5), Punishment::Timeout);
+		// a rule's own action is never made less severe
+		assert_eq!(cfg.escalate(Punishment::Kick, 2), Punishment::Kick);
+
+		let disabled = StrikeConfig::default();
+		assert_eq!(disabled.escalate(Punishment::Log, 10), Punishment::Log);
+	}
+}
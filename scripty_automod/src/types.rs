@@ -1,16 +1,29 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{rule_set::RuleSet, strikes::StrikeConfig};
 
 #[repr(i16)]
-#[derive(Debug, poise::ChoiceParameter, Copy, Clone)]
+#[derive(Debug, poise::ChoiceParameter, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum AutomodRuleType {
-	Regular = 1,
+	Regular   = 1,
+	#[name = "Whole word"]
+	WholeWord = 2,
+	Regex     = 3,
+	#[name = "Fuzzy match"]
+	Fuzzy     = 4,
 }
 
 impl From<i16> for AutomodRuleType {
 	fn from(value: i16) -> Self {
 		match value {
 			1 => AutomodRuleType::Regular,
+			2 => AutomodRuleType::WholeWord,
+			3 => AutomodRuleType::Regex,
+			4 => AutomodRuleType::Fuzzy,
 			_ => panic!("invalid value for AutomodRuleType"),
 		}
 	}
@@ -43,16 +56,26 @@ impl From<i16> for AutomodRuleAction {
 
 #[derive(Debug, Clone)]
 pub struct AutomodRule {
+	/// ID of the rule in the database.
+	pub rule_id:     i32,
 	pub rule_type:   AutomodRuleType,
 	pub rule_data:   String,
 	pub rule_action: AutomodRuleAction,
+	/// Channels the rule applies in. Empty if it applies everywhere.
+	pub channel_ids: Vec<u64>,
 }
 
+/// A named group of rules that all share one action, and can be turned on and off together.
 #[derive(Debug, Clone)]
 pub struct AutomodRuleGroup {
-	pub group_id:   i64,
-	pub group_name: String,
-	pub rules:      Vec<String>,
+	pub group_id:       i32,
+	pub group_name:     String,
+	/// The action every rule in this group takes.
+	pub default_action: AutomodRuleAction,
+	/// Rules in a disabled group are not checked.
+	pub enabled:        bool,
+	/// The content of every rule in this group.
+	pub rules:          Vec<String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -61,69 +84,61 @@ pub struct AutomodServerConfig {
 	pub internal_id:     i32,
 	pub enabled:         bool,
 	pub groups:          Vec<AutomodRuleGroup>,
-	rules:               Vec<AutomodRule>,
-	rule_action_map:     HashMap<String, AutomodRuleAction>,
-	rule_array:          Vec<String>,
+	/// Set with [`Self::with_rules`] or [`Self::add_rule`], which keep `rule_set` in sync.
+	pub(crate) rules:    Vec<AutomodRule>,
+	pub(crate) rule_set: RuleSet,
 	pub log_channel_id:  u64,
 	pub log_recording:   bool,
 	pub auto_join_voice: bool,
+	pub strikes:         StrikeConfig,
+	/// Only log what would have been done when a rule matches, without deleting the message or
+	/// punishing the user.
+	pub monitor_only:    bool,
+	/// Users with any of these roles are never actioned.
+	pub exempt_roles:    HashSet<u64>,
+	/// Channels automod is off in.
+	pub exempt_channels: HashSet<u64>,
 }
 
 impl AutomodServerConfig {
-	pub fn new(
-		guild_id: u64,
-		internal_id: i32,
-		enabled: bool,
-		groups: Vec<AutomodRuleGroup>,
-		rules: Vec<AutomodRule>,
-		log_channel_id: u64,
-		log_recording: bool,
-		auto_join_voice: bool,
-	) -> Self {
-		let mut rule_action_map = HashMap::new();
-		for rule in &rules {
-			rule_action_map.insert(rule.rule_data.clone(), rule.rule_action);
-		}
-		let rule_array = rules.iter().map(|r| r.rule_data.clone()).collect();
-
-		Self {
-			guild_id,
-			internal_id,
-			enabled,
-			groups,
-			rules,
-			rule_action_map,
-			rule_array,
-			log_channel_id,
-			log_recording,
-			auto_join_voice,
-		}
+	/// Replace all rules, compiling them into one rule set.
+	pub fn with_rules(mut self, rules: Vec<AutomodRule>) -> Self {
+		self.rule_set = RuleSet::compile(&rules);
+		self.rules = rules;
+		self
 	}
 
+	/// Add a rule, recompiling the rule set.
+	///
+	/// This rebuilds the whole rule set, so when loading many rules at once,
+	/// pass them all to [`Self::with_rules`] instead.
 	pub fn add_rule(&mut self, rule: AutomodRule) {
-		self.rule_action_map
-			.insert(rule.rule_data.clone(), rule.rule_action);
-		self.rule_array.push(rule.rule_data.clone());
 		self.rules.push(rule);
+		self.rule_set = RuleSet::compile(&self.rules);
 	}
 
-	pub fn get_action(&self, msg: &str) -> Option<AutomodRuleAction> {
-		if !self.enabled {
+	/// Find the action to take on a message sent in a channel, skipping rules scoped to other
+	/// channels.
+	pub fn get_action(&self, msg: &str, channel_id: u64) -> Option<AutomodRuleAction> {
+		if !self.enabled || self.exempt_channels.contains(&channel_id) {
 			return None;
 		}
 
-		let m = msg.to_lowercase();
-		for rule in &self.rule_array {
-			if m.contains(rule) {
-				return Some(
-					self.rule_action_map
-						.get(rule)
-						.copied()
-						.unwrap_or(AutomodRuleAction::SilentDelete),
-				);
-			}
-		}
+		self.rule_set.first_match(msg, channel_id)
+	}
+
+	/// Whether a user with these roles is exempt from automod.
+	pub fn is_exempt(&self, role_ids: impl IntoIterator<Item = u64>) -> bool {
+		role_ids
+			.into_iter()
+			.any(|role_id| self.exempt_roles.contains(&role_id))
+	}
 
-		None
+	/// Find the rule that would fire on a message, even if automod is disabled,
+	/// in any channel.
+	pub fn get_matching_rule(&self, msg: &str) -> Option<&AutomodRule> {
+		self.rule_set
+			.first_match_index(msg)
+			.map(|idx| &self.rules[idx])
 	}
 }
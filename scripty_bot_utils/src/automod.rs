@@ -0,0 +1,170 @@
+//! Automod for transcripts of voice messages and audio or video attachments.
+//!
+//! Users can't be muted or removed from a voice channel for a message,
+//! so any punishment harsher than logging is a timeout instead.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use scripty_automod::{strikes::Punishment, types::AutomodServerConfig};
+use serenity::{
+	all::{ChannelId, Context, EditMember, Message, Timestamp},
+	builder::{CreateEmbed, CreateMessage},
+};
+
+/// Longest a transcript can be in a log message before it is cut short.
+const MAX_LOGGED_TRANSCRIPT_LENGTH: usize = 1000;
+
+/// Run automod on the transcript of a message.
+///
+/// Returns `true` if the message was deleted, in which case its transcript shouldn't be sent.
+pub async fn check_message_transcript(ctx: &Context, msg: &Message, transcript: &str) -> bool {
+	let Some(guild_id) = msg.guild_id else {
+		return false;
+	};
+	let cfg = match scripty_automod::db::get_cached_guild_config(guild_id.get()).await {
+		Ok(Some(cfg)) => cfg,
+		Ok(None) => return false,
+		Err(e) => {
+			error!(%msg.id, "failed to fetch automod config: {}", e);
+			return false;
+		}
+	};
+	let Some(action) = cfg.get_action(transcript, msg.channel_id.get()) else {
+		return false;
+	};
+	if msg
+		.member
+		.as_ref()
+		.is_some_and(|member| cfg.is_exempt(member.roles.iter().map(|role| role.get())))
+	{
+		trace!(%msg.id, "message author has an automod exempt role, skipping");
+		return false;
+	}
+	trace!(?action, %msg.id, "automod action taken on message transcript");
+
+	let user_id = msg.author.id;
+	let punishment = Punishment::for_action(action);
+	if cfg.monitor_only {
+		// only log what would have happened
+		let would_have = match punishment {
+			None => "Silently deleted message",
+			Some(Punishment::Log) => "Deleted message",
+			Some(_) => "Deleted message and timed out user",
+		};
+		send_log(
+			ctx,
+			&cfg,
+			msg,
+			transcript,
+			format!("Would have: {}", would_have),
+		)
+		.await;
+		return false;
+	}
+
+	if let Err(e) = msg.delete(ctx).await {
+		error!(%msg.id, "failed to delete message: {}", e);
+	}
+	let Some(punishment) = punishment else {
+		return true; // silent delete, don't need to do anything more
+	};
+
+	// escalate the punishment for repeat offenders
+	let strikes = &cfg.strikes;
+	let strike_count = if strikes.enabled {
+		match scripty_automod::strikes::add_strike(cfg.internal_id, user_id.get(), strikes.decay)
+			.await
+		{
+			Ok(count) => Some(count),
+			Err(e) => {
+				error!("failed to add automod strike: {}", e);
+				None
+			}
+		}
+	} else {
+		None
+	};
+
+	let action_taken = match strikes.escalate(punishment, strike_count.unwrap_or(0)) {
+		Punishment::Log => "Deleted message".to_string(),
+		punishment => {
+			// a mute only lasts as long as mutes are set to, if they don't last forever
+			let duration = match punishment {
+				Punishment::Mute => strikes.mute_duration.unwrap_or(strikes.timeout_duration),
+				_ => strikes.timeout_duration,
+			};
+			if let Err(e) = guild_id
+				.edit_member(
+					ctx,
+					user_id,
+					EditMember::new().disable_communication_until_datetime(timeout_end(duration)),
+				)
+				.await
+			{
+				error!("failed to time out user: {}", e);
+			}
+			format!(
+				"Deleted message and timed out user for {} minutes",
+				duration.as_secs() / 60
+			)
+		}
+	};
+
+	let strike_details = strike_count
+		.map(|count| format!("\nStrikes: {}", count))
+		.unwrap_or_default();
+	send_log(
+		ctx,
+		&cfg,
+		msg,
+		transcript,
+		format!("{}{}", action_taken, strike_details),
+	)
+	.await;
+
+	true
+}
+
+/// When a timeout starting now should end.
+fn timeout_end(duration: Duration) -> Timestamp {
+	Timestamp::from_unix_timestamp(
+		(SystemTime::now() + duration)
+			.duration_since(UNIX_EPOCH)
+			.unwrap_or_default()
+			.as_secs() as i64,
+	)
+	.expect("now plus at most 28 days is a valid timestamp")
+}
+
+async fn send_log(
+	ctx: &Context,
+	cfg: &AutomodServerConfig,
+	msg: &Message,
+	transcript: &str,
+	action_taken: String,
+) {
+	let transcript = match transcript.char_indices().nth(MAX_LOGGED_TRANSCRIPT_LENGTH) {
+		Some((idx, _)) => format!("{}...", &transcript[..idx]),
+		None => transcript.to_string(),
+	};
+
+	let embed = CreateEmbed::new()
+		.title(if cfg.monitor_only {
+			"User said a forbidden word in a message (monitor only)"
+		} else {
+			"User said a forbidden word in a message"
+		})
+		.description(format!(
+			"{}\nUser: <@{}>\nMessage: {}\nDetected word: {}",
+			action_taken,
+			msg.author.id,
+			msg.link(),
+			transcript
+		));
+	if let Err(e) = ChannelId::from(cfg.log_channel_id)
+		.send_message(ctx, CreateMessage::new().embed(embed))
+		.await
+	{
+		error!("failed to send log message: {}", e);
+	}
+}
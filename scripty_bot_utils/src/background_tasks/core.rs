@@ -69,4 +69,6 @@ pub fn init_background_tasks(ctx: Context) {
 	init_task!(crate::background_tasks::tasks::CommandLatencyClearer, ctx);
 	init_task!(crate::background_tasks::tasks::BotListUpdater, ctx);
 	init_task!(crate::background_tasks::tasks::VoteReminderTask, ctx);
+	init_task!(crate::background_tasks::tasks::TranscriptPruner, ctx);
+	init_task!(crate::background_tasks::tasks::AutomodUnmuter, ctx);
 }
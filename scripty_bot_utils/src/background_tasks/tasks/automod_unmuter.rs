@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use serenity::{all::GuildId, builder::EditMember, client::Context};
+
+use crate::{background_tasks::core::BackgroundTask, Error};
+
+/// Unmutes users automod muted once their mute is up, checking every minute.
+pub struct AutomodUnmuter {
+	ctx: Context,
+}
+
+#[async_trait]
+impl BackgroundTask for AutomodUnmuter {
+	async fn init(ctx: Context) -> Result<Self, Error> {
+		Ok(Self { ctx })
+	}
+
+	fn interval(&mut self) -> Duration {
+		Duration::from_secs(60)
+	}
+
+	async fn run(&mut self) {
+		let expired = match scripty_automod::strikes::take_expired_mutes().await {
+			Ok(expired) => expired,
+			Err(e) => {
+				error!("failed to fetch expired automod mutes: {}", e);
+				return;
+			}
+		};
+
+		for (guild_id, user_id) in expired {
+			if let Err(e) = GuildId::new(guild_id)
+				.edit_member(&self.ctx, user_id, EditMember::new().mute(false))
+				.await
+			{
+				error!(%guild_id, %user_id, "failed to unmute user: {}", e);
+			}
+		}
+	}
+}
@@ -1,13 +1,17 @@
+mod automod_unmuter;
 mod basic_stats_update;
 mod bot_list_poster;
 mod bot_vote_reminder;
 mod cmd_latency_clear;
 mod prometheus_latency_update;
 mod status_update;
+mod transcript_pruner;
 
+pub use automod_unmuter::*;
 pub use basic_stats_update::*;
 pub use bot_list_poster::*;
 pub use bot_vote_reminder::*;
 pub use cmd_latency_clear::*;
 pub use prometheus_latency_update::*;
 pub use status_update::*;
+pub use transcript_pruner::*;
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serenity::client::Context;
+
+use crate::{background_tasks::core::BackgroundTask, Error};
+
+/// Deletes archived transcripts past their guild's retention period every hour.
+pub struct TranscriptPruner;
+
+#[async_trait]
+impl BackgroundTask for TranscriptPruner {
+	async fn init(_: Context) -> Result<Self, Error> {
+		Ok(Self)
+	}
+
+	fn interval(&mut self) -> Duration {
+		Duration::from_secs(3600)
+	}
+
+	async fn run(&mut self) {
+		match scripty_audio_handler::prune_expired_sessions().await {
+			Ok(deleted) => debug!("pruned {} expired transcript sessions", deleted),
+			Err(e) => error!("failed to prune expired transcript sessions: {}", e),
+		}
+	}
+}
@@ -256,6 +256,8 @@ impl From<scripty_audio_handler::Error> for Error {
 			scripty_audio_handler::ErrorKind::Join(e) => Self::join(e),
 			scripty_audio_handler::ErrorKind::Database(e) => Self::db(e),
 			scripty_audio_handler::ErrorKind::Serenity(e) => Self::serenity(e),
+			scripty_audio_handler::ErrorKind::Tts(e) => Self::custom(e.to_string()),
+			scripty_audio_handler::ErrorKind::TtsTask(e) => Self::custom(e.to_string()),
 		};
 		err.bt = e.backtrace;
 		err
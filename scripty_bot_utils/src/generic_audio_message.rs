@@ -197,6 +197,23 @@ pub async fn handle_message(ctx: Context, msg: Message) -> Result<(), GenericMes
 		return Ok(());
 	}
 
+	// run automod on everything that was said, and don't send the transcripts if the message
+	// was deleted for it
+	let full_transcript = transcripts
+		.iter()
+		.filter_map(|transcript| match transcript {
+			TranscriptResult::Success { transcript, .. } => Some(transcript.as_str()),
+			_ => None,
+		})
+		.collect::<Vec<_>>()
+		.join("\n");
+	if !full_transcript.is_empty()
+		&& crate::automod::check_message_transcript(&ctx, &msg, &full_transcript).await
+	{
+		new_msg.delete(&ctx).await?;
+		return Ok(());
+	}
+
 	// massage the transcripts into a message
 	let mut msg_builder = EditMessage::new();
 	if transcripts.len() == 1
@@ -450,10 +467,10 @@ async fn handle_transcripts(
 		let stream = scripty_stt::get_stream().await?;
 
 		stream.feed_audio(i16_audio)?;
-		let transcript = stream
-			.get_result(language.clone(), false, translate)
-			.await?;
-		let transcript = transcript.trim();
+		let transcript =
+			scripty_stt::get_result_with_retries(stream, language.clone(), false, translate)
+				.await?;
+		let transcript = transcript.text.trim();
 		if transcript.is_empty() {
 			output.push(TranscriptResult::EmptyTranscript {
 				file_name: file.filename.to_string(),
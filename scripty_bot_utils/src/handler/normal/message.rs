@@ -8,6 +8,22 @@ pub async fn message(ctx: Context, msg: Message) {
 		tokio::spawn(st.handle_message(ctx.clone(), msg.clone()));
 	}
 
+	// only spawn a task if this message is going to be read aloud
+	if let Some(guild_id) = msg.guild_id.filter(|g| {
+		!msg.author.bot && scripty_audio_handler::get_tts_channel(*g) == Some(msg.channel_id)
+	}) {
+		let content = msg.content_safe(&ctx);
+		let (channel_id, user_id) = (msg.channel_id, msg.author.id);
+		tokio::spawn(async move {
+			if let Err(e) =
+				scripty_audio_handler::queue_tts_message(guild_id, channel_id, user_id, content)
+					.await
+			{
+				error!("failed to queue tts message: {:?}", e);
+			}
+		});
+	}
+
 	tokio::spawn(crate::voice_message::handle_message(
 		ctx.clone(),
 		msg.clone(),
@@ -151,7 +151,7 @@ pub async fn voice_state_update(ctx: Context, _: Option<VoiceState>, new: VoiceS
 			voice_channel_id,
 			None,
 			false,
-			false,
+			None,
 		)
 		.await
 		{
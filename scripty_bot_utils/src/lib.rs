@@ -7,6 +7,7 @@ extern crate scripty_i18n;
 #[macro_use]
 extern crate async_trait;
 
+mod automod;
 pub mod background_tasks;
 pub mod checks;
 pub mod dm_support;
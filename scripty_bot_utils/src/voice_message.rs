@@ -64,8 +64,17 @@ async fn internal_handle_message(
 
 	let stream = scripty_stt::get_stream().await?;
 	stream.feed_audio(output)?;
-	let transcript = stream.get_result(lang, false, translate).await?;
-	let transcript = transcript.trim();
+	let transcript = scripty_stt::get_result_with_retries(stream, lang, false, translate).await?;
+	let transcript = transcript.text.trim();
+
+	if !transcript.is_empty()
+		&& crate::automod::check_message_transcript(ctx, &msg, transcript).await
+	{
+		debug!(%msg.id, "voice message removed by automod");
+		new_msg.delete(&ctx).await?;
+		return Ok(());
+	}
+
 	let mut msg_builder = EditMessage::new();
 
 	if transcript.is_empty() {
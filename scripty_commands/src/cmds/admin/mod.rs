@@ -4,10 +4,14 @@ mod cache_info;
 mod guild_check;
 mod hash_user_id;
 mod shutdown;
+mod speech_integrations;
+mod stt_pool;
 
 pub use cache_info::cache_info;
 pub use guild_check::*;
 pub use hash_user_id::hash_user_id;
+pub use speech_integrations::*;
+pub use stt_pool::*;
 
 #[poise::command(prefix_command, hide_in_help, owners_only)]
 pub async fn admin(ctx: Context<'_>) -> Result<(), Error> {
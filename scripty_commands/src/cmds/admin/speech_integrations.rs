@@ -0,0 +1,137 @@
+use scripty_utils::do_paginate;
+use serenity::{
+	builder::{CreateEmbed, CreateMessage},
+	model::user::User,
+};
+
+use crate::{Context, Error};
+
+/// Approve a speech command integration, DMing its credentials to the owner.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn approve_integration(
+	ctx: Context<'_>,
+	owner: User,
+	name: String,
+	webhook_url: String,
+	tts_voice: Option<String>,
+) -> Result<(), Error> {
+	if !webhook_url.starts_with("https://") {
+		ctx.say("webhook URL must start with `https://`").await?;
+		return Ok(());
+	}
+
+	let new = scripty_speech_commands::approve_integration(
+		owner.id.get(),
+		name.clone(),
+		webhook_url,
+		tts_voice.unwrap_or_else(|| "en".to_string()),
+	)
+	.await?;
+
+	let dm_res = owner
+		.dm(
+			&ctx,
+			CreateMessage::new().embed(
+				CreateEmbed::new()
+					.title(format!("{} approved for speech commands", name))
+					.description(
+						"Keep these secret! They won't be shown again.\nUse the API token in the \
+						 `Authorization` header to set your commands at `PUT \
+						 /speech_commands/commands`, and the signing secret to verify webhooks \
+						 came from Scripty.",
+					)
+					.field("Integration ID", new.id.to_string(), false)
+					.field("API token", format!("||{}||", new.api_token), false)
+					.field(
+						"Signing secret",
+						format!("||{}||", new.signing_secret),
+						false,
+					),
+			),
+		)
+		.await;
+
+	if let Err(e) = dm_res {
+		// the credentials can never be shown again, so there's no point keeping the integration around
+		scripty_speech_commands::revoke_integration(new.id).await?;
+		ctx.say(format!(
+			"failed to DM {} their credentials, so the integration was removed: {}",
+			owner.name, e
+		))
+		.await?;
+		return Ok(());
+	}
+
+	ctx.say(format!(
+		"approved integration {} (ID {}), credentials sent to {}",
+		name, new.id, owner.name
+	))
+	.await?;
+	Ok(())
+}
+
+/// List all speech command integrations.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn list_integrations(ctx: Context<'_>) -> Result<(), Error> {
+	let integrations = scripty_speech_commands::get_integrations();
+	if integrations.is_empty() {
+		ctx.say("no speech command integrations").await?;
+		return Ok(());
+	}
+
+	let fields = integrations
+		.iter()
+		.map(|integration| {
+			let commands = if integration.commands.is_empty() {
+				"none".to_string()
+			} else {
+				integration.commands.join(", ")
+			};
+			// embed field names are capped at 256 characters, and values at 1024
+			(
+				truncate(format!("`{}` {}", integration.id, integration.name), 250),
+				truncate(
+					format!(
+						"owner: <@{}>\nwebhook: {}\ncommands: {}",
+						integration.owner_id, integration.webhook_url, commands
+					),
+					1000,
+				),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	do_paginate(
+		ctx.serenity_context(),
+		ctx.channel_id(),
+		fields,
+		"Speech command integrations".to_string(),
+		None,
+		Some(5),
+		Some(ctx.author().id),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/// Revoke a speech command integration.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn revoke_integration(ctx: Context<'_>, id: i32) -> Result<(), Error> {
+	if scripty_speech_commands::revoke_integration(id).await? {
+		ctx.say(format!("revoked integration {}", id)).await?;
+	} else {
+		ctx.say(format!("no integration with ID {}", id)).await?;
+	}
+
+	Ok(())
+}
+
+/// Cut `text` off at `max_chars` characters, marking that it was cut off.
+fn truncate(text: String, max_chars: usize) -> String {
+	if text.chars().count() > max_chars {
+		text.chars().take(max_chars).chain(['…']).collect()
+	} else {
+		text
+	}
+}
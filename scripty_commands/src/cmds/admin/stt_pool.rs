@@ -0,0 +1,112 @@
+use std::time::SystemTime;
+
+use scripty_config::SttServiceDefinition;
+
+use crate::{Context, Error};
+
+/// Show every STT server in the pool, and how its last health check went.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn stt_pool(ctx: Context<'_>) -> Result<(), Error> {
+	let statuses = scripty_stt::get_server_statuses();
+	if statuses.is_empty() {
+		ctx.say("no STT servers in the pool").await?;
+		return Ok(());
+	}
+
+	let mut msg = String::new();
+	for status in statuses {
+		let state = if status.draining {
+			"draining"
+		} else if status.errored {
+			"errored"
+		} else if status.overloaded {
+			"overloaded"
+		} else {
+			"ok"
+		};
+		let latency = status.latency.map_or_else(
+			|| "none yet".to_string(),
+			|l| format!("{:.0}ms", l * 1000.0),
+		);
+		let health = match status.last_probe {
+			Some(probe) => {
+				let ago = SystemTime::now()
+					.duration_since(probe.checked_at)
+					.unwrap_or_default()
+					.as_secs();
+				match probe.result {
+					Ok(took) => format!("healthy ({}ms, {}s ago)", took.as_millis(), ago),
+					Err(e) => format!("unhealthy ({}, {}s ago)", e, ago),
+				}
+			}
+			None => "not checked yet".to_string(),
+		};
+		msg.push_str(&format!(
+			"`{}` from `{}`: {} - {} streams, latency {}, {}\n",
+			status.peer_address, status.service, state, status.in_flight, latency, health
+		));
+	}
+	ctx.say(msg).await?;
+
+	Ok(())
+}
+
+/// Add an STT service, as `host:port`, to the pool.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn stt_add(ctx: Context<'_>, service: String) -> Result<(), Error> {
+	if scripty_stt::add_service(SttServiceDefinition::HostString(service.clone())).await {
+		ctx.say(format!(
+			"added {} to the pool, use `stt_pool` to check it connected",
+			service
+		))
+		.await?;
+	} else {
+		ctx.say(format!(
+			"{} is already in the pool, or the remote STT backend isn't in use",
+			service
+		))
+		.await?;
+	}
+
+	Ok(())
+}
+
+/// Drain an STT service: it gets no new streams, and leaves the pool once its streams finish.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn stt_drain(ctx: Context<'_>, service: String) -> Result<(), Error> {
+	// services from the config may be either form, so match on how they're displayed
+	let statuses = scripty_stt::get_server_statuses();
+	let Some(definition) = statuses
+		.into_iter()
+		.map(|status| status.service)
+		.find(|definition| definition.to_string() == service)
+	else {
+		ctx.say(format!("{} is not in the pool", service)).await?;
+		return Ok(());
+	};
+
+	if scripty_stt::remove_service(&definition).await {
+		ctx.say(format!("draining {}", service)).await?;
+	} else {
+		ctx.say(format!("{} is already draining", service)).await?;
+	}
+
+	Ok(())
+}
+
+/// Read the STT services from the config file again, adding new ones and draining removed ones.
+#[poise::command(prefix_command, hide_in_help, owners_only)]
+pub async fn stt_reload(ctx: Context<'_>) -> Result<(), Error> {
+	match scripty_stt::reload_services().await {
+		Ok(()) => {
+			ctx.say("reloaded STT services, use `stt_pool` to check the new pool")
+				.await?
+		}
+		Err(e) => {
+			ctx.say(format!("failed to reload STT services: {}", e))
+				.await?
+		}
+	};
+
+	Ok(())
+}
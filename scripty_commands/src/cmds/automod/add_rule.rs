@@ -1,5 +1,6 @@
 use poise::CreateReply;
 use scripty_automod::{
+	matcher::RuleMatcher,
 	types::{AutomodRuleAction, AutomodRuleType},
 	utils::{get_next_tier, get_tier_rule_count},
 };
@@ -164,6 +165,27 @@ pub async fn automod_add_rule(
 		return Ok(());
 	}
 
+	// make sure the rule can actually be used before saving it
+	if let Err(e) = RuleMatcher::compile(rule_type, &content) {
+		ctx.send(
+			CreateReply::default().embed(
+				CreateEmbed::default()
+					.title(format_message!(
+						resolved_language,
+						"automod-add-rule-embed-failure-title"
+					))
+					.description(format_message!(
+						resolved_language,
+						"automod-add-rule-embed-failure-description-invalid-rule",
+						error: e.to_string()
+					)),
+			),
+		)
+		.await?;
+
+		return Ok(());
+	}
+
 	// all checks passed, add the rule
 	let rule_id = sqlx::query!(
 		"INSERT INTO automod_rules (source_id, rule_type, rule_data, rule_action) VALUES ($1, $2, \
@@ -176,6 +198,7 @@ pub async fn automod_add_rule(
 	.fetch_one(db)
 	.await?
 	.item_id;
+	scripty_automod::db::invalidate_guild_config(gid);
 
 	let extra_details = if !is_not_none {
 		format_message!(
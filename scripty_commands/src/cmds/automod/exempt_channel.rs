@@ -0,0 +1,71 @@
+use serenity::all::GuildChannel;
+
+use crate::{Context, Error};
+
+/// Turn automod off in a voice channel.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "exempt_channel"
+)]
+pub async fn automod_exempt_channel(
+	ctx: Context<'_>,
+	#[description = "The voice channel to exempt, or stop exempting."]
+	#[channel_types("Voice", "Stage")]
+	channel: GuildChannel,
+	#[description = "Whether automod is off in this channel."] exempt: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+	let db = scripty_db::get_db();
+
+	let source_id = sqlx::query!(
+		"SELECT item_id FROM automod_config WHERE guild_id = $1",
+		guild_id.get() as i64
+	)
+	.fetch_optional(db)
+	.await?
+	.map(|row| row.item_id);
+	let Some(source_id) = source_id else {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-exempt-channel-not-setup",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+
+	if exempt {
+		sqlx::query!(
+			"INSERT INTO automod_exempt_channels (source_id, channel_id) VALUES ($1, $2) ON \
+			 CONFLICT DO NOTHING",
+			source_id,
+			channel.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	} else {
+		sqlx::query!(
+			"DELETE FROM automod_exempt_channels WHERE source_id = $1 AND channel_id = $2",
+			source_id,
+			channel.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	}
+	scripty_automod::db::invalidate_guild_config(guild_id.get());
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-exempt-channel-updated",
+		channelId: channel.id.get(),
+		exempt: exempt.to_string()
+	))
+	.await?;
+
+	Ok(())
+}
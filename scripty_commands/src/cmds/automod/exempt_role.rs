@@ -0,0 +1,69 @@
+use serenity::all::Role;
+
+use crate::{Context, Error};
+
+/// Never take automod action against users with a role, such as staff.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "exempt_role"
+)]
+pub async fn automod_exempt_role(
+	ctx: Context<'_>,
+	#[description = "The role to exempt, or stop exempting."] role: Role,
+	#[description = "Whether users with this role are exempt from automod."] exempt: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+	let db = scripty_db::get_db();
+
+	let source_id = sqlx::query!(
+		"SELECT item_id FROM automod_config WHERE guild_id = $1",
+		guild_id.get() as i64
+	)
+	.fetch_optional(db)
+	.await?
+	.map(|row| row.item_id);
+	let Some(source_id) = source_id else {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-exempt-role-not-setup",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+
+	if exempt {
+		sqlx::query!(
+			"INSERT INTO automod_exempt_roles (source_id, role_id) VALUES ($1, $2) ON CONFLICT DO \
+			 NOTHING",
+			source_id,
+			role.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	} else {
+		sqlx::query!(
+			"DELETE FROM automod_exempt_roles WHERE source_id = $1 AND role_id = $2",
+			source_id,
+			role.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	}
+	scripty_automod::db::invalidate_guild_config(guild_id.get());
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-exempt-role-updated",
+		roleId: role.id.get(),
+		exempt: exempt.to_string()
+	))
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,60 @@
+use poise::ChoiceParameter;
+use scripty_automod::{groups::MAX_GROUP_NAME_LENGTH, types::AutomodRuleAction};
+
+use super::{get_source_id, invalidate_config};
+use crate::{Context, Error};
+
+/// Create a group of automod rules that share an action.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "create"
+)]
+pub async fn automod_group_create(
+	ctx: Context<'_>,
+	#[description = "Name of the group."] name: String,
+	#[description = "The action every rule in the group takes when triggered."]
+	action: AutomodRuleAction,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let name = name.trim();
+	if name.is_empty() || name.chars().count() > MAX_GROUP_NAME_LENGTH {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-name-invalid",
+			maxLength: MAX_GROUP_NAME_LENGTH
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let Some(source_id) = get_source_id(ctx, &resolved_language).await? else {
+		return Ok(());
+	};
+
+	let created = scripty_automod::groups::create_group(source_id, name, action).await?;
+	invalidate_config(ctx);
+
+	let message = match created {
+		Some(_) => format_message!(
+			resolved_language,
+			"automod-group-create-success",
+			groupName: name,
+			action: action.name(),
+			contextPrefix: ctx.prefix()
+		),
+		None => format_message!(
+			resolved_language,
+			"automod-group-name-taken",
+			groupName: name
+		),
+	};
+	ctx.say(message).await?;
+
+	Ok(())
+}
@@ -0,0 +1,36 @@
+use super::{find_group, invalidate_config};
+use crate::{Context, Error};
+
+/// Delete a group of automod rules, and every rule in it.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "delete"
+)]
+pub async fn automod_group_delete(
+	ctx: Context<'_>,
+	#[description = "Name of the group to delete."] group: String,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let Some((_, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	scripty_automod::groups::delete_group(group.id).await?;
+	invalidate_config(ctx);
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-group-delete-success",
+		groupName: group.name,
+		ruleCount: group.rule_count
+	))
+	.await?;
+
+	Ok(())
+}
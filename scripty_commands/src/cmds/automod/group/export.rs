@@ -0,0 +1,48 @@
+use poise::CreateReply;
+use scripty_automod::groups::RuleFileFormat;
+use serenity::builder::CreateAttachment;
+
+use super::find_group;
+use crate::{Context, Error};
+
+/// Download every rule in a group as a CSV or JSON file.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "export"
+)]
+pub async fn automod_group_export(
+	ctx: Context<'_>,
+	#[description = "Name of the group to export."] group: String,
+	#[description = "Format of the file. Defaults to CSV."] format: Option<RuleFileFormat>,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let Some((_, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	let format = format.unwrap_or(RuleFileFormat::Csv);
+	let rules = scripty_automod::groups::export_rules(group.id).await?;
+
+	ctx.send(
+		CreateReply::default()
+			.content(format_message!(
+				resolved_language,
+				"automod-group-export-success",
+				groupName: group.name,
+				ruleCount: rules.len()
+			))
+			.attachment(CreateAttachment::bytes(
+				scripty_automod::groups::write_rules(&rules, format),
+				format.file_name(),
+			)),
+	)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,128 @@
+use scripty_automod::{
+	groups::{RuleFileFormat, MAX_IMPORT_SIZE},
+	matcher::RuleMatcher,
+	types::AutomodRuleType,
+	utils::get_tier_rule_count,
+};
+use serenity::all::Attachment;
+
+use super::{find_group, invalidate_config};
+use crate::{Context, Error};
+
+/// Add every rule in a CSV or JSON file to a group.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "import"
+)]
+pub async fn automod_group_import(
+	ctx: Context<'_>,
+	#[description = "Name of the group to add the rules to."] group: String,
+	#[description = "CSV or JSON file of rules. Export a group to see the format."]
+	file: Attachment,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let Some(format) = RuleFileFormat::from_file_name(&file.filename) else {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-import-invalid-format"
+		))
+		.await?;
+		return Ok(());
+	};
+	if file.size > MAX_IMPORT_SIZE {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-import-too-large",
+			maxSize: MAX_IMPORT_SIZE / 1024 / 1024
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let Some((source_id, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	ctx.defer().await?;
+	let rules = match scripty_automod::groups::parse_rules(&file.download().await?, format) {
+		Ok(rules) => rules,
+		Err(e) => {
+			ctx.say(format_message!(
+				resolved_language,
+				"automod-group-import-invalid-file",
+				error: e.to_string()
+			))
+			.await?;
+			return Ok(());
+		}
+	};
+
+	// the same checks as adding a single rule, but for every rule at once
+	let premium_tier = scripty_premium::get_guild(guild_id.get())
+		.await
+		.unwrap_or_default();
+	if premium_tier == scripty_premium::PremiumTierList::None
+		&& rules
+			.iter()
+			.any(|rule| !matches!(rule.rule_type, AutomodRuleType::Regular))
+	{
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-add-rule-embed-failure-description-free-locked-type"
+		))
+		.await?;
+		return Ok(());
+	}
+	for (idx, rule) in rules.iter().enumerate() {
+		if let Err(e) = RuleMatcher::compile(rule.rule_type, &rule.rule_data) {
+			ctx.say(format_message!(
+				resolved_language,
+				"automod-group-import-invalid-rule",
+				ruleNumber: idx + 1,
+				error: e.to_string()
+			))
+			.await?;
+			return Ok(());
+		}
+	}
+
+	let count = sqlx::query!(
+		r#"SELECT COUNT(*) AS "count!" FROM automod_rules WHERE source_id = $1"#,
+		source_id
+	)
+	.fetch_one(scripty_db::get_db())
+	.await?
+	.count;
+	let max_rules = get_tier_rule_count(premium_tier);
+	if count + rules.len() as i64 > max_rules {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-import-too-many-rules",
+			ruleCount: rules.len(),
+			rulesLeft: (max_rules - count).max(0),
+			maxRules: max_rules
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let imported = scripty_automod::groups::import_rules(source_id, &group, &rules).await?;
+	invalidate_config(ctx);
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-group-import-success",
+		imported: imported,
+		skipped: rules.len() as u64 - imported,
+		groupName: group.name
+	))
+	.await?;
+
+	Ok(())
+}
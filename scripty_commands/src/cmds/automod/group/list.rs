@@ -0,0 +1,63 @@
+use poise::ChoiceParameter;
+use scripty_utils::do_paginate;
+
+use super::get_source_id;
+use crate::{Context, Error};
+
+/// List every group of automod rules in this server.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "list"
+)]
+pub async fn automod_group_list(ctx: Context<'_>) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let Some(source_id) = get_source_id(ctx, &resolved_language).await? else {
+		return Ok(());
+	};
+
+	let groups = scripty_automod::groups::list_groups(source_id).await?;
+	if groups.is_empty() {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-list-no-groups",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let formatted_groups = groups
+		.into_iter()
+		.map(|group| {
+			(
+				group.name,
+				format_message!(
+					resolved_language,
+					"automod-group-list-field-value",
+					action: group.default_action.name(),
+					ruleCount: group.rule_count,
+					enabled: group.enabled.to_string()
+				),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	do_paginate(
+		ctx.serenity_context(),
+		ctx.channel_id(),
+		formatted_groups,
+		format_message!(resolved_language, "automod-group-list-embed-title"),
+		None,
+		None,
+		Some(ctx.author().id),
+	)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,79 @@
+mod create;
+mod delete;
+mod export;
+mod import;
+mod list;
+mod rename;
+mod root;
+mod set_action;
+mod toggle;
+
+pub use create::automod_group_create;
+pub use delete::automod_group_delete;
+pub use export::automod_group_export;
+pub use import::automod_group_import;
+pub use list::automod_group_list;
+pub use rename::automod_group_rename;
+pub use root::automod_group_root;
+pub use set_action::automod_group_set_action;
+pub use toggle::automod_group_toggle;
+
+use crate::{Context, Error};
+
+/// Get this server's automod config ID, or tell the user to set up automod first.
+async fn get_source_id(
+	ctx: Context<'_>,
+	resolved_language: &scripty_i18n::LanguageIdentifier,
+) -> Result<Option<i32>, Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let source_id = sqlx::query!(
+		"SELECT item_id FROM automod_config WHERE guild_id = $1",
+		guild_id.get() as i64
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	.map(|row| row.item_id);
+
+	if source_id.is_none() {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-not-setup",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+	}
+
+	Ok(source_id)
+}
+
+/// Find a group by name, or tell the user it doesn't exist.
+async fn find_group(
+	ctx: Context<'_>,
+	resolved_language: &scripty_i18n::LanguageIdentifier,
+	name: &str,
+) -> Result<Option<(i32, scripty_automod::groups::RuleGroupInfo)>, Error> {
+	let Some(source_id) = get_source_id(ctx, resolved_language).await? else {
+		return Ok(None);
+	};
+
+	let group = scripty_automod::groups::get_group(source_id, name).await?;
+	if group.is_none() {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-not-found",
+			groupName: name,
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+	}
+
+	Ok(group.map(|group| (source_id, group)))
+}
+
+/// Make a change to this server's rule groups apply to the next message, rather than whenever
+/// the cached automod config expires.
+fn invalidate_config(ctx: Context<'_>) {
+	if let Some(guild_id) = ctx.guild_id() {
+		scripty_automod::db::invalidate_guild_config(guild_id.get());
+	}
+}
@@ -0,0 +1,58 @@
+use scripty_automod::groups::MAX_GROUP_NAME_LENGTH;
+
+use super::{find_group, invalidate_config};
+use crate::{Context, Error};
+
+/// Rename a group of automod rules.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "rename"
+)]
+pub async fn automod_group_rename(
+	ctx: Context<'_>,
+	#[description = "Current name of the group."] group: String,
+	#[description = "New name for the group."] new_name: String,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let new_name = new_name.trim();
+	if new_name.is_empty() || new_name.chars().count() > MAX_GROUP_NAME_LENGTH {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-group-name-invalid",
+			maxLength: MAX_GROUP_NAME_LENGTH
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let Some((source_id, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	let renamed = scripty_automod::groups::rename_group(source_id, group.id, new_name).await?;
+	invalidate_config(ctx);
+
+	let message = if renamed {
+		format_message!(
+			resolved_language,
+			"automod-group-rename-success",
+			oldName: group.name,
+			newName: new_name
+		)
+	} else {
+		format_message!(
+			resolved_language,
+			"automod-group-name-taken",
+			groupName: new_name
+		)
+	};
+	ctx.say(message).await?;
+
+	Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::{Context, Error};
+
+/// Manage groups of automod rules that share an action.
+///
+/// Does nothing, instead check out the sub-commands of this command.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "group"
+)]
+pub async fn automod_group_root(ctx: Context<'_>) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	ctx.say(
+		format_message!(resolved_language, "automod-group-root-response", contextPrefix: ctx.prefix()),
+	)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,41 @@
+use poise::ChoiceParameter;
+use scripty_automod::types::AutomodRuleAction;
+
+use super::{find_group, invalidate_config};
+use crate::{Context, Error};
+
+/// Change the action every rule in a group takes.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "set_action"
+)]
+pub async fn automod_group_set_action(
+	ctx: Context<'_>,
+	#[description = "Name of the group."] group: String,
+	#[description = "The action every rule in the group takes when triggered."]
+	action: AutomodRuleAction,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let Some((_, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	scripty_automod::groups::set_default_action(group.id, action).await?;
+	invalidate_config(ctx);
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-group-set-action-success",
+		groupName: group.name,
+		action: action.name()
+	))
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,44 @@
+use super::{find_group, invalidate_config};
+use crate::{Context, Error};
+
+/// Turn every rule in a group on or off at once.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "toggle"
+)]
+pub async fn automod_group_toggle(
+	ctx: Context<'_>,
+	#[description = "Name of the group."] group: String,
+	#[description = "Whether the group's rules should be checked."] enabled: bool,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let Some((_, group)) = find_group(ctx, &resolved_language, &group).await? else {
+		return Ok(());
+	};
+
+	scripty_automod::groups::set_enabled(group.id, enabled).await?;
+	invalidate_config(ctx);
+
+	ctx.say(if enabled {
+		format_message!(
+			resolved_language,
+			"automod-group-toggle-enabled",
+			groupName: group.name
+		)
+	} else {
+		format_message!(
+			resolved_language,
+			"automod-group-toggle-disabled",
+			groupName: group.name
+		)
+	})
+	.await?;
+
+	Ok(())
+}
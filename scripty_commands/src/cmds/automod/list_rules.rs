@@ -17,8 +17,10 @@ pub async fn automod_list_rules(ctx: Context<'_>) -> Result<(), Error> {
 		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(gid)).await;
 
 	let rules: Vec<_> = sqlx::query!(
-		"SELECT item_id, rule_type, rule_action, rule_data FROM automod_rules WHERE source_id = \
-		 (SELECT item_id FROM automod_config WHERE guild_id = $1) ORDER BY item_id ASC",
+		"SELECT r.item_id, r.rule_type, COALESCE(g.default_action, r.rule_action) AS \
+		 \"rule_action!\", r.rule_data FROM automod_rules r LEFT JOIN automod_rule_groups g ON \
+		 g.item_id = r.group_id WHERE r.source_id = (SELECT item_id FROM automod_config WHERE \
+		 guild_id = $1) ORDER BY r.item_id ASC",
 		gid as i64
 	)
 	.fetch_all(db)
@@ -1,11 +1,24 @@
 mod add_rule;
+mod exempt_channel;
+mod exempt_role;
+pub mod group;
 mod list_rules;
+mod monitor_only;
 mod remove_rule;
 mod root;
+mod scope_rule;
 mod setup;
+mod strikes;
+mod test;
 
 pub use add_rule::automod_add_rule;
+pub use exempt_channel::automod_exempt_channel;
+pub use exempt_role::automod_exempt_role;
 pub use list_rules::automod_list_rules;
+pub use monitor_only::automod_monitor_only;
 pub use remove_rule::automod_remove_rule;
 pub use root::automod_root;
+pub use scope_rule::automod_scope_rule;
 pub use setup::automod_setup;
+pub use strikes::automod_strikes;
+pub use test::automod_test;
@@ -0,0 +1,44 @@
+use crate::{Context, Error};
+
+/// Only log what automod would have done, without deleting anything or punishing anyone.
+///
+/// Useful to see what new rules catch before they go live.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "monitor_only"
+)]
+pub async fn automod_monitor_only(
+	ctx: Context<'_>,
+	#[description = "Defaults to false"] monitor_only: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let res = sqlx::query!(
+		"UPDATE automod_config SET monitor_only = $2 WHERE guild_id = $1",
+		guild_id.get() as i64,
+		monitor_only
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	scripty_automod::db::invalidate_guild_config(guild_id.get());
+
+	ctx.say(if res.rows_affected() == 0 {
+		format_message!(
+			resolved_language,
+			"automod-monitor-only-not-setup",
+			contextPrefix: ctx.prefix()
+		)
+	} else if monitor_only {
+		format_message!(resolved_language, "automod-monitor-only-enabled")
+	} else {
+		format_message!(resolved_language, "automod-monitor-only-disabled")
+	})
+	.await?;
+
+	Ok(())
+}
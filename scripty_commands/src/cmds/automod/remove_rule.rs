@@ -85,6 +85,7 @@ pub async fn automod_remove_rule(
 
 		return Ok(());
 	};
+	scripty_automod::db::invalidate_guild_config(gid);
 
 	let premium_tier = scripty_premium::get_guild(gid).await.unwrap_or_default();
 
@@ -0,0 +1,85 @@
+use serenity::all::GuildChannel;
+
+use crate::{Context, Error};
+
+/// Make a rule only apply in some voice channels.
+///
+/// Rules that aren't scoped to any channel apply everywhere.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "scope_rule"
+)]
+pub async fn automod_scope_rule(
+	ctx: Context<'_>,
+	#[description = "The rule ID to scope."] rule_id: i32,
+	#[description = "The voice channel to add to or remove from the rule's scope."]
+	#[channel_types("Voice", "Stage")]
+	channel: GuildChannel,
+	#[description = "Whether the rule applies in this channel."] scoped: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+	let db = scripty_db::get_db();
+
+	// make sure the rule belongs to this server
+	if sqlx::query!(
+		"SELECT r.item_id FROM automod_rules r INNER JOIN automod_config c ON c.item_id = \
+		 r.source_id WHERE c.guild_id = $1 AND r.item_id = $2",
+		guild_id.get() as i64,
+		rule_id
+	)
+	.fetch_optional(db)
+	.await?
+	.is_none()
+	{
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-scope-rule-invalid-id",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	}
+
+	if scoped {
+		sqlx::query!(
+			"INSERT INTO automod_rule_channels (rule_id, channel_id) VALUES ($1, $2) ON CONFLICT \
+			 DO NOTHING",
+			rule_id,
+			channel.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	} else {
+		sqlx::query!(
+			"DELETE FROM automod_rule_channels WHERE rule_id = $1 AND channel_id = $2",
+			rule_id,
+			channel.id.get() as i64
+		)
+		.execute(db)
+		.await?;
+	}
+	scripty_automod::db::invalidate_guild_config(guild_id.get());
+
+	let channel_count = sqlx::query!(
+		r#"SELECT COUNT(*) AS "count!" FROM automod_rule_channels WHERE rule_id = $1"#,
+		rule_id
+	)
+	.fetch_one(db)
+	.await?
+	.count;
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-scope-rule-updated",
+		ruleId: rule_id,
+		channelCount: channel_count
+	))
+	.await?;
+
+	Ok(())
+}
@@ -80,7 +80,7 @@ pub async fn automod_setup(
 	.execute(db)
 	.await
 	{
-		Ok(_) => {}
+		Ok(_) => scripty_automod::db::invalidate_guild_config(guild_id),
 		// if we get a 23503 error, it means this server has not been set up yet
 		// tell the user to run the setup command first
 		Err(sqlx::Error::Database(e)) if e.code() == Some("23503".into()) => {
@@ -0,0 +1,124 @@
+use scripty_automod::strikes::{
+	MUTE_MINUTES_RANGE,
+	STRIKE_DECAY_HOURS_RANGE,
+	STRIKE_THRESHOLD_RANGE,
+	TIMEOUT_MINUTES_RANGE,
+};
+
+use crate::{Context, Error};
+
+/// Escalate the action taken against users that keep breaking rules. Unset options are left as
+/// they were.
+///
+/// Every time a rule is triggered, the user gets a strike. As strikes add up, they are muted,
+/// then removed from voice, then timed out, even if the rule itself asked for less.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "strikes"
+)]
+pub async fn automod_strikes(
+	ctx: Context<'_>,
+	#[description = "Count strikes and escalate actions? Defaults to false."] enabled: Option<bool>,
+	#[description = "Hours until a strike no longer counts. Defaults to 24."]
+	#[min = 1]
+	#[max = 720]
+	decay_hours: Option<i32>,
+	#[description = "Strikes at which a user is muted. 0 to never mute. Defaults to 2."]
+	#[min = 0]
+	#[max = 100]
+	mute_at: Option<i32>,
+	#[description = "Strikes at which a user is removed from voice. 0 to never remove. Defaults \
+	                 to 3."]
+	#[min = 0]
+	#[max = 100]
+	kick_at: Option<i32>,
+	#[description = "Strikes at which a user is timed out. 0 to never time out. Defaults to 5."]
+	#[min = 0]
+	#[max = 100]
+	timeout_at: Option<i32>,
+	#[description = "Minutes a timeout lasts. Defaults to 10."]
+	#[min = 1]
+	#[max = 40320]
+	timeout_minutes: Option<i32>,
+	#[description = "Minutes until a muted user is unmuted. 0 to never unmute. Defaults to 0."]
+	#[min = 0]
+	#[max = 40320]
+	mute_minutes: Option<i32>,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	// prefix commands don't enforce the min and max above, so check them here too
+	for (option, value, range) in [
+		("decay_hours", decay_hours, STRIKE_DECAY_HOURS_RANGE),
+		("mute_at", mute_at, STRIKE_THRESHOLD_RANGE),
+		("kick_at", kick_at, STRIKE_THRESHOLD_RANGE),
+		("timeout_at", timeout_at, STRIKE_THRESHOLD_RANGE),
+		("timeout_minutes", timeout_minutes, TIMEOUT_MINUTES_RANGE),
+		("mute_minutes", mute_minutes, MUTE_MINUTES_RANGE),
+	] {
+		if let Some(value) = value
+			&& !range.contains(&value)
+		{
+			ctx.say(format_message!(
+				resolved_language,
+				"automod-strikes-out-of-range",
+				option: option,
+				min: *range.start(),
+				max: *range.end()
+			))
+			.await?;
+			return Ok(());
+		}
+	}
+
+	let Some(cfg) = sqlx::query!(
+		"UPDATE automod_config SET strikes_enabled = COALESCE($2, strikes_enabled), \
+		 strike_decay_hours = COALESCE($3, strike_decay_hours), strike_mute_threshold = \
+		 COALESCE($4, strike_mute_threshold), strike_kick_threshold = COALESCE($5, \
+		 strike_kick_threshold), strike_timeout_threshold = COALESCE($6, \
+		 strike_timeout_threshold), timeout_minutes = COALESCE($7, timeout_minutes), mute_minutes \
+		 = COALESCE($8, mute_minutes) WHERE guild_id = $1 RETURNING strikes_enabled, \
+		 strike_decay_hours, strike_mute_threshold, strike_kick_threshold, \
+		 strike_timeout_threshold, timeout_minutes, mute_minutes",
+		guild_id.get() as i64,
+		enabled,
+		decay_hours,
+		mute_at,
+		kick_at,
+		timeout_at,
+		timeout_minutes,
+		mute_minutes
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	else {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-strikes-not-setup",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+	scripty_automod::db::invalidate_guild_config(guild_id.get());
+
+	ctx.say(format_message!(
+		resolved_language,
+		"automod-strikes-updated",
+		enabled: cfg.strikes_enabled.to_string(),
+		decayHours: cfg.strike_decay_hours,
+		muteAt: cfg.strike_mute_threshold,
+		kickAt: cfg.strike_kick_threshold,
+		timeoutAt: cfg.strike_timeout_threshold,
+		timeoutMinutes: cfg.timeout_minutes,
+		muteMinutes: cfg.mute_minutes
+	))
+	.await?;
+
+	Ok(())
+}
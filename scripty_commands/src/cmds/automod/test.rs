@@ -0,0 +1,57 @@
+use poise::ChoiceParameter;
+
+use crate::{Context, Error};
+
+/// Check which automod rule, if any, would be triggered by some text.
+///
+/// Nothing is deleted, logged, or punished.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "test"
+)]
+pub async fn automod_test(
+	ctx: Context<'_>,
+	#[description = "The text to check, as if someone had said it."]
+	#[rest]
+	text: String,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let Some(cfg) = scripty_automod::db::get_guild_config(guild_id.get()).await? else {
+		ctx.say(format_message!(
+			resolved_language,
+			"automod-test-not-setup",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+
+	let mode = if !cfg.enabled {
+		"disabled"
+	} else if cfg.monitor_only {
+		"monitor"
+	} else {
+		"active"
+	};
+	let message = match cfg.get_matching_rule(&text) {
+		Some(rule) => format_message!(
+			resolved_language,
+			"automod-test-match",
+			ruleId: rule.rule_id,
+			ruleType: rule.rule_type.name(),
+			ruleContent: rule.rule_data.as_str(),
+			action: rule.rule_action.name(),
+			mode: mode
+		),
+		None => format_message!(resolved_language, "automod-test-no-match"),
+	};
+	ctx.say(message).await?;
+
+	Ok(())
+}
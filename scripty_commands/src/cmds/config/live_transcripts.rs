@@ -0,0 +1,45 @@
+use scripty_bot_utils::{checks::is_guild, Context, Error};
+
+/// Show transcripts live while users are still speaking?
+///
+/// When enabled, Scripty posts a partial transcript shortly after someone starts speaking,
+/// and keeps editing it until they stop, when it is replaced by the final transcript.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	check = "is_guild",
+	required_permissions = "MANAGE_GUILD",
+	rename = "live_transcripts"
+)]
+pub async fn config_live_transcripts(
+	ctx: Context<'_>,
+	#[description = "Defaults to false"] live_transcripts: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx
+		.guild_id()
+		.map(|g| g.get())
+		.ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id)).await;
+
+	sqlx::query!(
+		"INSERT INTO guilds (guild_id, live_transcripts) VALUES ($1, $2) ON CONFLICT (guild_id) \
+		 DO UPDATE SET live_transcripts = $2",
+		guild_id as i64,
+		live_transcripts
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	ctx.say(format_message!(
+		resolved_language,
+		if live_transcripts {
+			"config-live-transcripts-enabled"
+		} else {
+			"config-live-transcripts-disabled"
+		}
+	))
+	.await?;
+
+	Ok(())
+}
@@ -1,5 +1,8 @@
 mod auto_detect_lang;
 mod language;
+mod live_transcripts;
+mod session_summaries;
+mod speech_commands;
 mod transcribe_audio;
 mod transcribe_only_role;
 mod transcribe_video;
@@ -9,9 +12,12 @@ mod verbose;
 
 pub use auto_detect_lang::config_auto_detect_lang;
 pub use language::config_server_language;
+pub use live_transcripts::config_live_transcripts;
 use poise::CreateReply;
 use scripty_bot_utils::{checks::is_guild, Context, Error};
 use serenity::builder::CreateEmbed;
+pub use session_summaries::config_session_summaries;
+pub use speech_commands::config_speech_commands;
 pub use transcribe_audio::config_transcribe_audio;
 pub use transcribe_only_role::config_transcribe_only_role;
 pub use transcribe_video::config_transcribe_video;
@@ -0,0 +1,45 @@
+use scripty_bot_utils::{checks::is_guild, Context, Error};
+
+/// Send a summary alongside recorded transcripts?
+///
+/// When enabled, sessions joined with `record_transcriptions` also get a summary when they end,
+/// listing how long each user spoke for, the key points, and the languages spoken.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	check = "is_guild",
+	required_permissions = "MANAGE_GUILD",
+	rename = "session_summaries"
+)]
+pub async fn config_session_summaries(
+	ctx: Context<'_>,
+	#[description = "Defaults to false"] session_summaries: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx
+		.guild_id()
+		.map(|g| g.get())
+		.ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id)).await;
+
+	sqlx::query!(
+		"INSERT INTO guilds (guild_id, session_summaries) VALUES ($1, $2) ON CONFLICT (guild_id) \
+		 DO UPDATE SET session_summaries = $2",
+		guild_id as i64,
+		session_summaries
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	ctx.say(format_message!(
+		resolved_language,
+		if session_summaries {
+			"config-session-summaries-enabled"
+		} else {
+			"config-session-summaries-disabled"
+		}
+	))
+	.await?;
+
+	Ok(())
+}
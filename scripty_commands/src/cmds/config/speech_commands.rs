@@ -0,0 +1,45 @@
+use scripty_bot_utils::{checks::is_guild, Context, Error};
+
+/// Let users run "Hey Scripty" speech commands in voice chat?
+///
+/// When enabled, what users say after "Hey Scripty", along with their user and server IDs,
+/// is sent to the integration that handles the command.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	check = "is_guild",
+	required_permissions = "MANAGE_GUILD",
+	rename = "speech_commands"
+)]
+pub async fn config_speech_commands(
+	ctx: Context<'_>,
+	#[description = "Defaults to false"] speech_commands: bool,
+) -> Result<(), Error> {
+	let guild_id = ctx
+		.guild_id()
+		.map(|g| g.get())
+		.ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id)).await;
+
+	sqlx::query!(
+		"INSERT INTO guilds (guild_id, speech_commands) VALUES ($1, $2) ON CONFLICT (guild_id) DO \
+		 UPDATE SET speech_commands = $2",
+		guild_id as i64,
+		speech_commands
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+
+	ctx.say(format_message!(
+		resolved_language,
+		if speech_commands {
+			"config-speech-commands-enabled"
+		} else {
+			"config-speech-commands-disabled"
+		}
+	))
+	.await?;
+
+	Ok(())
+}
@@ -1,6 +1,7 @@
 use std::{borrow::Cow, time::SystemTime};
 
 use humantime::format_rfc3339_seconds;
+use scripty_audio_handler::TranscriptFormat;
 use scripty_bot_utils::checks::is_guild;
 use serenity::{
 	all::{AutoArchiveDuration, ChannelFlags},
@@ -40,6 +41,9 @@ pub async fn join(
 
 	#[description = "Create a new thread for this transcription? Defaults to false."]
 	create_thread: Option<bool>,
+
+	#[description = "Format to send logged transcripts in. Defaults to plain text."]
+	transcript_format: Option<TranscriptFormat>,
 ) -> Result<(), Error> {
 	let resolved_language =
 		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
@@ -49,7 +53,9 @@ pub async fn join(
 	let cfg = scripty_config::get_config();
 
 	// validate arguments
-	let record_transcriptions = record_transcriptions.unwrap_or(false);
+	let record_transcriptions = record_transcriptions
+		.unwrap_or(false)
+		.then(|| transcript_format.unwrap_or_default());
 	let mut create_thread = create_thread.unwrap_or(false);
 	let target_channel = match target_channel {
 		Some(c) => c,
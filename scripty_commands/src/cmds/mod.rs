@@ -11,8 +11,11 @@ mod leave;
 mod ping;
 pub mod premium;
 mod register_cmds;
+pub mod stats;
 mod terms_of_service;
 mod throw_error;
+pub mod transcripts;
+pub mod tts;
 mod vote_reminders;
 
 pub use admin::*;
@@ -0,0 +1,38 @@
+use poise::CreateReply;
+use serenity::builder::{CreateEmbed, CreateEmbedFooter};
+
+use crate::{Context, Error};
+
+/// See how much each user has taken part in the current call.
+#[poise::command(prefix_command, slash_command, guild_only, rename = "call")]
+pub async fn stats_call(ctx: Context<'_>) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let Some(call_stats) = scripty_audio_handler::get_call_stats(guild_id) else {
+		ctx.say(format_message!(
+			resolved_language,
+			"stats-call-not-in-call",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+
+	ctx.send(
+		CreateReply::default().embed(
+			CreateEmbed::new()
+				.title(format_message!(resolved_language, "stats-call-embed-title"))
+				.description(call_stats.render(&resolved_language))
+				.footer(CreateEmbedFooter::new(format_message!(
+					resolved_language,
+					"stats-call-embed-footer",
+					minutes: call_stats.duration().as_secs() / 60
+				))),
+		),
+	)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,5 @@
+mod call;
+mod root;
+
+pub use call::stats_call;
+pub use root::stats_root;
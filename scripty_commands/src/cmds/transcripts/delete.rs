@@ -0,0 +1,37 @@
+use crate::{Context, Error};
+
+/// Delete the transcript of a past recorded session.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "delete"
+)]
+pub async fn transcripts_delete(
+	ctx: Context<'_>,
+	#[description = "The session ID to delete. See `/transcripts list`."] session_id: i32,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	ctx.say(
+		if scripty_audio_handler::delete_session(guild_id, session_id).await? {
+			format_message!(
+				resolved_language,
+				"transcripts-delete-success",
+				sessionId: session_id
+			)
+		} else {
+			format_message!(
+				resolved_language,
+				"transcripts-invalid-session",
+				contextPrefix: ctx.prefix()
+			)
+		},
+	)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,53 @@
+use poise::CreateReply;
+use scripty_audio_handler::TranscriptFormat;
+use serenity::builder::CreateAttachment;
+
+use crate::{Context, Error};
+
+/// Download the transcript of a past recorded session.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "download"
+)]
+pub async fn transcripts_download(
+	ctx: Context<'_>,
+	#[description = "The session ID to download. See `/transcripts list`."] session_id: i32,
+	#[description = "Format to download the transcript in. Defaults to the one it was recorded in."]
+	format: Option<TranscriptFormat>,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let Some((recorded_format, entries)) =
+		scripty_audio_handler::load_session(guild_id, session_id).await?
+	else {
+		ctx.say(format_message!(
+			resolved_language,
+			"transcripts-invalid-session",
+			contextPrefix: ctx.prefix()
+		))
+		.await?;
+		return Ok(());
+	};
+
+	let format = format.unwrap_or(recorded_format);
+	ctx.send(
+		CreateReply::default()
+			.content(format_message!(
+				resolved_language,
+				"transcripts-download-success",
+				sessionId: session_id
+			))
+			.attachment(CreateAttachment::bytes(
+				format.render(&entries),
+				format.file_name(),
+			)),
+	)
+	.await?;
+
+	Ok(())
+}
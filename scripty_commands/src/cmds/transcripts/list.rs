@@ -0,0 +1,71 @@
+use scripty_utils::do_paginate;
+use serenity::prelude::Mentionable;
+
+use crate::{Context, Error};
+
+/// List transcripts of past recorded sessions in this server.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "list"
+)]
+pub async fn transcripts_list(ctx: Context<'_>) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+
+	let sessions = scripty_audio_handler::list_sessions(guild_id).await?;
+	let premium_tier = scripty_premium::get_guild(guild_id.get())
+		.await
+		.unwrap_or_default();
+	let retention_days = scripty_audio_handler::get_tier_retention_days(premium_tier);
+
+	if sessions.is_empty() {
+		ctx.say(format_message!(
+			resolved_language,
+			"transcripts-list-no-sessions",
+			retentionDays: retention_days
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let formatted_sessions = sessions
+		.into_iter()
+		.map(|session| {
+			(
+				format_message!(resolved_language, "transcripts-list-embed-field-name", sessionId: session.id),
+				format_message!(
+					resolved_language,
+					"transcripts-list-embed-field-value",
+					voiceChannelMention: session.voice_channel_id.mention().to_string(),
+					startedAt: format!("<t:{}:f>", session.started_at),
+					endedAt: session.ended_at.map_or_else(
+						|| format_message!(resolved_language, "transcripts-list-not-finished"),
+						|t| format!("<t:{}:f>", t)
+					),
+					entryCount: session.entry_count
+				),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	do_paginate(
+		ctx.serenity_context(),
+		ctx.channel_id(),
+		formatted_sessions,
+		format_message!(resolved_language, "transcripts-list-embed-title"),
+		Some(format_message!(
+			resolved_language,
+			"transcripts-list-embed-footer",
+			retentionDays: retention_days
+		)),
+		None,
+		Some(ctx.author().id),
+	)
+	.await?;
+
+	Ok(())
+}
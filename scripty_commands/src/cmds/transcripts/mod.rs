@@ -0,0 +1,11 @@
+mod delete;
+mod download;
+mod list;
+mod root;
+mod search;
+
+pub use delete::transcripts_delete;
+pub use download::transcripts_download;
+pub use list::transcripts_list;
+pub use root::transcripts_root;
+pub use search::transcripts_search;
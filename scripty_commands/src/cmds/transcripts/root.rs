@@ -0,0 +1,24 @@
+use crate::{Context, Error};
+
+/// Manage transcripts of past recorded sessions.
+///
+/// Does nothing, instead check out the sub-commands of this command.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "transcripts"
+)]
+pub async fn transcripts_root(ctx: Context<'_>) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	ctx.say(
+		format_message!(resolved_language, "transcripts-root-response", contextPrefix: ctx.prefix()),
+	)
+	.await?;
+
+	Ok(())
+}
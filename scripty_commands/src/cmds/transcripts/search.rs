@@ -0,0 +1,148 @@
+use std::time::UNIX_EPOCH;
+
+use poise::CreateReply;
+use scripty_audio_handler::{TranscriptSearch, MAX_SEARCH_HITS};
+use scripty_utils::do_paginate_with;
+use serenity::{
+	model::{channel::GuildChannel, user::User},
+	prelude::Mentionable,
+};
+
+use crate::{Context, Error};
+
+/// Search what was said in this server's past recorded sessions.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_only,
+	required_permissions = "MANAGE_GUILD",
+	rename = "search"
+)]
+pub async fn transcripts_search(
+	ctx: Context<'_>,
+	#[description = "Words to look for. Every word must have been said for a result to match."]
+	query: String,
+	#[description = "Only show what this user said."] user: Option<User>,
+	#[description = "Only show what was said in this voice chat."]
+	#[channel_types("Voice", "Stage")]
+	voice_channel: Option<GuildChannel>,
+	#[description = "Only show what was said on or after this date, ie `2023-12-25`."]
+	after: Option<String>,
+	#[description = "Only show what was said on or before this date, ie `2023-12-25`."]
+	before: Option<String>,
+) -> Result<(), Error> {
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), Some(guild_id.get())).await;
+	// results can quote archived transcripts, so only show them to whoever searched
+	ctx.defer_ephemeral().await?;
+
+	let (after, before) = match (
+		after.as_deref().map(parse_date).transpose(),
+		before.as_deref().map(parse_date).transpose(),
+	) {
+		// the end date is inclusive, so search up to the start of the next day
+		(Ok(after), Ok(before)) => (after, before.map(|b| b + 86_400)),
+		_ => {
+			ctx.say(format_message!(
+				resolved_language,
+				"transcripts-search-invalid-date"
+			))
+			.await?;
+			return Ok(());
+		}
+	};
+
+	let results = scripty_audio_handler::search_sessions(
+		guild_id,
+		&TranscriptSearch {
+			query,
+			user_id: user.map(|u| u.id.get()),
+			voice_channel_id: voice_channel.map(|c| c.id.get()),
+			after,
+			before,
+		},
+	)
+	.await?;
+
+	if results.hits.is_empty() {
+		ctx.say(format_message!(
+			resolved_language,
+			"transcripts-search-no-results"
+		))
+		.await?;
+		return Ok(());
+	}
+
+	let footer = if results.hits.len() >= MAX_SEARCH_HITS {
+		Some(format_message!(
+			resolved_language,
+			"transcripts-search-embed-footer-truncated",
+			maxHits: MAX_SEARCH_HITS
+		))
+	} else if !results.complete {
+		Some(format_message!(
+			resolved_language,
+			"transcripts-search-embed-footer-incomplete"
+		))
+	} else {
+		None
+	};
+	let formatted_hits = results
+		.hits
+		.into_iter()
+		.map(|hit| {
+			let mut text = hit.text;
+			// embed field values are capped at 1024 characters
+			if text.chars().count() > 900 {
+				text = text.chars().take(900).chain(['…']).collect();
+			}
+			(
+				format_message!(
+					resolved_language,
+					"transcripts-search-embed-field-name",
+					username: hit.username,
+					timestamp: format!("<t:{}:f>", hit.timestamp)
+				),
+				format_message!(
+					resolved_language,
+					"transcripts-search-embed-field-value",
+					text: text,
+					voiceChannelMention: hit.voice_channel_id.mention().to_string(),
+					sessionId: hit.session_id
+				),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	do_paginate_with(
+		ctx.serenity_context(),
+		|embed, components| async move {
+			let reply = ctx
+				.send(
+					CreateReply::default()
+						.embed(embed)
+						.components(components)
+						.ephemeral(true),
+				)
+				.await?;
+			Ok(reply.message().await?.id)
+		},
+		formatted_hits,
+		format_message!(resolved_language, "transcripts-search-embed-title"),
+		footer,
+		Some(5),
+		Some(ctx.author().id),
+	)
+	.await?;
+
+	Ok(())
+}
+
+/// Parse a `YYYY-MM-DD` date into a Unix timestamp of the start of that day, in UTC.
+fn parse_date(date: &str) -> Result<i64, humantime::TimestampError> {
+	let time = humantime::parse_rfc3339(&format!("{}T00:00:00Z", date.trim()))?;
+	Ok(time
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |d| d.as_secs() as i64))
+}
@@ -0,0 +1,9 @@
+mod root;
+mod start;
+mod stop;
+mod voice;
+
+pub use root::tts_root;
+pub use start::tts_start;
+pub use stop::tts_stop;
+pub use voice::tts_voice;
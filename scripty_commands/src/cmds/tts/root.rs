@@ -0,0 +1,16 @@
+use crate::{Context, Error};
+
+/// Read a text channel aloud in voice chat.
+///
+/// Does nothing, instead check out the sub-commands of this command.
+#[poise::command(prefix_command, slash_command, guild_only, rename = "tts")]
+pub async fn tts_root(ctx: Context<'_>) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	ctx.say(format_message!(resolved_language, "tts-root-response", contextPrefix: ctx.prefix()))
+		.await?;
+
+	Ok(())
+}
@@ -0,0 +1,45 @@
+use scripty_bot_utils::checks::is_guild;
+use serenity::{model::channel::GuildChannel, prelude::Mentionable};
+
+use crate::{Context, Error};
+
+/// Start reading messages sent in a text channel aloud in the current voice call.
+///
+/// Scripty must already be in a voice call, started with the join command.
+#[poise::command(
+	prefix_command,
+	slash_command,
+	guild_cooldown = 15,
+	check = "is_guild",
+	rename = "start"
+)]
+pub async fn tts_start(
+	ctx: Context<'_>,
+	#[description = "Channel to read messages from. Defaults to the current channel."]
+	#[channel_types("Text", "Voice", "Stage", "PublicThread", "PrivateThread")]
+	text_channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+	let _typing = ctx.defer_or_broadcast().await;
+
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let text_channel_id = text_channel.map_or_else(|| ctx.channel_id(), |c| c.id);
+
+	let started =
+		scripty_audio_handler::start_tts(ctx.serenity_context(), guild_id, text_channel_id).await?;
+
+	ctx.say(if started {
+		format_message!(
+			resolved_language,
+			"tts-start-success",
+			channelMention: text_channel_id.mention().to_string()
+		)
+	} else {
+		format_message!(resolved_language, "tts-start-not-in-call", contextPrefix: ctx.prefix())
+	})
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,26 @@
+use scripty_bot_utils::checks::is_guild;
+
+use crate::{Context, Error};
+
+/// Stop reading messages aloud. Scripty stays in the voice call.
+#[poise::command(prefix_command, slash_command, check = "is_guild", rename = "stop")]
+pub async fn tts_stop(ctx: Context<'_>) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let guild_id = ctx.guild_id().ok_or_else(Error::expected_guild)?;
+	let was_running = scripty_audio_handler::stop_tts(ctx.serenity_context(), guild_id).await?;
+
+	ctx.say(format_message!(
+		resolved_language,
+		if was_running {
+			"tts-stop-success"
+		} else {
+			"tts-stop-not-running"
+		}
+	))
+	.await?;
+
+	Ok(())
+}
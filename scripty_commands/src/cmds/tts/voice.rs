@@ -0,0 +1,64 @@
+use crate::{Context, Error};
+
+/// Change the voice your messages are read aloud with. Unset options are left as they were.
+#[poise::command(prefix_command, slash_command, rename = "voice")]
+pub async fn tts_voice(
+	ctx: Context<'_>,
+	#[description = "espeak-ng voice name, ie `en`, `en-us`, or `fr`"] voice: Option<String>,
+	#[description = "Speed in words per minute. Defaults to 175."]
+	#[min = 80]
+	#[max = 450]
+	speed: Option<i16>,
+	#[description = "Pitch, from 0 to 99. Defaults to 50."]
+	#[min = 0]
+	#[max = 99]
+	pitch: Option<i16>,
+	#[description = "Volume, from 0 to 200. Defaults to 100."]
+	#[min = 0]
+	#[max = 200]
+	amplitude: Option<i16>,
+	#[description = "Extra pause between words, in units of 10ms. Defaults to 0."]
+	#[min = 0]
+	#[max = 255]
+	gap: Option<i16>,
+) -> Result<(), Error> {
+	let resolved_language =
+		scripty_i18n::get_resolved_language(ctx.author().id.get(), ctx.guild_id().map(|g| g.get()))
+			.await;
+
+	let db = scripty_db::get_db();
+	let hashed_user_id = scripty_utils::hash_user_id(ctx.author().id.get());
+	sqlx::query!(
+		"INSERT INTO users (user_id) VALUES ($1) ON CONFLICT ON CONSTRAINT users_pkey DO NOTHING",
+		hashed_user_id,
+	)
+	.execute(db)
+	.await?;
+	let params = sqlx::query!(
+		"UPDATE users SET tts_voice = COALESCE($1, tts_voice), tts_speed = COALESCE($2, \
+		 tts_speed), tts_pitch = COALESCE($3, tts_pitch), tts_amplitude = COALESCE($4, \
+		 tts_amplitude), tts_gap = COALESCE($5, tts_gap) WHERE user_id = $6 RETURNING tts_voice, \
+		 tts_speed, tts_pitch, tts_amplitude, tts_gap",
+		voice,
+		speed,
+		pitch,
+		amplitude,
+		gap,
+		hashed_user_id,
+	)
+	.fetch_one(db)
+	.await?;
+
+	ctx.say(format_message!(
+		resolved_language,
+		"tts-voice-updated",
+		voice: params.tts_voice,
+		speed: params.tts_speed,
+		pitch: params.tts_pitch,
+		amplitude: params.tts_amplitude,
+		gap: params.tts_gap
+	))
+	.await?;
+
+	Ok(())
+}
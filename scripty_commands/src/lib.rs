@@ -29,6 +29,13 @@ pub fn build_commands() -> Vec<poise::Command<Data, Error>> {
 				cmds::check_guilds(),
 				cmds::hash_user_id(),
 				cmds::cache_info(),
+				cmds::approve_integration(),
+				cmds::list_integrations(),
+				cmds::revoke_integration(),
+				cmds::stt_pool(),
+				cmds::stt_add(),
+				cmds::stt_drain(),
+				cmds::stt_reload(),
 			],
 			..cmds::admin()
 		},
@@ -49,9 +56,53 @@ pub fn build_commands() -> Vec<poise::Command<Data, Error>> {
 				cmds::automod::automod_add_rule(),
 				cmds::automod::automod_list_rules(),
 				cmds::automod::automod_remove_rule(),
+				cmds::automod::automod_strikes(),
+				cmds::automod::automod_test(),
+				cmds::automod::automod_monitor_only(),
+				cmds::automod::automod_exempt_role(),
+				cmds::automod::automod_exempt_channel(),
+				cmds::automod::automod_scope_rule(),
+				poise::Command {
+					subcommands: vec![
+						cmds::automod::group::automod_group_create(),
+						cmds::automod::group::automod_group_rename(),
+						cmds::automod::group::automod_group_set_action(),
+						cmds::automod::group::automod_group_toggle(),
+						cmds::automod::group::automod_group_delete(),
+						cmds::automod::group::automod_group_list(),
+						cmds::automod::group::automod_group_import(),
+						cmds::automod::group::automod_group_export(),
+					],
+					subcommand_required: true,
+					..cmds::automod::group::automod_group_root()
+				},
 			],
 			..cmds::automod::automod_root()
 		},
+		poise::Command {
+			subcommands: vec![
+				cmds::tts::tts_start(),
+				cmds::tts::tts_stop(),
+				cmds::tts::tts_voice(),
+			],
+			subcommand_required: true,
+			..cmds::tts::tts_root()
+		},
+		poise::Command {
+			subcommands: vec![
+				cmds::transcripts::transcripts_list(),
+				cmds::transcripts::transcripts_download(),
+				cmds::transcripts::transcripts_delete(),
+				cmds::transcripts::transcripts_search(),
+			],
+			subcommand_required: true,
+			..cmds::transcripts::transcripts_root()
+		},
+		poise::Command {
+			subcommands: vec![cmds::stats::stats_call()],
+			subcommand_required: true,
+			..cmds::stats::stats_root()
+		},
 		poise::Command {
 			subcommands: vec![
 				cmds::config::config_server_language(),
@@ -62,6 +113,9 @@ pub fn build_commands() -> Vec<poise::Command<Data, Error>> {
 				cmds::config::config_auto_detect_lang(),
 				cmds::config::config_transcribe_only_role(),
 				cmds::config::config_translate(),
+				cmds::config::config_live_transcripts(),
+				cmds::config::config_session_summaries(),
+				cmds::config::config_speech_commands(),
 			],
 			subcommand_required: true,
 			..cmds::config::config_root()
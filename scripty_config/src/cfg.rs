@@ -1,5 +1,19 @@
 use std::collections::HashMap;
 
+use crate::ConfigError;
+
+/// Shortest `max_utterance_length` allowed, in seconds.
+///
+/// Utterances are cut at quiet points in their last few seconds,
+/// so anything shorter would leave next to no room to speak before being cut.
+pub const MIN_MAX_UTTERANCE_LENGTH: u64 = 10;
+
+/// Longest `max_utterance_length` allowed, in seconds.
+///
+/// Longer utterances risk the STT service timing out before it's done transcribing them,
+/// and must stay short enough for their audio to be kept around to retry on another server.
+pub const MAX_MAX_UTTERANCE_LENGTH: u64 = 60;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BotConfig {
 	pub database: DatabaseConfig,
@@ -33,9 +47,33 @@ pub struct BotConfig {
 	/// Automated error webhook URL.
 	pub error_webhook: String,
 
+	/// Which STT backend transcribes audio. Defaults to the remote STT services.
+	#[serde(default)]
+	pub stt_backend: SttBackendConfig,
+
 	/// List of \["host", port] for the STT services.
+	///
+	/// Only used by the remote backend, so it may be left out if another backend is used.
+	#[serde(default)]
 	pub stt_services: Vec<SttServiceDefinition>,
 
+	/// How to pick which STT service a new stream is opened on. Defaults to round robin.
+	#[serde(default)]
+	pub stt_load_balancing: SttLoadBalancingStrategy,
+
+	/// How many times to try getting the result of a stream, in total, before giving up. Defaults to 3.
+	///
+	/// If an STT service disconnects or times out, the stream's audio is replayed on another one.
+	/// Each attempt can take up to 35 seconds, so users may wait this many times as long for a
+	/// transcript before being told it failed.
+	pub stt_finalize_attempts: Option<usize>,
+
+	/// Maximum length of one utterance, in seconds. Defaults to 20, and must be between 10 and 60.
+	///
+	/// Users who talk for longer than this without pausing have their speech cut into pieces,
+	/// which are transcribed separately.
+	pub max_utterance_length: Option<u64>,
+
 	/// Loki config
 	pub loki: LokiConfig,
 
@@ -49,6 +87,27 @@ pub struct BotConfig {
 	pub bot_lists: HashMap<String, BotListsConfig>,
 }
 
+impl BotConfig {
+	/// Check the values that parse fine, but that the bot can't work with.
+	pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+		validate_max_utterance_length(self.max_utterance_length)
+	}
+}
+
+fn validate_max_utterance_length(max_utterance_length: Option<u64>) -> Result<(), ConfigError> {
+	match max_utterance_length {
+		Some(length) if length < MIN_MAX_UTTERANCE_LENGTH => Err(ConfigError::OutOfRange(format!(
+			"max_utterance_length must be at least {} seconds, but is {}",
+			MIN_MAX_UTTERANCE_LENGTH, length
+		))),
+		Some(length) if length > MAX_MAX_UTTERANCE_LENGTH => Err(ConfigError::OutOfRange(format!(
+			"max_utterance_length must be at most {} seconds, but is {}",
+			MAX_MAX_UTTERANCE_LENGTH, length
+		))),
+		_ => Ok(()),
+	}
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DatabaseConfig {
 	pub host:     DatabaseConnection,
@@ -70,13 +129,55 @@ pub struct DmSupport {
 	pub guild_id:            u64,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 #[serde[untagged]]
 pub enum SttServiceDefinition {
 	IPTuple(String, u16),
 	HostString(String),
 }
 
+impl std::fmt::Display for SttServiceDefinition {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SttServiceDefinition::IPTuple(addr, port) => write!(f, "{}:{}", addr, port),
+			SttServiceDefinition::HostString(host) => write!(f, "{}", host),
+		}
+	}
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SttBackendConfig {
+	/// Send audio to the STT services in `stt_services`.
+	///
+	/// The STT transport only carries the text of a transcript,
+	/// so this backend reports no word timings or confidence.
+	#[default]
+	Remote,
+	/// Run whisper.cpp inside the bot, on the CPU.
+	///
+	/// Only available if the bot was built with the `whisper` feature.
+	Whisper {
+		/// Path to a GGML whisper model, like `ggml-base.en.bin`.
+		model_path: String,
+		/// CPU threads to transcribe with. Defaults to every available core.
+		threads:    Option<usize>,
+	},
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SttLoadBalancingStrategy {
+	/// Take turns between every service.
+	#[default]
+	RoundRobin,
+	/// Use whichever service has the fewest streams open.
+	LeastActiveStreams,
+	/// Prefer services that have been returning results quickly,
+	/// scaled by how many streams they already have open.
+	LatencyWeighted,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LokiConfig {
 	/// Loki ingest URL
@@ -103,6 +204,7 @@ mod tests {
 		net::{IpAddr, Ipv4Addr, SocketAddr},
 	};
 
+	use super::validate_max_utterance_length;
 	use crate::*;
 
 	#[test]
@@ -133,4 +235,50 @@ mod tests {
 			SttServiceDefinition::HostString(_) => panic!(),
 		};
 	}
+
+	#[test]
+	fn test_max_utterance_length() {
+		assert!(validate_max_utterance_length(None).is_ok());
+		assert!(validate_max_utterance_length(Some(MIN_MAX_UTTERANCE_LENGTH)).is_ok());
+		assert!(validate_max_utterance_length(Some(MAX_MAX_UTTERANCE_LENGTH)).is_ok());
+		assert!(matches!(
+			validate_max_utterance_length(Some(0)),
+			Err(ConfigError::OutOfRange(_))
+		));
+		assert!(matches!(
+			validate_max_utterance_length(Some(MIN_MAX_UTTERANCE_LENGTH - 1)),
+			Err(ConfigError::OutOfRange(_))
+		));
+		assert!(matches!(
+			validate_max_utterance_length(Some(MAX_MAX_UTTERANCE_LENGTH + 1)),
+			Err(ConfigError::OutOfRange(_))
+		));
+		assert!(matches!(
+			validate_max_utterance_length(Some(u64::MAX)),
+			Err(ConfigError::OutOfRange(_))
+		));
+	}
+
+	#[test]
+	fn test_stt_backend_config() {
+		#[derive(Deserialize)]
+		struct BotConfigTest {
+			#[serde(default)]
+			stt_backend: SttBackendConfig,
+		}
+
+		let parsed_cfg: BotConfigTest = toml::from_str("").unwrap();
+		assert_eq!(parsed_cfg.stt_backend, SttBackendConfig::Remote);
+
+		let parsed_cfg: BotConfigTest =
+			toml::from_str("[stt_backend]\ntype = \"whisper\"\nmodel_path = \"ggml-base.en.bin\"")
+				.unwrap();
+		assert_eq!(
+			parsed_cfg.stt_backend,
+			SttBackendConfig::Whisper {
+				model_path: "ggml-base.en.bin".to_string(),
+				threads:    None,
+			}
+		);
+	}
 }
@@ -1,17 +1,18 @@
-use std::fs;
+use std::{fmt, fs, io};
 
 use once_cell::sync::OnceCell;
 
 use crate::cfg::BotConfig;
 
 static GLOBAL_CONFIG: OnceCell<BotConfig> = OnceCell::new();
+static CONFIG_PATH: OnceCell<String> = OnceCell::new();
 
 pub fn load_config(cfg_path: &str) {
-	let cfg = fs::read(cfg_path).expect("failed to read config");
-	let cfg_str = String::from_utf8(cfg).expect("config is not valid utf8");
-
-	let parsed_cfg: BotConfig = toml::from_str(&cfg_str).expect("config invalid");
+	let parsed_cfg = read_config(cfg_path).expect("failed to load config");
 
+	CONFIG_PATH
+		.set(cfg_path.to_string())
+		.unwrap_or_else(|_| panic!("don't call `load_config()` more than once"));
 	GLOBAL_CONFIG
 		.set(parsed_cfg)
 		.unwrap_or_else(|_| panic!("don't call `load_config()` more than once"));
@@ -22,3 +23,57 @@ pub fn get_config() -> &'static BotConfig {
 		.get()
 		.expect("called `get_config()` before config was initialized")
 }
+
+/// Read the config file again, without replacing the config returned by [`get_config`].
+///
+/// Only the few parts of the bot that can change at runtime, like the STT services, use this.
+pub fn reload_config() -> Result<BotConfig, ConfigError> {
+	read_config(
+		CONFIG_PATH
+			.get()
+			.expect("called `reload_config()` before config was initialized"),
+	)
+}
+
+fn read_config(cfg_path: &str) -> Result<BotConfig, ConfigError> {
+	let cfg = fs::read(cfg_path)?;
+	let cfg_str = String::from_utf8(cfg).map_err(|_| ConfigError::InvalidUtf8)?;
+
+	let cfg: BotConfig = toml::from_str(&cfg_str)?;
+	cfg.validate()?;
+	Ok(cfg)
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+	Io(io::Error),
+	InvalidUtf8,
+	Invalid(toml::de::Error),
+	/// A value parsed, but is outside the range the bot can work with.
+	OutOfRange(String),
+}
+
+impl From<io::Error> for ConfigError {
+	fn from(err: io::Error) -> Self {
+		ConfigError::Io(err)
+	}
+}
+
+impl From<toml::de::Error> for ConfigError {
+	fn from(err: toml::de::Error) -> Self {
+		ConfigError::Invalid(err)
+	}
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ConfigError::Io(e) => write!(f, "failed to read config: {}", e),
+			ConfigError::InvalidUtf8 => write!(f, "config is not valid utf8"),
+			ConfigError::Invalid(e) => write!(f, "config invalid: {}", e),
+			ConfigError::OutOfRange(e) => write!(f, "config invalid: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for ConfigError {}
@@ -138,6 +138,10 @@ async fn async_init() {
 
 	scripty_db::init_db().await;
 
+	scripty_speech_commands::reload_integrations()
+		.await
+		.expect("failed to load speech command integrations");
+
 	scripty_data_storage::init_cache_async()
 		.await
 		.expect("failed to init cache");
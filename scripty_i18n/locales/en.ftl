@@ -26,6 +26,8 @@ cmds_join = join
     .target_channel-description = Send transcripts here, instead of the current channel. Target a forum to create a new post.
     .create_thread = create_thread
     .create_thread-description = Create a new thread for this transcription? Defaults to false.
+    .transcript_format = transcript_format
+    .transcript_format-description = Format to send logged transcripts in. Defaults to plain text.
 
 # This message is shown when the user is not in a voice channel, nor was a voice channel specified.
 no-channel-specified = You're not in a voice chat, nor did you tell me a channel to join. Try `{ $contextPrefix }join <channel>` to specify a voice chat, or join a voice chat yourself and re-run this command.
@@ -67,6 +69,41 @@ cmds_leave = leave
 # This is shown when the bot successfully leaves a voice call
 leave-success = Left VC successfully.
 
+## tts commands
+# This and all attributes show up exclusively in the slash command picker when `tts` is selected.
+cmds_tts_root = tts
+    .description = Read a text channel aloud in voice chat.
+tts-root-response = This is the root command, due to Discord limitations it does nothing. See `{ $contextPrefix }help tts` for more info.
+# This and all attributes show up exclusively in the slash command picker when `tts start` is selected.
+cmds_tts_start = start
+    .description = Start reading messages sent in a text channel aloud in the current voice call.
+    .text_channel = text_channel
+    .text_channel-description = Channel to read messages from. Defaults to the current channel.
+# This is shown when the bot starts reading messages from a channel aloud.
+tts-start-success = I'll now read messages sent in { $channelMention } aloud.
+# This is shown when the user tries to start TTS, but the bot isn't in a voice call.
+tts-start-not-in-call = I'm not in a voice call. Use `{ $contextPrefix }join` to have me join one first.
+# This and all attributes show up exclusively in the slash command picker when `tts stop` is selected.
+cmds_tts_stop = stop
+    .description = Stop reading messages aloud. Scripty stays in the voice call.
+tts-stop-success = I'll no longer read messages aloud.
+tts-stop-not-running = I wasn't reading any messages aloud.
+# This and all attributes show up exclusively in the slash command picker when `tts voice` is selected.
+cmds_tts_voice = voice
+    .description = Change the voice your messages are read aloud with.
+    .voice = voice
+    .voice-description = espeak-ng voice name, ie `en`, `en-us`, or `fr`
+    .speed = speed
+    .speed-description = Speed in words per minute. Defaults to 175.
+    .pitch = pitch
+    .pitch-description = Pitch, from 0 to 99. Defaults to 50.
+    .amplitude = amplitude
+    .amplitude-description = Volume, from 0 to 200. Defaults to 100.
+    .gap = gap
+    .gap-description = Extra pause between words, in units of 10ms. Defaults to 0.
+# This is shown when the user updates their TTS voice settings. `voice` is not translated, as it is a voice name.
+tts-voice-updated = Your messages will now be read with voice `{ $voice }`, at speed { $speed }, pitch { $pitch }, volume { $amplitude }, and word gap { $gap }.
+
 ## Help command
 # This and all attributes show up exclusively in the slash command picker when `help` is selected.
 cmds_help = help
@@ -164,6 +201,33 @@ config_translate = translate
 config-translate-enabled = Scripty will now translate transcriptions to English.
 config-translate-disabled = Scripty will now attempt to match the phrases being spoken to English words, but will not translate. 
 
+## config - live transcripts command
+config_live_transcripts = live_transcripts
+    .description = Show transcripts live while users are still speaking?
+    .live_transcripts = live_transcripts
+    .live_transcripts-description = Defaults to false
+
+config-live-transcripts-enabled = Scripty will now post transcripts while users are speaking, and update them until they finish.
+config-live-transcripts-disabled = Scripty will now only post transcripts once users finish speaking.
+
+## config - session summaries command
+config_session_summaries = session_summaries
+    .description = Send a summary alongside recorded transcripts?
+    .session_summaries = session_summaries
+    .session_summaries-description = Defaults to false
+
+config-session-summaries-enabled = Recorded transcripts will now come with a summary of who spoke, the key points, and the languages used.
+config-session-summaries-disabled = Recorded transcripts will no longer come with a summary.
+
+## config - speech commands command
+config_speech_commands = speech_commands
+    .description = Let users run "Hey Scripty" speech commands in voice chat?
+    .speech_commands = speech_commands
+    .speech_commands-description = Defaults to false
+
+config-speech-commands-enabled = Scripty will now listen for "Hey Scripty" speech commands. What users say after "Hey Scripty" is sent to the integration that handles the command.
+config-speech-commands-disabled = Scripty will no longer listen for "Hey Scripty" speech commands.
+
 ## Help menu translation strings
 
 command-not-found = No command with name `{ $commandName }` found.
@@ -307,6 +371,9 @@ cmds_add_rule = add_rule
     .rule_type = rule_type
     .rule_type-description = The type of rule to add. See `/automod rule_help` for more info.
     .rule_type-choice-Regular = Regular
+    .rule_type-choice-WholeWord = Whole word
+    .rule_type-choice-Regex = Regex
+    .rule_type-choice-Fuzzy = Fuzzy match
     .content = content
     .content-description = The rule content to add.
     .action = action
@@ -323,6 +390,8 @@ automod-add-rule-embed-failure-description-free-limit = Free servers are limited
 automod-add-rule-embed-failure-description-premium-limit = Premium tier { $tier } servers are limited to { $maxRules } rules. If you upgrade to tier { $nextTier }, you can add { $nextTierMaxRules } rules.
 automod-add-rule-embed-failure-description-premium-limit-hard-cap = You've reached the absolute maximum number of rules ({ $hardCap }). This limit exists to ensure we don't add too much latency in a single message.
 automod-add-rule-embed-failure-description-invalid-type = Invalid rule type. See `{ $contextPrefix }automod rule_help` for more info.
+# `error` is the reason the rule couldn't be used, and is not translated.
+automod-add-rule-embed-failure-description-invalid-rule = That rule can't be used: { $error }
 automod-add-rule-embed-failure-description-free-locked-type = Free servers can only use regular rules. If you'd like to use other rule types, check out our Premium over at https://scripty.org/premium.
 automod-add-rule-embed-failure-description-not-setup = You must run `{ $contextPrefix }automod setup` before adding rules.
 
@@ -353,6 +422,281 @@ automod-list-rules-embed-field-value = Type: { $ruleType }
 automod-list-rules-footer = Page { $page } of { $maxPage }
 automod-list-rules-no-rules = You don't have any rules!
 
+## automod strikes command
+# This and all attributes show up exclusively in the slash command picker when `automod strikes` is selected.
+cmds_automod_strikes = strikes
+    .description = Escalate the action taken against users that keep breaking rules.
+    .enabled = enabled
+    .enabled-description = Count strikes and escalate actions? Defaults to false.
+    .decay_hours = decay_hours
+    .decay_hours-description = Hours until a strike no longer counts. Defaults to 24.
+    .mute_at = mute_at
+    .mute_at-description = Strikes at which a user is muted. 0 to never mute. Defaults to 2.
+    .kick_at = kick_at
+    .kick_at-description = Strikes at which a user is removed from voice. 0 to never remove. Defaults to 3.
+    .timeout_at = timeout_at
+    .timeout_at-description = Strikes at which a user is timed out. 0 to never time out. Defaults to 5.
+    .timeout_minutes = timeout_minutes
+    .timeout_minutes-description = Minutes a timeout lasts. Defaults to 10.
+    .mute_minutes = mute_minutes
+    .mute_minutes-description = Minutes until a muted user is unmuted. 0 to never unmute. Defaults to 0.
+automod-strikes-not-setup = You must run `{ $contextPrefix }automod setup` before configuring strikes.
+# `option` is the name of the command option, which should not be translated.
+automod-strikes-out-of-range = `{ $option }` must be between { $min } and { $max }. Nothing was changed.
+automod-strikes-updated = { $enabled ->
+        [true] Strikes are enabled.
+       *[false] Strikes are disabled.
+    } Strikes last { $decayHours } hours. Users are muted at { $muteAt } strikes, removed from voice at { $kickAt }, and timed out for { $timeoutMinutes } minutes at { $timeoutAt }. { $muteMinutes ->
+        [0] Muted users stay muted until someone unmutes them.
+       *[other] Muted users are unmuted after { $muteMinutes } minutes.
+    } This takes effect the next time Scripty joins a voice channel.
+
+## automod test command
+# This and all attributes show up exclusively in the slash command picker when `automod test` is selected.
+cmds_automod_test = test
+    .description = Check which automod rule, if any, would be triggered by some text.
+    .text = text
+    .text-description = The text to check, as if someone had said it.
+automod-test-not-setup = You must run `{ $contextPrefix }automod setup` before testing rules.
+automod-test-no-match = No rule would be triggered by that.
+# `ruleType` and `action` are the names of a rule type and action, and are not translated.
+automod-test-match = Rule { $ruleId } would be triggered, and the action "{ $action }" would be taken.
+    Type: { $ruleType }
+    Content: { $ruleContent }
+    { $mode ->
+        [disabled] Automod is currently disabled, so nothing would actually happen.
+        [monitor] Automod is in monitor only mode, so this would only be logged.
+       *[active] {""}
+    }
+
+## automod monitor only command
+# This and all attributes show up exclusively in the slash command picker when `automod monitor_only` is selected.
+cmds_automod_monitor_only = monitor_only
+    .description = Only log what automod would have done, without deleting anything or punishing anyone.
+    .monitor_only = monitor_only
+    .monitor_only-description = Defaults to false
+automod-monitor-only-not-setup = You must run `{ $contextPrefix }automod setup` before enabling monitor only mode.
+automod-monitor-only-enabled = Automod is now in monitor only mode. Rule matches will be logged, but nothing will be deleted and nobody will be punished. This takes effect the next time Scripty joins a voice channel.
+automod-monitor-only-disabled = Automod is no longer in monitor only mode. This takes effect the next time Scripty joins a voice channel.
+
+## automod exempt role command
+# This and all attributes show up exclusively in the slash command picker when `automod exempt_role` is selected.
+cmds_automod_exempt_role = exempt_role
+    .description = Never take automod action against users with a role, such as staff.
+    .role = role
+    .role-description = The role to exempt, or stop exempting.
+    .exempt = exempt
+    .exempt-description = Whether users with this role are exempt from automod.
+automod-exempt-role-not-setup = You must run `{ $contextPrefix }automod setup` before exempting roles.
+automod-exempt-role-updated = { $exempt ->
+        [true] Users with <@&{ $roleId }> are now exempt from automod.
+       *[false] Users with <@&{ $roleId }> are no longer exempt from automod.
+    } This takes effect the next time Scripty joins a voice channel.
+
+## automod exempt channel command
+# This and all attributes show up exclusively in the slash command picker when `automod exempt_channel` is selected.
+cmds_automod_exempt_channel = exempt_channel
+    .description = Turn automod off in a voice channel.
+    .channel = channel
+    .channel-description = The voice channel to exempt, or stop exempting.
+    .exempt = exempt
+    .exempt-description = Whether automod is off in this channel.
+automod-exempt-channel-not-setup = You must run `{ $contextPrefix }automod setup` before exempting channels.
+automod-exempt-channel-updated = { $exempt ->
+        [true] Automod is now off in <#{ $channelId }>.
+       *[false] Automod is now on in <#{ $channelId }>.
+    } This takes effect the next time Scripty joins a voice channel.
+
+## automod scope rule command
+# This and all attributes show up exclusively in the slash command picker when `automod scope_rule` is selected.
+cmds_automod_scope_rule = scope_rule
+    .description = Make a rule only apply in some voice channels.
+    .rule_id = rule_id
+    .rule_id-description = The rule ID to scope.
+    .channel = channel
+    .channel-description = The voice channel to add to or remove from the rule's scope.
+    .scoped = scoped
+    .scoped-description = Whether the rule applies in this channel.
+automod-scope-rule-invalid-id = Invalid rule ID. Use `{ $contextPrefix }automod list_rules` to see every rule.
+automod-scope-rule-updated = { $channelCount ->
+        [0] Rule { $ruleId } now applies in every channel.
+        [one] Rule { $ruleId } now only applies in 1 channel.
+       *[other] Rule { $ruleId } now only applies in { $channelCount } channels.
+    } This takes effect the next time Scripty joins a voice channel.
+
+## automod group commands
+# This and all attributes show up exclusively in the slash command picker when `automod group` is selected.
+cmds_automod_group_root = group
+    .description = Manage groups of automod rules that share an action.
+automod-group-root-response = This is the root command, due to Discord limitations it does nothing. See `{ $contextPrefix }help automod group` for more info.
+automod-group-not-setup = You must run `{ $contextPrefix }automod setup` before using rule groups.
+automod-group-not-found = There's no rule group called { $groupName }. See `{ $contextPrefix }automod group list` for all of them.
+automod-group-name-invalid = Group names must be between 1 and { $maxLength } characters long.
+automod-group-name-taken = There's already a rule group called { $groupName }.
+# This and all attributes show up exclusively in the slash command picker when `automod group create` is selected.
+cmds_automod_group_create = create
+    .description = Create a group of automod rules that share an action.
+    .name = name
+    .name-description = Name of the group.
+    .action = action
+    .action-description = The action every rule in the group takes when triggered.
+    .action-choice-SilentDelete = Silent delete
+    .action-choice-DeleteAndLog = Delete and log
+    .action-choice-DeleteLogAndKick = Delete, log, and remove user from voice
+    .action-choice-DeleteLogAndSilence = Delete, log, and mute user
+# `action` is the name of an action, and is not translated.
+automod-group-create-success = Created rule group { $groupName }. Every rule in it will use the action "{ $action }". Add rules to it with `{ $contextPrefix }automod group import`.
+# This and all attributes show up exclusively in the slash command picker when `automod group rename` is selected.
+cmds_automod_group_rename = rename
+    .description = Rename a group of automod rules.
+    .group = group
+    .group-description = Current name of the group.
+    .new_name = new_name
+    .new_name-description = New name for the group.
+automod-group-rename-success = Renamed rule group { $oldName } to { $newName }.
+# This and all attributes show up exclusively in the slash command picker when `automod group set_action` is selected.
+cmds_automod_group_set_action = set_action
+    .description = Change the action every rule in a group takes.
+    .group = group
+    .group-description = Name of the group.
+    .action = action
+    .action-description = The action every rule in the group takes when triggered.
+    .action-choice-SilentDelete = Silent delete
+    .action-choice-DeleteAndLog = Delete and log
+    .action-choice-DeleteLogAndKick = Delete, log, and remove user from voice
+    .action-choice-DeleteLogAndSilence = Delete, log, and mute user
+# `action` is the name of an action, and is not translated.
+automod-group-set-action-success = Every rule in { $groupName } will now use the action "{ $action }". This takes effect the next time Scripty joins a voice channel.
+# This and all attributes show up exclusively in the slash command picker when `automod group toggle` is selected.
+cmds_automod_group_toggle = toggle
+    .description = Turn every rule in a group on or off at once.
+    .group = group
+    .group-description = Name of the group.
+    .enabled = enabled
+    .enabled-description = Whether the group's rules should be checked.
+automod-group-toggle-enabled = Rules in { $groupName } are now enabled. This takes effect the next time Scripty joins a voice channel.
+automod-group-toggle-disabled = Rules in { $groupName } are now disabled. This takes effect the next time Scripty joins a voice channel.
+# This and all attributes show up exclusively in the slash command picker when `automod group delete` is selected.
+cmds_automod_group_delete = delete
+    .description = Delete a group of automod rules, and every rule in it.
+    .group = group
+    .group-description = Name of the group to delete.
+automod-group-delete-success = Deleted rule group { $groupName } and its { $ruleCount } rules.
+# This and all attributes show up exclusively in the slash command picker when `automod group list` is selected.
+cmds_automod_group_list = list
+    .description = List every group of automod rules in this server.
+automod-group-list-embed-title = Automod rule groups
+# `action` is the name of an action, and is not translated.
+automod-group-list-field-value = Action: { $action }
+    Rules: { $ruleCount }
+    { $enabled ->
+        [true] Enabled
+       *[false] Disabled
+    }
+automod-group-list-no-groups = You don't have any rule groups! Create one with `{ $contextPrefix }automod group create`.
+# This and all attributes show up exclusively in the slash command picker when `automod group import` is selected.
+cmds_automod_group_import = import
+    .description = Add every rule in a CSV or JSON file to a group.
+    .group = group
+    .group-description = Name of the group to add the rules to.
+    .file = file
+    .file-description = CSV or JSON file of rules. Export a group to see the format.
+automod-group-import-invalid-format = Rule files must be CSV or JSON, ending in `.csv` or `.json`.
+automod-group-import-too-large = Rule files can be at most { $maxSize } MiB.
+# `error` is the reason the file couldn't be read, and is not translated.
+automod-group-import-invalid-file = That file couldn't be read: { $error }
+# `error` is the reason the rule couldn't be used, and is not translated.
+automod-group-import-invalid-rule = Rule { $ruleNumber } in that file can't be used: { $error }
+automod-group-import-too-many-rules = That file has { $ruleCount } rules, but you only have { $rulesLeft } rules left out of { $maxRules }.
+automod-group-import-success = Imported { $imported } rules into { $groupName }. { $skipped } rules were skipped, as they already exist.
+# This and all attributes show up exclusively in the slash command picker when `automod group export` is selected.
+cmds_automod_group_export = export
+    .description = Download every rule in a group as a CSV or JSON file.
+    .group = group
+    .group-description = Name of the group to export.
+    .format = format
+    .format-description = Format of the file. Defaults to CSV.
+    .format-choice-Csv = CSV
+    .format-choice-Json = JSON
+automod-group-export-success = Exported { $ruleCount } rules from { $groupName }.
+
+## transcripts commands
+# This and all attributes show up exclusively in the slash command picker when `transcripts` is selected.
+cmds_transcripts_root = transcripts
+    .description = Manage transcripts of past recorded sessions.
+transcripts-root-response = This is the root command, due to Discord limitations it does nothing. See `{ $contextPrefix }help transcripts` for more info.
+# This and all attributes show up exclusively in the slash command picker when `transcripts list` is selected.
+cmds_transcripts_list = list
+    .description = List transcripts of past recorded sessions in this server.
+transcripts-list-embed-title = Recorded sessions
+transcripts-list-embed-field-name = Session { $sessionId }
+transcripts-list-embed-field-value = Channel: { $voiceChannelMention }
+    Started: { $startedAt }
+    Ended: { $endedAt }
+    Entries: { $entryCount }
+# This is shown instead of an end time for sessions that are still going, or were cut off.
+transcripts-list-not-finished = not finished
+transcripts-list-embed-footer = Transcripts are kept for { $retentionDays } days.
+transcripts-list-no-sessions = There are no recorded sessions in this server. Transcripts are kept for { $retentionDays } days.
+# This and all attributes show up exclusively in the slash command picker when `transcripts download` is selected.
+cmds_transcripts_download = download
+    .description = Download the transcript of a past recorded session.
+    .session_id = session_id
+    .session_id-description = The session ID to download. See `/transcripts list`.
+    .format = format
+    .format-description = Format to download the transcript in. Defaults to the one it was recorded in.
+transcripts-download-success = Here's the transcript of session { $sessionId }.
+# This and all attributes show up exclusively in the slash command picker when `transcripts delete` is selected.
+cmds_transcripts_delete = delete
+    .description = Delete the transcript of a past recorded session.
+    .session_id = session_id
+    .session_id-description = The session ID to delete. See `/transcripts list`.
+transcripts-delete-success = Deleted session { $sessionId }.
+# This and all attributes show up exclusively in the slash command picker when `transcripts search` is selected.
+cmds_transcripts_search = search
+    .description = Search what was said in this server's past recorded sessions.
+    .query = query
+    .query-description = Words to look for. Every word must have been said for a result to match.
+    .user = user
+    .user-description = Only show what this user said.
+    .voice_channel = voice_channel
+    .voice_channel-description = Only show what was said in this voice chat.
+    .after = after
+    .after-description = Only show what was said on or after this date, ie `2023-12-25`.
+    .before = before
+    .before-description = Only show what was said on or before this date, ie `2023-12-25`.
+transcripts-search-embed-title = Search results
+transcripts-search-embed-field-name = { $username } at { $timestamp }
+transcripts-search-embed-field-value = { $text }
+    In { $voiceChannelMention }, session { $sessionId }
+# This is shown in the footer when there were more results than could be shown.
+transcripts-search-embed-footer-truncated = Only the newest { $maxHits } results are shown. Try a more specific search.
+# This is shown in the footer when only the most recent sessions could be searched.
+transcripts-search-embed-footer-incomplete = Only the most recent sessions were searched. Try narrowing it down by date, user, or voice chat.
+transcripts-search-no-results = Nothing matched your search.
+# Dates are always in the format `YYYY-MM-DD`, which should not be translated.
+transcripts-search-invalid-date = Dates must be in the format `YYYY-MM-DD`, ie `2023-12-25`.
+transcripts-invalid-session = There's no session with that ID in this server. See `{ $contextPrefix }transcripts list` for a list of sessions.
+
+## stats commands
+# This and all attributes show up exclusively in the slash command picker when `stats` is selected.
+cmds_stats_root = stats
+    .description = See statistics about how Scripty is being used.
+stats-root-response = This is the root command, due to Discord limitations it does nothing. See `{ $contextPrefix }help stats` for more info.
+# This and all attributes show up exclusively in the slash command picker when `stats call` is selected.
+cmds_stats_call = call
+    .description = See how much each user has taken part in the current call.
+stats-call-embed-title = Call statistics
+# One line per user who has spoken. `speakingShare` is a percentage of the total time anyone spoke.
+stats-call-speaker = { $userMention }: { $speakingSeconds }s ({ $speakingShare }%), { $utterances } utterances, { $words } words
+# Added after the list of speakers when there are too many to show them all.
+stats-call-more-speakers = ...and { $count } more
+stats-call-no-speakers = Nobody has spoken yet.
+stats-call-embed-footer = Call has been going for { $minutes } minutes
+# This is the footer of the statistics sent along with the transcript once a call ends.
+stats-call-ended-embed-footer = Call lasted { $minutes } minutes
+stats-call-not-in-call = I'm not in a voice call. Use `{ $contextPrefix }join` to have me join one first.
+
 ## vote reminder command
 cmds_vote_reminder = vote_reminder
     .description = Toggle whether Scripty will remind you to vote for the bot after the time limit has passed.
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use chrono::{NaiveDateTime, Utc};
 use once_cell::sync::OnceCell;
 use prometheus::{
+	GaugeVec,
 	Histogram,
 	HistogramOpts,
 	IntCounter,
@@ -150,8 +151,11 @@ make_static_metric! {
 
 pub static METRICS: OnceCell<Arc<Metrics>> = OnceCell::new();
 
+/// Get the metrics, creating them if the runtime monitor hasn't yet.
+///
+/// Creating them here lets crates that record metrics be tested without starting the monitor.
 pub fn get_metrics() -> Arc<Metrics> {
-	METRICS.get().expect("metrics not initialized").clone()
+	METRICS.get_or_init(Metrics::new).clone()
 }
 
 pub struct Metrics {
@@ -170,6 +174,10 @@ pub struct Metrics {
 	pub total_commands:           IntCounter,
 	pub stt_server_fetch_success: IntCounter,
 	pub stt_server_fetch_failure: IntCounter,
+	pub stt_finalize_retries:     IntCounter,
+	pub stt_server_in_flight:     IntGaugeVec,
+	pub stt_server_latency:       GaugeVec,
+	pub stt_server_healthy:       IntGaugeVec,
 	pub commands:                 IntCounterVec,
 	pub runtime_metrics:          RuntimeMetricsVec,
 	pub latency:                  LatencyVec,
@@ -288,6 +296,51 @@ impl Metrics {
 			.register(Box::new(stt_server_fetch_failure.clone()))
 			.unwrap();
 
+		let stt_finalize_retries = IntCounter::new(
+			"stt_finalize_retries",
+			"Streams replayed on another STT server after failing to finalize",
+		)
+		.unwrap();
+		registry
+			.register(Box::new(stt_finalize_retries.clone()))
+			.unwrap();
+
+		let stt_server_in_flight = IntGaugeVec::new(
+			Opts::new(
+				"stt_server_in_flight",
+				"Streams currently open on each STT server",
+			),
+			&["server"],
+		)
+		.unwrap();
+		registry
+			.register(Box::new(stt_server_in_flight.clone()))
+			.unwrap();
+
+		let stt_server_latency = GaugeVec::new(
+			Opts::new(
+				"stt_server_latency",
+				"Moving average of seconds each STT server takes to return a result",
+			),
+			&["server"],
+		)
+		.unwrap();
+		registry
+			.register(Box::new(stt_server_latency.clone()))
+			.unwrap();
+
+		let stt_server_healthy = IntGaugeVec::new(
+			Opts::new(
+				"stt_server_healthy",
+				"Whether each STT server passed its last health check",
+			),
+			&["server"],
+		)
+		.unwrap();
+		registry
+			.register(Box::new(stt_server_healthy.clone()))
+			.unwrap();
+
 		let up = IntCounter::new("up", "Always 1").unwrap();
 		up.inc();
 		registry.register(Box::new(up)).unwrap();
@@ -310,6 +363,10 @@ impl Metrics {
 			latency: latency_static,
 			stt_server_fetch_success,
 			stt_server_fetch_failure,
+			stt_finalize_retries,
+			stt_server_in_flight,
+			stt_server_latency,
+			stt_server_healthy,
 		})
 	}
 }
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use reqwest::{Error as ReqwestError, StatusCode};
+
+#[derive(Debug)]
+pub enum Error {
+	/// The integration didn't respond within [`crate::WEBHOOK_TIMEOUT`].
+	Timeout,
+	Reqwest(ReqwestError),
+	/// The integration responded with a body that didn't match the documented format.
+	Json(serde_json::Error),
+	/// The integration responded with a status code that isn't documented.
+	StatusCode(StatusCode),
+}
+
+impl From<ReqwestError> for Error {
+	fn from(error: ReqwestError) -> Self {
+		if error.is_timeout() {
+			Self::Timeout
+		} else {
+			Self::Reqwest(error)
+		}
+	}
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(error: serde_json::Error) -> Self {
+		Self::Json(error)
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::Timeout => f.write_str("integration timed out"),
+			Error::Reqwest(e) => write!(f, "Reqwest error: {}", e),
+			Error::Json(e) => write!(f, "JSON error: {}", e),
+			Error::StatusCode(e) => write!(f, "Status code error: {}", e),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,189 @@
+use std::sync::{Arc, OnceLock};
+
+use parking_lot::RwLock;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// Maximum number of commands one integration can register.
+pub const MAX_COMMANDS: usize = 25;
+
+/// Maximum length of a single command, in characters.
+pub const MAX_COMMAND_LENGTH: usize = 32;
+
+/// A bot that receives speech commands over a webhook.
+#[derive(Debug)]
+pub struct SpeechCommandIntegration {
+	/// Database ID of the integration.
+	pub id:             i32,
+	/// Discord ID of the user who owns the integration.
+	pub owner_id:       u64,
+	/// Name of the integration, used in error messages.
+	pub name:           String,
+	/// URL the webhook is POSTed to.
+	pub webhook_url:    String,
+	/// Secret used to sign each webhook with HMAC-SHA256.
+	pub signing_secret: String,
+	/// Commands this integration handles, ie `["play", "skip song"]`.
+	pub commands:       Vec<String>,
+	/// espeak-ng voice to speak replies with.
+	pub tts_voice:      String,
+}
+
+/// Credentials for a newly approved integration.
+///
+/// These are only ever available once: the API token is stored hashed.
+pub struct NewIntegration {
+	pub id:             i32,
+	/// Secret the integration uses to verify webhooks came from us.
+	pub signing_secret: String,
+	/// Token the integration uses to authenticate with our API.
+	pub api_token:      String,
+}
+
+/// Why a list of commands was rejected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidCommands {
+	/// More than [`MAX_COMMANDS`] commands were given.
+	TooMany,
+	/// A command had no letters or numbers in it.
+	Empty,
+	/// A command was longer than [`MAX_COMMAND_LENGTH`].
+	TooLong,
+}
+
+type IntegrationList = Arc<Vec<Arc<SpeechCommandIntegration>>>;
+
+static INTEGRATIONS: OnceLock<RwLock<IntegrationList>> = OnceLock::new();
+
+fn integrations_lock() -> &'static RwLock<IntegrationList> {
+	INTEGRATIONS.get_or_init(|| RwLock::new(Arc::new(Vec::new())))
+}
+
+/// Get all registered speech command integrations, as of the last call to [`reload_integrations`].
+pub fn get_integrations() -> IntegrationList {
+	Arc::clone(&integrations_lock().read())
+}
+
+/// Reload all integrations from the database.
+///
+/// Call this at startup, and after any change to an integration.
+pub async fn reload_integrations() -> Result<(), sqlx::Error> {
+	let integrations = sqlx::query!(
+		"SELECT id, owner_id, name, webhook_url, signing_secret, commands, tts_voice FROM \
+		 speech_command_integrations ORDER BY id"
+	)
+	.fetch_all(scripty_db::get_db())
+	.await?
+	.into_iter()
+	.map(|row| {
+		Arc::new(SpeechCommandIntegration {
+			id:             row.id,
+			owner_id:       row.owner_id as u64,
+			name:           row.name,
+			webhook_url:    row.webhook_url,
+			signing_secret: row.signing_secret,
+			commands:       row.commands,
+			tts_voice:      row.tts_voice,
+		})
+	})
+	.collect::<Vec<_>>();
+
+	debug!("loaded {} speech command integrations", integrations.len());
+	*integrations_lock().write() = Arc::new(integrations);
+
+	Ok(())
+}
+
+/// Register a new integration, generating its signing secret and API token.
+pub async fn approve_integration(
+	owner_id: u64,
+	name: String,
+	webhook_url: String,
+	tts_voice: String,
+) -> Result<NewIntegration, sqlx::Error> {
+	let signing_secret = generate_secret();
+	let api_token = generate_secret();
+
+	let id = sqlx::query!(
+		"INSERT INTO speech_command_integrations (owner_id, name, webhook_url, signing_secret, \
+		 api_token_hash, tts_voice) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+		owner_id as i64,
+		name,
+		webhook_url,
+		signing_secret,
+		hash_api_token(&api_token),
+		tts_voice
+	)
+	.fetch_one(scripty_db::get_db())
+	.await?
+	.id;
+	reload_integrations().await?;
+
+	Ok(NewIntegration {
+		id,
+		signing_secret,
+		api_token,
+	})
+}
+
+/// Remove an integration. Returns `false` if it didn't exist.
+pub async fn revoke_integration(id: i32) -> Result<bool, sqlx::Error> {
+	let res = sqlx::query!("DELETE FROM speech_command_integrations WHERE id = $1", id)
+		.execute(scripty_db::get_db())
+		.await?;
+	reload_integrations().await?;
+
+	Ok(res.rows_affected() != 0)
+}
+
+/// Find the integration an API token belongs to.
+pub async fn authenticate(api_token: &str) -> Result<Option<i32>, sqlx::Error> {
+	Ok(sqlx::query!(
+		"SELECT id FROM speech_command_integrations WHERE api_token_hash = $1",
+		hash_api_token(api_token)
+	)
+	.fetch_optional(scripty_db::get_db())
+	.await?
+	.map(|row| row.id))
+}
+
+/// Check a list of commands is acceptable to register.
+pub fn validate_commands(commands: &[String]) -> Result<(), InvalidCommands> {
+	if commands.len() > MAX_COMMANDS {
+		return Err(InvalidCommands::TooMany);
+	}
+	for command in commands {
+		if !command.chars().any(char::is_alphanumeric) {
+			return Err(InvalidCommands::Empty);
+		}
+		if command.chars().count() > MAX_COMMAND_LENGTH {
+			return Err(InvalidCommands::TooLong);
+		}
+	}
+	Ok(())
+}
+
+/// Replace the commands an integration handles.
+///
+/// Call [`validate_commands`] first.
+pub async fn set_commands(id: i32, commands: Vec<String>) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"UPDATE speech_command_integrations SET commands = $1 WHERE id = $2",
+		&commands,
+		id
+	)
+	.execute(scripty_db::get_db())
+	.await?;
+	reload_integrations().await
+}
+
+/// 32 random bytes, hex encoded.
+fn generate_secret() -> String {
+	let mut bytes = [0; 32];
+	rand::thread_rng().fill_bytes(&mut bytes);
+	hex::encode(bytes)
+}
+
+fn hash_api_token(api_token: &str) -> Vec<u8> {
+	Sha256::digest(api_token.as_bytes()).to_vec()
+}
@@ -0,0 +1,27 @@
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate tracing;
+
+mod error;
+mod integrations;
+mod matcher;
+mod webhook;
+
+pub use error::Error;
+pub use integrations::{
+	approve_integration,
+	authenticate,
+	get_integrations,
+	reload_integrations,
+	revoke_integration,
+	set_commands,
+	validate_commands,
+	InvalidCommands,
+	NewIntegration,
+	SpeechCommandIntegration,
+	MAX_COMMANDS,
+	MAX_COMMAND_LENGTH,
+};
+pub use matcher::{find_command, SpeechCommand};
+pub use webhook::{dispatch, SpeechCommandResponse, WEBHOOK_TIMEOUT};
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use crate::SpeechCommandIntegration;
+
+/// Words that must be said, in order, before a command.
+const WAKE_PHRASE: [&str; 2] = ["hey", "scripty"];
+
+/// A speech command found in a transcript.
+#[derive(Debug)]
+pub struct SpeechCommand {
+	/// The integration that registered this command.
+	pub integration: Arc<SpeechCommandIntegration>,
+	/// The command, exactly as the integration registered it.
+	pub command:     String,
+	/// Everything said after the command, if anything.
+	pub remainder:   Option<String>,
+}
+
+/// Search a transcript for the wake phrase followed by a command registered by one of `integrations`.
+///
+/// Matching ignores case and punctuation. If several commands match, the longest one wins,
+/// and ties go to the integration listed first.
+pub fn find_command(
+	transcript: &str,
+	integrations: &[Arc<SpeechCommandIntegration>],
+) -> Option<SpeechCommand> {
+	// pair each word as spoken with its normalized form, dropping words that are only punctuation
+	let words = transcript
+		.split_whitespace()
+		.map(|w| (w, normalize(w)))
+		.filter(|(_, n)| !n.is_empty())
+		.collect::<Vec<_>>();
+
+	let after_wake = words
+		.windows(WAKE_PHRASE.len())
+		.position(|w| w.iter().map(|(_, n)| n.as_str()).eq(WAKE_PHRASE))?
+		+ WAKE_PHRASE.len();
+	let rest = &words[after_wake..];
+
+	let mut best: Option<(&Arc<SpeechCommandIntegration>, &String, usize)> = None;
+	for integration in integrations {
+		for command in &integration.commands {
+			let command_words = command
+				.split_whitespace()
+				.map(normalize)
+				.filter(|n| !n.is_empty())
+				.collect::<Vec<_>>();
+			if command_words.is_empty()
+				|| command_words.len() > rest.len()
+				|| best.is_some_and(|(_, _, len)| len >= command_words.len())
+			{
+				continue;
+			}
+
+			if rest.iter().zip(&command_words).all(|((_, n), c)| n == c) {
+				best = Some((integration, command, command_words.len()));
+			}
+		}
+	}
+
+	let (integration, command, len) = best?;
+	let remainder = rest[len..]
+		.iter()
+		.map(|(w, _)| *w)
+		.collect::<Vec<_>>()
+		.join(" ");
+	let remainder = remainder.trim_matches(|c: char| !c.is_alphanumeric());
+
+	Some(SpeechCommand {
+		integration: Arc::clone(integration),
+		command:     command.clone(),
+		remainder:   (!remainder.is_empty()).then(|| remainder.to_string()),
+	})
+}
+
+/// Lowercase a word and strip everything that isn't a letter or number.
+fn normalize(word: &str) -> String {
+	word.chars()
+		.filter(|c| c.is_alphanumeric())
+		.flat_map(char::to_lowercase)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn integration(name: &str, commands: &[&str]) -> Arc<SpeechCommandIntegration> {
+		Arc::new(SpeechCommandIntegration {
+			id:             0,
+			owner_id:       0,
+			name:           name.to_string(),
+			webhook_url:    String::new(),
+			signing_secret: String::new(),
+			commands:       commands.iter().map(|c| c.to_string()).collect(),
+			tts_voice:      "en".to_string(),
+		})
+	}
+
+	#[test]
+	fn test_find_command() {
+		let integrations = [integration("music", &["play", "pause"])];
+
+		let cmd =
+			find_command("Hey Scripty, play Never Gonna Give You Up.", &integrations).unwrap();
+		assert_eq!(cmd.integration.name, "music");
+		assert_eq!(cmd.command, "play");
+		assert_eq!(cmd.remainder.as_deref(), Some("Never Gonna Give You Up"));
+
+		let cmd = find_command("um, hey scripty... pause!", &integrations).unwrap();
+		assert_eq!(cmd.command, "pause");
+		assert_eq!(cmd.remainder, None);
+	}
+
+	#[test]
+	fn test_no_match() {
+		let integrations = [integration("music", &["play"])];
+
+		// no wake phrase
+		assert!(find_command("play something", &integrations).is_none());
+		// wake phrase, but no registered command
+		assert!(find_command("Hey Scripty, stop", &integrations).is_none());
+		// wake phrase out of order
+		assert!(find_command("Scripty hey play something", &integrations).is_none());
+	}
+
+	#[test]
+	fn test_longest_command_wins() {
+		let integrations = [
+			integration("first", &["skip"]),
+			integration("second", &["skip song", "skip"]),
+		];
+
+		let cmd = find_command("Hey Scripty, skip song please", &integrations).unwrap();
+		assert_eq!(cmd.integration.name, "second");
+		assert_eq!(cmd.command, "skip song");
+		assert_eq!(cmd.remainder.as_deref(), Some("please"));
+
+		let cmd = find_command("Hey Scripty, skip this", &integrations).unwrap();
+		assert_eq!(cmd.integration.name, "first");
+	}
+}
@@ -0,0 +1,125 @@
+use std::{
+	sync::OnceLock,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use reqwest::{header::CONTENT_TYPE, Client, StatusCode};
+use sha2::Sha256;
+
+use crate::{Error, SpeechCommand};
+
+/// How long an integration has to respond before we give up on it.
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+	command:   &'a str,
+	remainder: Option<&'a str>,
+	user:      u64,
+	guild:     u64,
+}
+
+#[derive(Deserialize)]
+struct TextResponse {
+	text:          String,
+	#[serde(default)]
+	high_priority: bool,
+}
+
+/// What the integration asked us to do in response to a speech command.
+#[derive(Debug)]
+pub enum SpeechCommandResponse {
+	/// HTTP 204: play a "ding" to let the user know the command was received.
+	Acknowledged,
+	/// HTTP 200: speak `text`, mixing it over anything else playing if `high_priority` is set.
+	Reply {
+		text:          String,
+		high_priority: bool,
+	},
+	/// HTTP 400: speak `text` at low priority, DMing it to the user if it doesn't start playing
+	/// within [`WEBHOOK_TIMEOUT`].
+	UserError { text: String },
+	/// HTTP 500: speak `text` at low priority, and DM it to the user.
+	IntegrationError { text: String },
+}
+
+/// Send a speech command to its integration, and parse the response.
+///
+/// The body is signed with the integration's secret: the `X-Scripty-Signature` header is
+/// `sha256=` followed by the hex HMAC-SHA256 of `{X-Scripty-Timestamp}.{body}`.
+pub async fn dispatch(
+	command: &SpeechCommand,
+	user_id: u64,
+	guild_id: u64,
+) -> Result<SpeechCommandResponse, Error> {
+	let integration = &command.integration;
+	let body = serde_json::to_vec(&WebhookBody {
+		command:   &command.command,
+		remainder: command.remainder.as_deref(),
+		user:      user_id,
+		guild:     guild_id,
+	})?;
+
+	let timestamp = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_secs()
+		.to_string();
+	let signature = sign(&integration.signing_secret, &timestamp, &body);
+
+	debug!(
+		integration = %integration.name,
+		command = %command.command,
+		"dispatching speech command"
+	);
+	let client = CLIENT.get_or_init(|| {
+		Client::builder()
+			.timeout(WEBHOOK_TIMEOUT)
+			.build()
+			.expect("failed to build speech command client")
+	});
+	let res = client
+		.post(&integration.webhook_url)
+		.header(CONTENT_TYPE, "application/json")
+		.header("X-Scripty-Timestamp", timestamp)
+		.header("X-Scripty-Signature", format!("sha256={}", signature))
+		.body(body)
+		.send()
+		.await?;
+
+	let status = res.status();
+	match status {
+		StatusCode::NO_CONTENT => Ok(SpeechCommandResponse::Acknowledged),
+		StatusCode::OK => {
+			let TextResponse {
+				text,
+				high_priority,
+			} = serde_json::from_slice(&res.bytes().await?)?;
+			Ok(SpeechCommandResponse::Reply {
+				text,
+				high_priority,
+			})
+		}
+		StatusCode::BAD_REQUEST => {
+			let TextResponse { text, .. } = serde_json::from_slice(&res.bytes().await?)?;
+			Ok(SpeechCommandResponse::UserError { text })
+		}
+		StatusCode::INTERNAL_SERVER_ERROR => {
+			let TextResponse { text, .. } = serde_json::from_slice(&res.bytes().await?)?;
+			Ok(SpeechCommandResponse::IntegrationError { text })
+		}
+		status => Err(Error::StatusCode(status)),
+	}
+}
+
+fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+	let mut mac =
+		Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+	mac.update(timestamp.as_bytes());
+	mac.update(b".");
+	mac.update(body);
+	hex::encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,118 @@
+//! Backends that do the actual transcribing, and the streams they open.
+//!
+//! Which backend is used is picked in the config, and set up by [`crate::init_stt`].
+
+use std::future::Future;
+
+use futures::future::BoxFuture;
+use once_cell::sync::OnceCell;
+
+use crate::{ModelError, Transcript};
+
+pub(crate) static BACKEND: OnceCell<Box<dyn SttBackend>> = OnceCell::new();
+
+/// Something that can transcribe audio, such as a pool of remote STT servers.
+#[async_trait]
+pub trait SttBackend: Send + Sync {
+	/// Open a new stream to feed audio to.
+	async fn open_stream(&self) -> Result<Box<dyn SttStream>, ModelError>;
+}
+
+/// One stream of audio being transcribed by a backend.
+#[async_trait]
+pub trait SttStream: Send + Sync {
+	fn feed_audio(&self, data: Vec<i16>) -> Result<(), ModelError>;
+
+	/// Make sure [`Self::get_partial_result`] can be used.
+	fn enable_partial_results(&self);
+
+	/// Get a transcript of the most recent audio fed so far, without finalizing the stream.
+	///
+	/// How much audio is transcribed should be bounded, so this costs the same however long
+	/// the stream gets. The returned future must not borrow the stream.
+	///
+	/// Backends that can't transcribe partial audio return [`ModelError::PartialResultsDisabled`].
+	fn get_partial_result(
+		&self,
+		language: String,
+		translate: bool,
+	) -> BoxFuture<'static, Result<String, ModelError>>;
+
+	/// Finalize the stream, and get a transcript of all the audio fed to it.
+	async fn get_result(
+		self: Box<Self>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError>;
+
+	/// Like [`Self::get_result`], but tries up to `attempts` times in total
+	/// if the backend fails in a way that isn't the audio's fault.
+	///
+	/// Backends that can't fail like that don't need to implement this.
+	async fn get_result_with_retries(
+		self: Box<Self>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+		_attempts: usize,
+	) -> Result<Transcript, ModelError> {
+		self.get_result(language, verbose, translate).await
+	}
+}
+
+/// A stream of audio being transcribed, by whichever backend is in use.
+pub struct Stream(Box<dyn SttStream>);
+
+impl Stream {
+	pub(crate) fn new(stream: Box<dyn SttStream>) -> Self {
+		Self(stream)
+	}
+
+	/// Keep enough of the audio fed to this stream that [`Self::get_partial_result`] can be used.
+	///
+	/// Call it before feeding any audio.
+	pub fn enable_partial_results(&self) {
+		self.0.enable_partial_results()
+	}
+
+	pub fn feed_audio(&self, data: Vec<i16>) -> Result<(), ModelError> {
+		self.0.feed_audio(data)
+	}
+
+	/// Get a transcript of the most recent audio fed to this stream, without finalizing it.
+	///
+	/// Only the last few seconds are transcribed, so long streams only get a transcript of their end.
+	/// The returned future does not borrow this stream.
+	///
+	/// Returns [`ModelError::PartialResultsDisabled`] if [`Self::enable_partial_results`] was never called,
+	/// or the backend doesn't support partial results at all.
+	pub fn get_partial_result(
+		&self,
+		language: String,
+		translate: bool,
+	) -> impl Future<Output = Result<String, ModelError>> + Send + 'static {
+		self.0.get_partial_result(language, translate)
+	}
+
+	pub async fn get_result(
+		self,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError> {
+		self.0.get_result(language, verbose, translate).await
+	}
+
+	pub(crate) async fn get_result_with_retries(
+		self,
+		language: String,
+		verbose: bool,
+		translate: bool,
+		attempts: usize,
+	) -> Result<Transcript, ModelError> {
+		self.0
+			.get_result_with_retries(language, verbose, translate, attempts)
+			.await
+	}
+}
@@ -1,10 +1,34 @@
-use crate::load_balancer::LoadBalancer;
+use scripty_config::SttBackendConfig;
+
+use crate::{
+	backend::{SttBackend, BACKEND},
+	load_balancer::{LoadBalancer, LOAD_BALANCER},
+	remote_backend::RemoteBackend,
+};
 
 pub async fn init_stt() {
-	let balancer = LoadBalancer::new()
-		.await
-		.expect("failed to initialize a STT service");
-	crate::load_balancer::LOAD_BALANCER
-		.set(balancer)
-		.unwrap_or_else(|_| panic!("don't try to set the load balancer twice"));
+	let backend: Box<dyn SttBackend> = match &scripty_config::get_config().stt_backend {
+		SttBackendConfig::Remote => {
+			let balancer = LoadBalancer::new().await;
+			LOAD_BALANCER
+				.set(balancer.clone())
+				.unwrap_or_else(|_| panic!("don't try to set the load balancer twice"));
+			Box::new(RemoteBackend::new(balancer))
+		}
+		#[cfg(feature = "whisper")]
+		SttBackendConfig::Whisper {
+			model_path,
+			threads,
+		} => Box::new(
+			crate::whisper_backend::WhisperBackend::new(model_path, *threads)
+				.unwrap_or_else(|e| panic!("failed to load whisper model: {}", e)),
+		),
+		#[cfg(not(feature = "whisper"))]
+		SttBackendConfig::Whisper { .. } => {
+			panic!("the whisper STT backend needs scripty to be built with the `whisper` feature")
+		}
+	};
+	BACKEND
+		.set(backend)
+		.unwrap_or_else(|_| panic!("don't try to set the STT backend twice"));
 }
@@ -2,17 +2,28 @@
 
 #[macro_use]
 extern crate tracing;
+#[macro_use]
+extern crate async_trait;
 
+mod backend;
 mod decode_ogg_opus;
 mod ffprobe;
 mod init;
 mod load_balancer;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
 mod models;
 mod process_audio;
+mod remote_backend;
+mod server_stats;
+#[cfg(feature = "whisper")]
+mod whisper_backend;
 
+pub use backend::Stream;
 pub use decode_ogg_opus::decode_ogg_opus_file;
 pub use ffprobe::*;
 pub use init::init_stt;
+pub use load_balancer::{ProbeResult, SttServerStatus};
 pub use magnum::error::OpusSourceError;
 pub use models::*;
 pub use process_audio::process_audio;
@@ -20,6 +31,9 @@ pub use process_audio::process_audio;
 /// Number of times to try to find an available STT service before giving up.
 const NUM_STT_SERVICE_TRIES: usize = 1024;
 
+/// Number of times to try getting the result of a stream, if not set in the config.
+const DEFAULT_FINALIZE_ATTEMPTS: usize = 3;
+
 /// Check if a language is supported by the STT model.
 pub fn check_model_language(language: &str) -> bool {
 	scripty_config::get_config()
@@ -33,11 +47,88 @@ pub fn get_model_languages() -> Vec<String> {
 	scripty_config::get_config().languages.clone()
 }
 
+fn get_backend() -> &'static dyn backend::SttBackend {
+	backend::BACKEND
+		.get()
+		.expect("initialize STT before trying to use it")
+		.as_ref()
+}
+
+/// The pool of STT servers, if the remote backend is in use.
+fn get_load_balancer() -> Option<&'static load_balancer::LoadBalancer> {
+	load_balancer::LOAD_BALANCER.get()
+}
+
 /// Get a new stream.
 pub async fn get_stream() -> Result<Stream, ModelError> {
-	load_balancer::LOAD_BALANCER
-		.get()
-		.expect("initialize load balancer before trying to get stream")
-		.get_stream()
+	Ok(Stream::new(get_backend().open_stream().await?))
+}
+
+/// Get the result of a stream, retrying if the backend fails in a way that isn't the audio's fault.
+///
+/// With the remote backend, the audio is replayed on another server if the first one
+/// disconnects or times out.
+///
+/// Prefer this over [`Stream::get_result`], which gives up after one try.
+pub async fn get_result_with_retries(
+	stream: Stream,
+	language: String,
+	verbose: bool,
+	translate: bool,
+) -> Result<Transcript, ModelError> {
+	stream
+		.get_result_with_retries(language, verbose, translate, finalize_attempts())
 		.await
 }
+
+/// The longest [`get_result_with_retries`] can take with the configured number of attempts,
+/// if every attempt opens a new stream and then waits as long as it can for the result.
+pub fn finalize_timeout() -> std::time::Duration {
+	let attempts = u32::try_from(finalize_attempts().max(1)).unwrap_or(u32::MAX);
+	(models::INITIALIZATION_TIMEOUT + models::RESULT_TIMEOUT).saturating_mul(attempts)
+}
+
+fn finalize_attempts() -> usize {
+	scripty_config::get_config()
+		.stt_finalize_attempts
+		.unwrap_or(DEFAULT_FINALIZE_ATTEMPTS)
+}
+
+/// The state of every STT server in the pool, in the order they were added.
+///
+/// Empty if the remote backend isn't in use.
+pub fn get_server_statuses() -> Vec<SttServerStatus> {
+	get_load_balancer().map_or_else(Vec::new, |balancer| balancer.server_statuses())
+}
+
+/// Add an STT service to the pool, and connect to it right away.
+///
+/// Returns `false` if the service was already in the pool, or the remote backend isn't in use.
+pub async fn add_service(service: scripty_config::SttServiceDefinition) -> bool {
+	match get_load_balancer() {
+		Some(balancer) => balancer.add_service(service).await,
+		None => false,
+	}
+}
+
+/// Remove an STT service from the pool.
+///
+/// Its servers stop getting new streams right away, and leave the pool once their streams finish.
+/// Returns `false` if the service wasn't in the pool.
+pub async fn remove_service(service: &scripty_config::SttServiceDefinition) -> bool {
+	match get_load_balancer() {
+		Some(balancer) => balancer.remove_service(service).await,
+		None => false,
+	}
+}
+
+/// Read the STT services from the config file again, and make the pool match them.
+///
+/// Does nothing but check the config file if the remote backend isn't in use.
+pub async fn reload_services() -> Result<(), scripty_config::ConfigError> {
+	let config = scripty_config::reload_config()?;
+	if let Some(balancer) = get_load_balancer() {
+		balancer.set_services(config.stt_services).await;
+	}
+	Ok(())
+}
@@ -1,35 +1,46 @@
 use std::{
-	collections::VecDeque,
+	collections::{HashMap, VecDeque},
+	future::Future,
 	net::SocketAddr,
 	sync::{
 		atomic::{AtomicBool, AtomicUsize, Ordering},
 		Arc,
 	},
-	time::Duration,
+	time::{Duration, Instant, SystemTime},
 };
 
 use byteorder::NetworkEndian;
 use dashmap::DashMap;
 use once_cell::sync::OnceCell;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use scripty_common::stt_transport_models::{
 	ClientToServerMessage,
 	ServerToClientMessage,
 	StatusConnectionData,
 	StatusConnectionOpen,
 };
-use scripty_config::SttServiceDefinition;
+use scripty_config::{SttLoadBalancingStrategy, SttServiceDefinition};
+use serde::{de::DeserializeOwned, Serialize};
 use tokio::{
-	io::{AsyncReadExt, AsyncWriteExt},
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
 	net::{
 		lookup_host,
 		tcp::{OwnedReadHalf, OwnedWriteHalf},
 		TcpStream,
 	},
-	sync::broadcast::{Receiver, Sender},
+	sync::{
+		broadcast::{Receiver, Sender},
+		watch,
+	},
 };
 
-use crate::{ModelError, Stream, NUM_STT_SERVICE_TRIES};
+use crate::{
+	server_stats::ServerStats,
+	ModelError,
+	RemoteStream,
+	Transcript,
+	NUM_STT_SERVICE_TRIES,
+};
 
 /// Maximum number of workers to queue up.
 ///
@@ -40,60 +51,80 @@ use crate::{ModelError, Stream, NUM_STT_SERVICE_TRIES};
 /// rounded down to 32.
 const MAXIMUM_QUEUE_SIZE: usize = 32;
 
+/// How often every server is health checked, and DNS names are resolved again.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long to wait for a server to accept a connection, and then to say it's ready.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long to wait for a service's host name to resolve.
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub static LOAD_BALANCER: OnceCell<LoadBalancer> = OnceCell::new();
 
-/// Round-robin load balancer that equally loads all tasks,
+/// Load balancer that spreads streams over all workers with the configured strategy,
 /// until one notes that it is overloaded, at which point it is removed from the pool.
 ///
 /// If it notifies the master that it is no longer overloaded, it is re-added.
+///
+/// Workers can be added and drained at runtime. A draining worker gets no new streams,
+/// and leaves the pool once all of its streams are finished.
 #[derive(Clone)]
 pub struct LoadBalancer {
-	/// The current worker index.
+	/// How to pick the next worker.
+	strategy:       SttLoadBalancingStrategy,
+	/// The current worker index, for round robin.
 	current_index:  Arc<AtomicUsize>,
+	/// ID the next worker added to the pool gets.
+	next_worker_id: Arc<AtomicUsize>,
 	/// A list of all workers.
 	workers:        Arc<DashMap<usize, LoadBalancedStream>>,
+	/// The services the pool should be made of.
+	services:       Arc<RwLock<Vec<SttServiceDefinition>>>,
+	/// Held while workers are being added or drained, so the same server isn't added twice.
+	reconcile_lock: Arc<tokio::sync::Mutex<()>>,
 	/// Queued-up workers ready for use.
 	///
 	/// This is used to prevent dropping a few hundred milliseconds of audio at the very start of a stream.
 	/// If a worker is queued up, it is ready to be used immediately.
-	queued_workers: Arc<Mutex<VecDeque<Stream>>>,
+	queued_workers: Arc<Mutex<VecDeque<RemoteStream>>>,
 	/// Channel to request a new worker be queued up.
 	///
 	/// Allows avoiding busy waiting in the background task.
 	new_worker_tx:  flume::Sender<()>,
+	/// Channel to throw away all queued workers, and queue up new ones.
+	purge_tx:       flume::Sender<()>,
 }
 
 impl LoadBalancer {
-	pub async fn new() -> Result<Self, ModelError> {
-		let stt_services = scripty_config::get_config().stt_services.clone();
-		let mut peer_addresses: Vec<SocketAddr> = Vec::new();
-		for service in stt_services {
-			match service {
-				SttServiceDefinition::HostString(host) => peer_addresses.extend(
-					lookup_host(host)
-						.await
-						.expect("Could not resolve stt hostname"),
-				),
-				SttServiceDefinition::IPTuple(addr, port) => peer_addresses.push(SocketAddr::new(
-					addr.parse()
-						.expect("Could not parse IP address for stt server"),
-					port,
-				)),
-			}
-		}
+	/// Create a load balancer with the services in the config.
+	///
+	/// Services that can't be reached yet are tried again at every health check.
+	pub async fn new() -> Self {
+		let config = scripty_config::get_config();
+		Self::with_services(config.stt_load_balancing, config.stt_services.clone()).await
+	}
 
-		let workers = Arc::new(DashMap::new());
+	/// Create a load balancer with the given services, instead of the ones in the config.
+	pub(crate) async fn with_services(
+		strategy: SttLoadBalancingStrategy,
+		services: Vec<SttServiceDefinition>,
+	) -> Self {
 		let (purge_tx, purge_rx) = flume::bounded(1);
-		for (n, addr) in peer_addresses.into_iter().enumerate() {
-			workers.insert(n, LoadBalancedStream::new(addr, purge_tx.clone()).await?);
-		}
 		let (new_worker_tx, new_worker_rx) = flume::unbounded();
 		let this = Self {
+			strategy,
 			current_index: Arc::new(AtomicUsize::new(0)),
-			workers,
+			next_worker_id: Arc::new(AtomicUsize::new(0)),
+			workers: Arc::new(DashMap::new()),
+			services: Arc::new(RwLock::new(services)),
+			reconcile_lock: Arc::new(tokio::sync::Mutex::new(())),
 			queued_workers: Arc::new(Mutex::new(VecDeque::with_capacity(MAXIMUM_QUEUE_SIZE))),
 			new_worker_tx,
+			purge_tx,
 		};
+		this.reconcile().await;
+
 		let t2 = this.clone();
 		tokio::spawn(t2.new_worker_background_task(new_worker_rx));
 		let t3 = this.clone();
@@ -110,32 +141,196 @@ impl LoadBalancer {
 				}
 			}
 		});
-		Ok(this)
+		let t4 = this.clone();
+		tokio::spawn(t4.health_check_background_task());
+		this
+	}
+
+	/// Replace every service in the pool.
+	///
+	/// Servers that are no longer in any service are drained.
+	pub async fn set_services(&self, services: Vec<SttServiceDefinition>) {
+		*self.services.write() = services;
+		self.reconcile().await;
+	}
+
+	/// Add a service to the pool, and connect to it right away.
+	///
+	/// Returns `false` if the service was already in the pool.
+	pub async fn add_service(&self, service: SttServiceDefinition) -> bool {
+		{
+			let mut services = self.services.write();
+			if services.contains(&service) {
+				return false;
+			}
+			services.push(service);
+		}
+		self.reconcile().await;
+		true
+	}
+
+	/// Drain every server in a service, and remove the service from the pool.
+	///
+	/// Returns `false` if the service wasn't in the pool.
+	pub async fn remove_service(&self, service: &SttServiceDefinition) -> bool {
+		{
+			let mut services = self.services.write();
+			let len = services.len();
+			services.retain(|s| s != service);
+			if services.len() == len {
+				return false;
+			}
+		}
+		self.reconcile().await;
+		true
 	}
 
-	fn get_next_worker_idx(&self) -> usize {
-		self.current_index
-			.fetch_update(Ordering::Release, Ordering::Acquire, |x| {
-				if x == self.workers.len() {
-					Some(0)
-				} else {
-					Some(x + 1)
+	/// The state of every server in the pool, in the order they were added.
+	pub fn server_statuses(&self) -> Vec<SttServerStatus> {
+		let mut statuses = self
+			.workers
+			.iter()
+			.map(|worker| (*worker.key(), worker.status()))
+			.collect::<Vec<_>>();
+		statuses.sort_unstable_by_key(|(id, _)| *id);
+		statuses.into_iter().map(|(_, status)| status).collect()
+	}
+
+	/// Make the workers match the services the pool should be made of:
+	/// resolve every service, connect to new servers, and drain ones that are no longer wanted.
+	async fn reconcile(&self) {
+		let _guard = self.reconcile_lock.lock().await;
+		let services = self.services.read().clone();
+
+		let mut wanted: HashMap<SocketAddr, SttServiceDefinition> = HashMap::new();
+		for service in services {
+			match resolve_service(&service).await {
+				Ok(addresses) => {
+					wanted.extend(addresses.into_iter().map(|addr| (addr, service.clone())))
+				}
+				Err(e) => {
+					// don't drain a service's servers just because DNS failed once
+					error!(%service, "failed to resolve STT service: {}", e);
+					for worker in self.workers.iter() {
+						if worker.service == service {
+							wanted.insert(worker.peer_address, service.clone());
+						}
+					}
+				}
+			}
+		}
+
+		let mut drained_any = false;
+		for worker in self.workers.iter() {
+			let is_wanted = wanted.remove(&worker.peer_address).is_some();
+			if is_wanted && worker.is_draining() {
+				info!(peer_address = %worker.peer_address, "STT server added back to pool, no longer draining");
+				worker.draining.store(false, Ordering::Relaxed);
+			} else if !is_wanted && !worker.is_draining() {
+				info!(peer_address = %worker.peer_address, "draining STT server");
+				worker.draining.store(true, Ordering::Relaxed);
+				drained_any = true;
+			}
+		}
+		if drained_any {
+			// queued streams count as active, so throw them away to let drained servers finish
+			self.purge_tx.send_async(()).await.ok();
+		}
+
+		// everything left is a new server
+		for (peer_address, service) in wanted {
+			match LoadBalancedStream::new(peer_address, service, self.purge_tx.clone()).await {
+				Ok(worker) => {
+					let id = self.next_worker_id.fetch_add(1, Ordering::Relaxed);
+					self.workers.insert(id, worker);
+					info!(%peer_address, "added STT server to pool");
+				}
+				Err(e) => {
+					error!(%peer_address, "failed to connect to STT server: {}", e);
 				}
-			})
-			.expect("get_next_worker_idx::{closure} should never return None")
+			}
+		}
+
+		self.remove_drained_workers();
 	}
 
-	fn find_worker(&self) -> Result<usize, ModelError> {
-		let mut idx = self.get_next_worker_idx();
-		let mut iter_count: usize = 0;
-		let mut allow_overload = false;
+	/// Remove draining workers that have no streams left.
+	fn remove_drained_workers(&self) {
+		self.workers.retain(|_, worker| {
+			let drained = worker.is_draining() && worker.stats.in_flight() == 0;
+			if drained {
+				info!(peer_address = %worker.peer_address, "drained STT server removed from pool");
+			}
+			!drained
+		});
+	}
 
+	async fn health_check_background_task(self) {
 		loop {
+			tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+
+			// picks up DNS changes, retries servers that couldn't be reached,
+			// and removes drained servers
+			self.reconcile().await;
+
+			let ids = self
+				.workers
+				.iter()
+				.filter(|worker| !worker.is_draining())
+				.map(|worker| *worker.key())
+				.collect::<Vec<_>>();
+			for id in ids {
+				// don't hold a reference into the map while waiting for the probe
+				let Some(probe) = self.workers.get(&id).map(|worker| worker.probe()) else {
+					continue;
+				};
+				let result = probe.await;
+				if let Err(ref e) = result.result {
+					warn!(worker = id, "STT server failed health check: {}", e);
+				}
+				if let Some(worker) = self.workers.get(&id) {
+					worker.stats.record_health(result.result.is_ok());
+					*worker.last_probe.lock() = Some(result);
+				}
+			}
+		}
+	}
+
+	fn find_worker(&self) -> Result<usize, ModelError> {
+		self.find_worker_excluding(&[])
+	}
+
+	/// Find a worker, skipping any on the `excluded` servers.
+	fn find_worker_excluding(&self, excluded: &[SocketAddr]) -> Result<usize, ModelError> {
+		match self.strategy {
+			SttLoadBalancingStrategy::RoundRobin => self.find_worker_round_robin(excluded),
+			SttLoadBalancingStrategy::LeastActiveStreams => {
+				self.find_least_loaded_worker(excluded, |stats| stats.in_flight() as f64)
+			}
+			SttLoadBalancingStrategy::LatencyWeighted => {
+				self.find_least_loaded_worker(excluded, ServerStats::latency_score)
+			}
+		}
+	}
+
+	fn find_worker_round_robin(&self, excluded: &[SocketAddr]) -> Result<usize, ModelError> {
+		// worker IDs have gaps once workers have been removed, so take turns in order of ID
+		let mut ids = self
+			.workers
+			.iter()
+			.filter(|worker| !excluded.contains(&worker.peer_address))
+			.map(|worker| *worker.key())
+			.collect::<Vec<_>>();
+		if ids.is_empty() {
+			return Err(no_available_servers());
+		}
+		ids.sort_unstable();
+
+		let mut allow_overload = false;
+		for iter_count in 0..NUM_STT_SERVICE_TRIES {
+			let idx = ids[self.current_index.fetch_add(1, Ordering::Relaxed) % ids.len()];
 			if let Some(worker) = self.workers.get(&idx) {
-				// if we're allowing overloading, or this worker isn't overloaded and isn't in error
-				if (allow_overload && worker.can_overload)
-					|| !worker.is_overloaded() && !worker.is_in_error()
-				{
+				if worker.is_available(allow_overload) {
 					// usually this is going to be the fast path, and it will immediately return this worker.
 					// if it isn't, this is still decently fast, an O(2n) operation worst case.
 					// given there's very likely never going to be more than 255 workers, this is fine
@@ -143,38 +338,64 @@ impl LoadBalancer {
 				}
 			}
 
-			idx = self.get_next_worker_idx();
-
 			// are we back at the start?
-			if !allow_overload && iter_count > self.workers.len() {
+			if iter_count >= ids.len() {
 				// we've looped through all workers, and none are available:
 				// try again, but this time, allow overloading
 				allow_overload = true;
 			}
+		}
 
-			iter_count += 1;
-
-			if iter_count > NUM_STT_SERVICE_TRIES {
-				// failed to find any available workers
-				// give up and return an error
-				scripty_metrics::get_metrics()
-					.stt_server_fetch_failure
-					.inc_by(1);
-				error!(
-					"no available STT servers after {} tries",
-					NUM_STT_SERVICE_TRIES
-				);
-				return Err(ModelError::NoAvailableServers);
+		// failed to find any available workers
+		// give up and return an error
+		Err(no_available_servers())
+	}
+
+	/// Find the available worker with the lowest load, as measured by `load`.
+	///
+	/// Like round robin, overloaded workers are only used if no other worker is available.
+	fn find_least_loaded_worker(
+		&self,
+		excluded: &[SocketAddr],
+		load: impl Fn(&ServerStats) -> f64,
+	) -> Result<usize, ModelError> {
+		for allow_overload in [false, true] {
+			let least_loaded = self
+				.workers
+				.iter()
+				.filter(|worker| {
+					worker.is_available(allow_overload) && !excluded.contains(&worker.peer_address)
+				})
+				.map(|worker| (*worker.key(), load(&worker.stats)))
+				.min_by(|(_, a), (_, b)| a.total_cmp(b));
+			if let Some((idx, _)) = least_loaded {
+				return Ok(idx);
 			}
 		}
+
+		Err(no_available_servers())
+	}
+
+	/// Open a new stream, without taking one from the queue.
+	pub(crate) async fn spawn_new_stream(&self) -> Result<RemoteStream, ModelError> {
+		self.spawn_new_stream_excluding(&[]).await
 	}
 
-	async fn spawn_new_stream(&self) -> Result<Stream, ModelError> {
-		let worker_id = self.find_worker()?;
-		let worker = self.workers.get(&worker_id).expect("worker should exist");
+	/// Open a new stream, on any server but the `excluded` ones.
+	async fn spawn_new_stream_excluding(
+		&self,
+		excluded: &[SocketAddr],
+	) -> Result<RemoteStream, ModelError> {
+		let worker_id = self.find_worker_excluding(excluded)?;
+		// the worker may have just been removed from the pool, but that's no different from it failing
+		let connection = self
+			.workers
+			.get(&worker_id)
+			.map(|worker| worker.open_connection())
+			.ok_or(ModelError::RemoteDisconnected)?;
 
 		let metrics = scripty_metrics::get_metrics();
-		match worker.open_connection().await {
+		match connection.await {
 			Ok(s) => {
 				metrics.stt_server_fetch_success.inc_by(1);
 				Ok(s)
@@ -208,6 +429,8 @@ impl LoadBalancer {
 				Ok(s) => s,
 				Err(e) => {
 					error!("failed to spawn new worker: {}", e);
+					// the pool may be empty until the next health check, so don't spin
+					tokio::time::sleep(Duration::from_secs(1)).await;
 					continue;
 				}
 			};
@@ -215,7 +438,7 @@ impl LoadBalancer {
 		}
 	}
 
-	pub async fn get_stream(&self) -> Result<Stream, ModelError> {
+	pub async fn get_stream(&self) -> Result<RemoteStream, ModelError> {
 		// check if we have any queued workers
 		{
 			let mut queued_workers = self.queued_workers.lock();
@@ -239,21 +462,96 @@ impl LoadBalancer {
 		};
 		Ok(new_worker)
 	}
+
+	/// Get the result of a stream, replaying its audio on another server if it fails in a way
+	/// that isn't the audio's fault.
+	///
+	/// Tries at most `attempts` times in total, and returns the last error if none succeed.
+	/// Streams that were too long to keep a copy of their audio aren't retried.
+	pub async fn get_result_with_retries(
+		&self,
+		mut stream: RemoteStream,
+		language: String,
+		verbose: bool,
+		translate: bool,
+		attempts: usize,
+	) -> Result<Transcript, ModelError> {
+		let mut failed_servers = Vec::new();
+		let mut attempt = 1;
+		let error = 'attempts: loop {
+			let mut error = match stream
+				.try_get_result(language.clone(), verbose, translate)
+				.await
+			{
+				Ok(transcript) => return Ok(transcript),
+				Err(e) => e,
+			};
+			if !error.is_retryable() {
+				break error;
+			}
+			let Some(audio) = stream.take_fed_audio() else {
+				break error;
+			};
+
+			// replay the audio on another server, until one takes all of it
+			loop {
+				if attempt >= attempts {
+					break 'attempts error;
+				}
+				let peer_address = stream.peer_address();
+				warn!(%peer_address, attempt, "STT stream failed, retrying on another server: {}", error);
+				failed_servers.push(peer_address);
+				// a server that already failed this stream is still better than none at all
+				let retry_stream = match self.spawn_new_stream_excluding(&failed_servers).await {
+					Err(ModelError::NoAvailableServers) => self.spawn_new_stream().await,
+					res => res,
+				};
+				stream = match retry_stream {
+					Ok(stream) => stream,
+					Err(e) => {
+						error!("failed to open stream to retry on: {}", e);
+						break 'attempts error;
+					}
+				};
+				scripty_metrics::get_metrics().stt_finalize_retries.inc();
+				attempt += 1;
+				match stream.feed_audio(audio.clone()) {
+					Ok(()) => break,
+					// this server went away too, which counts as another failed attempt
+					Err(e) => error = e,
+				}
+			}
+		};
+
+		if attempt > 1 {
+			error!(attempt, "STT stream failed on every retry: {}", error);
+		}
+		Err(error)
+	}
 }
 
 pub struct LoadBalancedStream {
 	peer_address:           SocketAddr,
+	/// The service this server was resolved from.
+	service:                SttServiceDefinition,
+	/// If set, no new streams are opened on this server.
+	draining:               AtomicBool,
+	/// Result of the last health check, if there's been one yet.
+	last_probe:             Mutex<Option<ProbeResult>>,
 	is_overloaded:          Arc<AtomicBool>,
 	can_overload:           bool,
 	waiting_for_new_stream: Arc<AtomicBool>,
 	is_errored:             Arc<AtomicBool>,
+	stats:                  Arc<ServerStats>,
 
 	msg_tx:                 Sender<ClientToServerMessage>,
 	msg_rx_transmit_handle: Sender<ServerToClientMessage>,
 	// keep this field that way there's always one receiver
 	_msg_rx:                Receiver<ServerToClientMessage>,
 
-	purge_tx: flume::Sender<()>,
+	purge_tx:     flume::Sender<()>,
+	/// Background tasks for this server stop once this is dropped.
+	_shutdown_tx: watch::Sender<()>,
 }
 
 impl LoadBalancedStream {
@@ -268,29 +566,84 @@ impl LoadBalancedStream {
 			|| self.is_errored.load(Ordering::Relaxed)
 	}
 
-	pub async fn open_connection(&self) -> Result<Stream, ModelError> {
-		if !self.can_overload && self.is_overloaded() {
-			return Err(ModelError::OverloadedRemote);
+	#[inline]
+	pub fn is_draining(&self) -> bool {
+		self.draining.load(Ordering::Relaxed)
+	}
+
+	/// Whether new streams can be opened on this server.
+	fn is_available(&self, allow_overload: bool) -> bool {
+		// if we're allowing overloading, or this worker isn't overloaded and isn't in error
+		!self.is_draining()
+			&& ((allow_overload && self.can_overload)
+				|| !self.is_overloaded() && !self.is_in_error())
+	}
+
+	pub fn status(&self) -> SttServerStatus {
+		SttServerStatus {
+			peer_address: self.peer_address,
+			service:      self.service.clone(),
+			draining:     self.is_draining(),
+			overloaded:   self.is_overloaded(),
+			errored:      self.is_in_error(),
+			in_flight:    self.stats.in_flight(),
+			latency:      self.stats.latency(),
+			last_probe:   self.last_probe.lock().clone(),
+		}
+	}
+
+	/// Open a new stream on this server.
+	///
+	/// The returned future doesn't borrow the worker, so it can be awaited without holding a
+	/// reference into the pool.
+	pub fn open_connection(
+		&self,
+	) -> impl Future<Output = Result<RemoteStream, ModelError>> + 'static {
+		let overloaded = !self.can_overload && self.is_overloaded();
+		let is_errored = Arc::clone(&self.is_errored);
+		let new_stream = self.new_stream();
+		async move {
+			if overloaded {
+				return Err(ModelError::OverloadedRemote);
+			}
+
+			let res = new_stream.await;
+			is_errored.store(res.is_err(), Ordering::Relaxed);
+			res
+		}
+	}
+
+	/// Check the server is healthy by opening a stream on it, and timing how long that takes.
+	fn probe(&self) -> impl Future<Output = ProbeResult> + 'static {
+		let new_stream = self.new_stream();
+		async move {
+			let start = Instant::now();
+			let result = new_stream.await;
+			ProbeResult {
+				checked_at: SystemTime::now(),
+				result:     result.map(|_| start.elapsed()).map_err(|e| e.to_string()),
+			}
 		}
+	}
 
-		let res = Stream::new(
+	fn new_stream(&self) -> impl Future<Output = Result<RemoteStream, ModelError>> + 'static {
+		RemoteStream::new(
 			self.peer_address,
 			self.msg_tx.clone(),
 			self.msg_rx_transmit_handle.subscribe(),
 			self.purge_tx.clone(),
+			Arc::clone(&self.stats),
 		)
-		.await;
-		self.is_errored.store(res.is_err(), Ordering::Relaxed);
-		res
 	}
 
 	pub async fn new(
 		peer_address: SocketAddr,
+		service: SttServiceDefinition,
 		purge_tx: flume::Sender<()>,
 	) -> Result<Self, ModelError> {
 		// open a connection to the remote
 		info!("trying to connect to STT service at {}", peer_address);
-		let peer_stream = TcpStream::connect(peer_address).await?;
+		let peer_stream = connect_with_timeout(peer_address).await?;
 		let (mut stream_read, stream_write) = peer_stream.into_split();
 
 		// wait for the server to send a StatusConnectionOpen message
@@ -298,7 +651,12 @@ impl LoadBalancedStream {
 		let ServerToClientMessage::StatusConnectionOpen(StatusConnectionOpen {
 			max_utilization,
 			can_overload,
-		}) = read_socket_message(&mut stream_read).await?
+		}) = tokio::time::timeout(
+			CONNECT_TIMEOUT,
+			read_socket_message::<ServerToClientMessage, _>(&mut stream_read),
+		)
+		.await
+		.map_err(|_| ModelError::InitializationTimedOut)??
 		else {
 			// got something other than a StatusConnectionOpen message
 			// should never happen
@@ -319,6 +677,8 @@ impl LoadBalancedStream {
 		let (stream_error_tx, mut stream_error_rx) = tokio::sync::mpsc::channel(2);
 		let (new_read_stream_tx, new_read_stream_rx) = tokio::sync::mpsc::channel(1);
 		let (new_write_stream_tx, new_write_stream_rx) = tokio::sync::mpsc::channel(1);
+		// every task stops once the worker is dropped
+		let (shutdown_tx, shutdown_rx) = watch::channel(());
 
 		// read stream task
 		struct ReadStreamTask {
@@ -326,18 +686,24 @@ impl LoadBalancedStream {
 			server_to_client_tx: tokio::sync::broadcast::Sender<ServerToClientMessage>,
 			stream_error_tx:     tokio::sync::mpsc::Sender<ModelError>,
 			new_read_stream_rx:  tokio::sync::mpsc::Receiver<OwnedReadHalf>,
+			shutdown_rx:         watch::Receiver<()>,
 		}
 		let mut read_stream_task = ReadStreamTask {
 			stream_read,
 			server_to_client_tx: server_to_client_tx.clone(),
 			stream_error_tx: stream_error_tx.clone(),
 			new_read_stream_rx,
+			shutdown_rx: shutdown_rx.clone(),
 		};
 		tokio::spawn(async move {
 			'outer: loop {
 				let error = 'inner: loop {
 					let message = tokio::select! {
 						biased;
+						_ = read_stream_task.shutdown_rx.changed() => {
+							debug!(%peer_address, "server left the pool, stopping read task");
+							break 'outer;
+						}
 						new_handle = read_stream_task.new_read_stream_rx.recv() => {
 							// always swap handles before trying to send anything new
 							match new_handle {
@@ -353,7 +719,7 @@ impl LoadBalancedStream {
 								}
 							}
 						}
-						message = read_socket_message(&mut read_stream_task.stream_read) => {
+						message = read_socket_message::<ServerToClientMessage, _>(&mut read_stream_task.stream_read) => {
 							message
 						}
 					};
@@ -404,18 +770,24 @@ impl LoadBalancedStream {
 			client_to_server_rx: tokio::sync::broadcast::Receiver<ClientToServerMessage>,
 			stream_error_tx:     tokio::sync::mpsc::Sender<ModelError>,
 			new_write_stream_rx: tokio::sync::mpsc::Receiver<OwnedWriteHalf>,
+			shutdown_rx:         watch::Receiver<()>,
 		}
 		let mut write_stream_task = WriteStreamTask {
 			stream_write,
 			client_to_server_rx,
 			stream_error_tx,
 			new_write_stream_rx,
+			shutdown_rx: shutdown_rx.clone(),
 		};
 		tokio::spawn(async move {
 			'outer: loop {
 				let error = 'inner: loop {
 					let message = tokio::select! {
 						biased;
+						_ = write_stream_task.shutdown_rx.changed() => {
+							debug!(%peer_address, "server left the pool, stopping write task");
+							break 'outer;
+						}
 						new_handle = write_stream_task.new_write_stream_rx.recv() => {
 							// always swap handles before trying to send anything new
 							match new_handle {
@@ -483,10 +855,15 @@ impl LoadBalancedStream {
 		let waiting_for_new_stream = Arc::new(AtomicBool::new(false));
 		let wfns2 = Arc::clone(&waiting_for_new_stream);
 		let purge_tx2 = purge_tx.clone();
+		let mut shutdown_rx2 = shutdown_rx.clone();
 		// error handling task
 		tokio::spawn(async move {
 			loop {
-				let _error = stream_error_rx.recv().await;
+				let _error = tokio::select! {
+					biased;
+					_ = shutdown_rx2.changed() => break,
+					error = stream_error_rx.recv() => error,
+				};
 				warn!("got error from stream pair");
 				wfns2.store(true, Ordering::Relaxed);
 
@@ -500,7 +877,7 @@ impl LoadBalancedStream {
 				let mut peer_stream = None;
 				for n in 0..=12 {
 					// try 12 times to connect to the server with exponential backoff
-					let maybe_stream = TcpStream::connect(peer_address).await;
+					let maybe_stream = connect_with_timeout(peer_address).await;
 					match maybe_stream {
 						Ok(stream) => {
 							peer_stream = Some(stream);
@@ -552,11 +929,14 @@ impl LoadBalancedStream {
 			}
 		});
 
+		let stats = Arc::new(ServerStats::new(peer_address));
 		let is_errored = Arc::new(AtomicBool::new(false));
 		let ie2 = Arc::clone(&is_errored);
+		let stats2 = Arc::clone(&stats);
 		let cts2 = client_to_server_tx.clone();
 		let stc2 = server_to_client_tx.clone();
 		let ptx2 = purge_tx.clone();
+		let mut shutdown_rx3 = shutdown_rx;
 		// If in error state, clear out the queue
 		// and also try creating a new worker every few seconds.
 		// When one does succeed, unset the flag
@@ -564,8 +944,14 @@ impl LoadBalancedStream {
 			loop {
 				if ie2.load(Ordering::Relaxed) {
 					// try fetching a new worker
-					match Stream::new(peer_address, cts2.clone(), stc2.subscribe(), ptx2.clone())
-						.await
+					match RemoteStream::new(
+						peer_address,
+						cts2.clone(),
+						stc2.subscribe(),
+						ptx2.clone(),
+						Arc::clone(&stats2),
+					)
+					.await
 					{
 						Ok(_) => {
 							ie2.store(false, Ordering::Relaxed);
@@ -575,12 +961,18 @@ impl LoadBalancedStream {
 						}
 					}
 				}
-				tokio::time::sleep(Duration::from_secs(5)).await;
+				tokio::select! {
+					_ = shutdown_rx3.changed() => break,
+					_ = tokio::time::sleep(Duration::from_secs(5)) => {}
+				}
 			}
 		});
 
 		Ok(Self {
 			peer_address,
+			service,
+			draining: AtomicBool::new(false),
+			last_probe: Mutex::new(None),
 			is_overloaded,
 			can_overload,
 			waiting_for_new_stream,
@@ -589,13 +981,85 @@ impl LoadBalancedStream {
 			_msg_rx: server_to_client_rx,
 			purge_tx,
 			is_errored,
+			stats,
+			_shutdown_tx: shutdown_tx,
 		})
 	}
 }
 
-async fn read_socket_message(
-	socket: &mut OwnedReadHalf,
-) -> Result<ServerToClientMessage, ModelError> {
+/// The state of one server in the pool.
+#[derive(Debug, Clone)]
+pub struct SttServerStatus {
+	pub peer_address: SocketAddr,
+	/// The service this server was resolved from.
+	pub service:      SttServiceDefinition,
+	/// Whether this server is waiting for its streams to finish before leaving the pool.
+	pub draining:     bool,
+	pub overloaded:   bool,
+	pub errored:      bool,
+	/// Streams currently open on this server.
+	pub in_flight:    usize,
+	/// Average seconds this server takes to return a result.
+	pub latency:      Option<f64>,
+	pub last_probe:   Option<ProbeResult>,
+}
+
+/// The result of a health check on a server.
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+	pub checked_at: SystemTime,
+	/// How long it took to open a stream, or why it couldn't be opened.
+	pub result:     Result<Duration, String>,
+}
+
+/// Find every server a service points to.
+async fn resolve_service(service: &SttServiceDefinition) -> Result<Vec<SocketAddr>, ModelError> {
+	match service {
+		SttServiceDefinition::HostString(host) => {
+			let addrs = tokio::time::timeout(RESOLVE_TIMEOUT, lookup_host(host))
+				.await
+				.map_err(|_| {
+					std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out resolving host")
+				})??;
+			Ok(addrs.collect())
+		}
+		SttServiceDefinition::IPTuple(addr, port) => Ok(vec![SocketAddr::new(
+			addr.parse().map_err(|_| {
+				std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid IP address")
+			})?,
+			*port,
+		)]),
+	}
+}
+
+/// Connect to a server, giving up after [`CONNECT_TIMEOUT`]
+/// rather than waiting on the OS, which can take minutes for an unreachable host.
+async fn connect_with_timeout(peer_address: SocketAddr) -> std::io::Result<TcpStream> {
+	tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(peer_address))
+		.await
+		.unwrap_or_else(|_| {
+			Err(std::io::Error::new(
+				std::io::ErrorKind::TimedOut,
+				"timed out connecting to STT server",
+			))
+		})
+}
+
+fn no_available_servers() -> ModelError {
+	scripty_metrics::get_metrics()
+		.stt_server_fetch_failure
+		.inc_by(1);
+	error!(
+		"no available STT servers after {} tries",
+		NUM_STT_SERVICE_TRIES
+	);
+	ModelError::NoAvailableServers
+}
+
+/// Read one message framed as magic bytes, a length, and a MessagePack payload.
+pub(crate) async fn read_socket_message<T: DeserializeOwned, R: AsyncRead + Unpin>(
+	socket: &mut R,
+) -> Result<T, ModelError> {
 	// read the magic bytes
 	let mut magic = [0; 4];
 	socket.read_exact(&mut magic).await?;
@@ -619,9 +1083,10 @@ async fn read_socket_message(
 	Ok(rmp_serde::from_slice(&data)?)
 }
 
-async fn write_socket_message(
-	socket: &mut OwnedWriteHalf,
-	message: &ClientToServerMessage,
+/// Write one message, framed the same way [`read_socket_message`] reads it.
+pub(crate) async fn write_socket_message<T: Serialize, W: AsyncWrite + Unpin>(
+	socket: &mut W,
+	message: &T,
 ) -> Result<(), ModelError> {
 	// serialize the message
 	let mut data = Vec::new();
@@ -641,3 +1106,282 @@ async fn write_socket_message(
 
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock_server::{MockBehavior, MockSttServer, MOCK_MAX_UTILIZATION};
+
+	/// Wait until `condition` is true, or fail the test after a few seconds.
+	async fn wait_for(mut condition: impl FnMut() -> bool) {
+		tokio::time::timeout(Duration::from_secs(5), async {
+			while !condition() {
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		})
+		.await
+		.expect("timed out waiting for condition");
+	}
+
+	fn worker_id(balancer: &LoadBalancer, server: &MockSttServer) -> usize {
+		*balancer
+			.workers
+			.iter()
+			.find(|worker| worker.peer_address == server.address())
+			.expect("mock server is not in the pool")
+			.key()
+	}
+
+	#[tokio::test]
+	async fn test_overloaded_failover() {
+		let a = MockSttServer::start(false).await.unwrap();
+		let b = MockSttServer::start(false).await.unwrap();
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![a.service(), b.service()],
+		)
+		.await;
+		let (a_id, b_id) = (worker_id(&balancer, &a), worker_id(&balancer, &b));
+
+		a.set_utilization(MOCK_MAX_UTILIZATION * 2.0);
+		wait_for(|| balancer.workers.get(&a_id).unwrap().is_overloaded()).await;
+		for _ in 0..8 {
+			assert_eq!(balancer.find_worker().unwrap(), b_id);
+		}
+
+		// neither server allows overloading, so there's nowhere left to go
+		b.set_utilization(MOCK_MAX_UTILIZATION * 2.0);
+		wait_for(|| balancer.workers.get(&b_id).unwrap().is_overloaded()).await;
+		assert!(matches!(
+			balancer.find_worker(),
+			Err(ModelError::NoAvailableServers)
+		));
+	}
+
+	#[tokio::test]
+	async fn test_disconnected_failover() {
+		let a = MockSttServer::start(false).await.unwrap();
+		let b = MockSttServer::start(false).await.unwrap();
+		b.set_behavior(MockBehavior::Transcribe("from b".to_string()));
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![a.service(), b.service()],
+		)
+		.await;
+		let a_id = worker_id(&balancer, &a);
+
+		// a is gone for good, so it can't be reconnected to
+		drop(a);
+		wait_for(|| balancer.workers.get(&a_id).unwrap().is_in_error()).await;
+
+		for _ in 0..4 {
+			let stream = balancer.spawn_new_stream().await.unwrap();
+			let transcript = stream
+				.get_result("en".to_string(), false, false)
+				.await
+				.unwrap();
+			assert_eq!(transcript.text, "from b");
+		}
+	}
+
+	#[tokio::test]
+	async fn test_error_purges_queue() {
+		let server = MockSttServer::start(false).await.unwrap();
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![server.service()],
+		)
+		.await;
+		wait_for(|| balancer.queued_workers.lock().len() == MAXIMUM_QUEUE_SIZE).await;
+
+		server.set_behavior(MockBehavior::Error("model exploded".to_string()));
+		let stream = balancer.get_stream().await.unwrap();
+		assert!(stream
+			.get_result("en".to_string(), false, false)
+			.await
+			.is_err());
+
+		// the queue is only ever refilled past its size if it was thrown away
+		wait_for(|| server.streams_opened() >= MAXIMUM_QUEUE_SIZE * 2).await;
+	}
+
+	#[tokio::test]
+	async fn test_drained_server_removed() {
+		let server = MockSttServer::start(false).await.unwrap();
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![server.service()],
+		)
+		.await;
+		let id = worker_id(&balancer, &server);
+		// wait for the queue to fill, so no stream is being opened while draining
+		wait_for(|| balancer.queued_workers.lock().len() == MAXIMUM_QUEUE_SIZE).await;
+
+		assert!(balancer.remove_service(&server.service()).await);
+		assert!(!balancer.remove_service(&server.service()).await);
+		assert!(matches!(
+			balancer.find_worker(),
+			Err(ModelError::NoAvailableServers)
+		));
+
+		// queued streams are thrown away, so the server has nothing left to finish
+		wait_for(|| {
+			balancer
+				.workers
+				.get(&id)
+				.map_or(true, |worker| worker.stats.in_flight() == 0)
+		})
+		.await;
+		balancer.reconcile().await;
+		assert!(balancer.workers.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_failed_result_retried() {
+		let a = MockSttServer::start(false).await.unwrap();
+		let b = MockSttServer::start(false).await.unwrap();
+		b.set_behavior(MockBehavior::Transcribe("from b".to_string()));
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![a.service(), b.service()],
+		)
+		.await;
+		let a_id = worker_id(&balancer, &a);
+		let stream = balancer
+			.workers
+			.get(&a_id)
+			.unwrap()
+			.open_connection()
+			.await
+			.unwrap();
+		stream.feed_audio(vec![0; 320]).unwrap();
+
+		// a's connection is torn down before the stream is finalized
+		drop(balancer.workers.remove(&a_id));
+		let transcript = balancer
+			.get_result_with_retries(stream, "en".to_string(), false, false, 3)
+			.await
+			.unwrap();
+
+		assert_eq!(transcript.text, "from b");
+		assert_eq!(b.audio_samples(), 320);
+		assert_eq!(a.streams_ended(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_failed_result_not_retried_past_attempts() {
+		let a = MockSttServer::start(false).await.unwrap();
+		let b = MockSttServer::start(false).await.unwrap();
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![a.service(), b.service()],
+		)
+		.await;
+		let a_id = worker_id(&balancer, &a);
+		let stream = balancer
+			.workers
+			.get(&a_id)
+			.unwrap()
+			.open_connection()
+			.await
+			.unwrap();
+		stream.feed_audio(vec![0; 320]).unwrap();
+
+		drop(balancer.workers.remove(&a_id));
+		let result = balancer
+			.get_result_with_retries(stream, "en".to_string(), false, false, 1)
+			.await;
+
+		assert!(matches!(result, Err(ModelError::RemoteDisconnected)));
+		assert_eq!(b.streams_ended(), 0);
+	}
+
+	#[tokio::test]
+	async fn test_disconnect_mid_finalize_retried() {
+		let a = MockSttServer::start(false).await.unwrap();
+		let b = MockSttServer::start(false).await.unwrap();
+		b.set_behavior(MockBehavior::Transcribe("from b".to_string()));
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![a.service(), b.service()],
+		)
+		.await;
+		let a_id = worker_id(&balancer, &a);
+		wait_for(|| balancer.queued_workers.lock().len() == MAXIMUM_QUEUE_SIZE).await;
+		let stream = balancer
+			.workers
+			.get(&a_id)
+			.unwrap()
+			.open_connection()
+			.await
+			.unwrap();
+		stream.feed_audio(vec![0; 320]).unwrap();
+
+		a.set_behavior(MockBehavior::DisconnectOnFinalize);
+		// a's answer never comes, so skip ahead to the result timeout instead of waiting for it
+		tokio::time::pause();
+		let transcript = balancer
+			.get_result_with_retries(stream, "en".to_string(), false, false, 3)
+			.await
+			.unwrap();
+
+		assert_eq!(transcript.text, "from b");
+		assert_eq!(a.streams_ended(), 1);
+		assert_eq!(b.audio_samples(), 320);
+	}
+
+	#[tokio::test]
+	async fn test_reconnect_after_disconnect() {
+		let server = MockSttServer::start(false).await.unwrap();
+		let balancer = LoadBalancer::with_services(
+			SttLoadBalancingStrategy::RoundRobin,
+			vec![server.service()],
+		)
+		.await;
+		let id = worker_id(&balancer, &server);
+		wait_for(|| balancer.queued_workers.lock().len() == MAXIMUM_QUEUE_SIZE).await;
+		let connections = server.connections();
+
+		server.disconnect_all();
+		// the worker reconnects by itself, and refills the queue it threw away
+		wait_for(|| server.connections() > connections).await;
+		wait_for(|| {
+			!balancer.workers.get(&id).unwrap().is_in_error()
+				&& balancer.queued_workers.lock().len() == MAXIMUM_QUEUE_SIZE
+		})
+		.await;
+
+		let stream = balancer.get_stream().await.unwrap();
+		stream.feed_audio(vec![0; 320]).unwrap();
+		let transcript = stream
+			.get_result("en".to_string(), false, false)
+			.await
+			.unwrap();
+		assert_eq!(transcript.text, "mock transcript");
+		assert_eq!(server.audio_samples(), 320);
+	}
+
+	#[tokio::test]
+	async fn test_silent_server_times_out() {
+		// accepts connections, but never says it's ready
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let address = listener.local_addr().unwrap();
+		let _accept = tokio::spawn(async move {
+			let mut connections = Vec::new();
+			while let Ok((stream, _)) = listener.accept().await {
+				connections.push(stream);
+			}
+		});
+
+		let (purge_tx, _purge_rx) = flume::unbounded();
+		// skip ahead to the timeout instead of waiting for it
+		tokio::time::pause();
+		let result = LoadBalancedStream::new(
+			address,
+			SttServiceDefinition::IPTuple(address.ip().to_string(), address.port()),
+			purge_tx,
+		)
+		.await;
+		assert!(matches!(result, Err(ModelError::InitializationTimedOut)));
+	}
+}
@@ -0,0 +1,276 @@
+//! An in-process STT server, for testing against without a real STTS node.
+//!
+//! It speaks the same framing as a real server, but its answers are scripted with [`MockBehavior`].
+
+use std::{
+	io,
+	net::{Ipv4Addr, SocketAddr},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+use parking_lot::Mutex;
+use scripty_common::stt_transport_models::{
+	AudioData,
+	ClientToServerMessage,
+	FinalizeStreaming,
+	InitializationComplete,
+	InitializeStreaming,
+	ServerToClientMessage,
+	StatusConnectionData,
+	StatusConnectionOpen,
+	SttError,
+	SttSuccess,
+};
+use scripty_config::SttServiceDefinition;
+use tokio::{
+	net::{TcpListener, TcpStream},
+	sync::{broadcast, mpsc, watch},
+	task::JoinHandle,
+};
+
+use crate::load_balancer::{read_socket_message, write_socket_message};
+
+/// Utilization above which the mock server reports itself as overloaded.
+pub const MOCK_MAX_UTILIZATION: f64 = 1.0;
+
+/// How the mock server answers streams.
+#[derive(Debug, Clone)]
+pub enum MockBehavior {
+	/// Answer every finalized stream with this transcript.
+	Transcribe(String),
+	/// Answer every finalized stream with this error.
+	Error(String),
+	/// Never acknowledge new streams, so opening one times out.
+	IgnoreInitialize,
+	/// Never answer finalized streams, so waiting for a result times out.
+	IgnoreFinalize,
+	/// Close the connection as soon as a stream is finalized.
+	DisconnectOnFinalize,
+}
+
+impl Default for MockBehavior {
+	fn default() -> Self {
+		Self::Transcribe("mock transcript".to_string())
+	}
+}
+
+#[derive(Default)]
+struct MockState {
+	behavior:       Mutex<MockBehavior>,
+	connections:    AtomicUsize,
+	streams_opened: AtomicUsize,
+	audio_samples:  AtomicUsize,
+	streams_ended:  AtomicUsize,
+}
+
+/// A mock STT server listening on a random local port.
+///
+/// It stops listening, and closes every connection, once dropped.
+pub struct MockSttServer {
+	address:        SocketAddr,
+	state:          Arc<MockState>,
+	utilization_tx: broadcast::Sender<f64>,
+	/// Every connection is closed when this is changed or dropped.
+	disconnect_tx:  watch::Sender<()>,
+	accept_task:    JoinHandle<()>,
+}
+
+impl MockSttServer {
+	/// Start a mock server that transcribes everything as "mock transcript".
+	///
+	/// `can_overload` is sent to clients, and decides if they may use the server while it is overloaded.
+	pub async fn start(can_overload: bool) -> io::Result<Self> {
+		let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+		let address = listener.local_addr()?;
+
+		let state = Arc::new(MockState::default());
+		let (utilization_tx, _) = broadcast::channel(16);
+		let (disconnect_tx, disconnect_rx) = watch::channel(());
+
+		let accept_task = tokio::spawn({
+			let state = Arc::clone(&state);
+			let utilization_tx = utilization_tx.clone();
+			async move {
+				loop {
+					let Ok((socket, _)) = listener.accept().await else {
+						continue;
+					};
+					state.connections.fetch_add(1, Ordering::Relaxed);
+					// only disconnects after this connection was accepted should close it
+					let mut disconnect_rx = disconnect_rx.clone();
+					disconnect_rx.borrow_and_update();
+					tokio::spawn(handle_connection(
+						socket,
+						can_overload,
+						Arc::clone(&state),
+						utilization_tx.subscribe(),
+						disconnect_rx,
+					));
+				}
+			}
+		});
+
+		Ok(Self {
+			address,
+			state,
+			utilization_tx,
+			disconnect_tx,
+			accept_task,
+		})
+	}
+
+	pub fn address(&self) -> SocketAddr {
+		self.address
+	}
+
+	/// A service definition pointing at this server.
+	pub fn service(&self) -> SttServiceDefinition {
+		SttServiceDefinition::IPTuple(self.address.ip().to_string(), self.address.port())
+	}
+
+	/// Change how streams are answered from now on.
+	pub fn set_behavior(&self, behavior: MockBehavior) {
+		*self.state.behavior.lock() = behavior;
+	}
+
+	/// Send a utilization update to every open connection.
+	///
+	/// Above [`MOCK_MAX_UTILIZATION`], clients consider the server overloaded.
+	pub fn set_utilization(&self, utilization: f64) {
+		// no receivers just means no connections are open
+		let _ = self.utilization_tx.send(utilization);
+	}
+
+	/// Close every open connection. New connections are still accepted.
+	pub fn disconnect_all(&self) {
+		self.disconnect_tx.send_replace(());
+	}
+
+	/// Number of connections accepted so far.
+	pub fn connections(&self) -> usize {
+		self.state.connections.load(Ordering::Relaxed)
+	}
+
+	/// Number of streams clients have tried to open so far, whether or not they were acknowledged.
+	pub fn streams_opened(&self) -> usize {
+		self.state.streams_opened.load(Ordering::Relaxed)
+	}
+
+	/// Number of audio samples received so far, over all streams.
+	pub fn audio_samples(&self) -> usize {
+		self.state.audio_samples.load(Ordering::Relaxed)
+	}
+
+	/// Number of streams that have been finalized so far, whether or not they were answered.
+	pub fn streams_ended(&self) -> usize {
+		self.state.streams_ended.load(Ordering::Relaxed)
+	}
+}
+
+impl Drop for MockSttServer {
+	fn drop(&mut self) {
+		// open connections close by themselves once the disconnect sender is dropped
+		self.accept_task.abort();
+	}
+}
+
+async fn handle_connection(
+	socket: TcpStream,
+	can_overload: bool,
+	state: Arc<MockState>,
+	mut utilization_rx: broadcast::Receiver<f64>,
+	mut disconnect_rx: watch::Receiver<()>,
+) {
+	let (mut read, mut write) = socket.into_split();
+	let (response_tx, mut response_rx) = mpsc::unbounded_channel();
+
+	// a real server sends this before anything else
+	if write_socket_message(
+		&mut write,
+		&ServerToClientMessage::StatusConnectionOpen(StatusConnectionOpen {
+			max_utilization: MOCK_MAX_UTILIZATION,
+			can_overload,
+		}),
+	)
+	.await
+	.is_err()
+	{
+		return;
+	}
+
+	let write_task = tokio::spawn(async move {
+		loop {
+			let message = tokio::select! {
+				message = response_rx.recv() => match message {
+					Some(message) => message,
+					// the connection is closing
+					None => break,
+				},
+				utilization = utilization_rx.recv() => match utilization {
+					Ok(utilization) => {
+						ServerToClientMessage::StatusConnectionData(StatusConnectionData { utilization })
+					}
+					Err(broadcast::error::RecvError::Lagged(_)) => continue,
+					Err(broadcast::error::RecvError::Closed) => break,
+				},
+			};
+			if write_socket_message(&mut write, &message).await.is_err() {
+				break;
+			}
+		}
+	});
+
+	loop {
+		let message = tokio::select! {
+			biased;
+			_ = disconnect_rx.changed() => break,
+			message = read_socket_message::<ClientToServerMessage, _>(&mut read) => match message {
+				Ok(message) => message,
+				Err(_) => break,
+			},
+		};
+
+		let behavior = state.behavior.lock().clone();
+		let response = match message {
+			ClientToServerMessage::InitializeStreaming(InitializeStreaming { id }) => {
+				state.streams_opened.fetch_add(1, Ordering::Relaxed);
+				match behavior {
+					MockBehavior::IgnoreInitialize => None,
+					_ => Some(ServerToClientMessage::InitializationComplete(
+						InitializationComplete { id },
+					)),
+				}
+			}
+			ClientToServerMessage::AudioData(AudioData { data, .. }) => {
+				state.audio_samples.fetch_add(data.len(), Ordering::Relaxed);
+				None
+			}
+			ClientToServerMessage::FinalizeStreaming(FinalizeStreaming { id, .. }) => {
+				state.streams_ended.fetch_add(1, Ordering::Relaxed);
+				match behavior {
+					MockBehavior::Transcribe(result) => {
+						Some(ServerToClientMessage::SttResult(SttSuccess { id, result }))
+					}
+					MockBehavior::Error(error) => {
+						Some(ServerToClientMessage::SttError(SttError { id, error }))
+					}
+					MockBehavior::IgnoreInitialize | MockBehavior::IgnoreFinalize => None,
+					MockBehavior::DisconnectOnFinalize => break,
+				}
+			}
+			#[allow(unreachable_patterns)]
+			_ => None,
+		};
+		if let Some(response) = response {
+			if response_tx.send(response).is_err() {
+				break;
+			}
+		}
+	}
+
+	// stop writing, so both halves of the socket are dropped and the connection closes
+	write_task.abort();
+}
@@ -1,5 +1,11 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{
+	collections::VecDeque,
+	net::SocketAddr,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
+use parking_lot::Mutex;
 use scripty_common::stt_transport_models::{
 	AudioData,
 	ClientToServerMessage,
@@ -16,23 +22,55 @@ use tokio::{
 };
 use uuid::Uuid;
 
-use crate::NUM_STT_SERVICE_TRIES;
+use crate::{server_stats::ServerStats, NUM_STT_SERVICE_TRIES};
+
+/// Most audio a stream keeps a copy of for retrying, in samples: 2 minutes at 16kHz.
+///
+/// Longer streams can't be replayed on another server if theirs fails.
+const MAX_RETAINED_SAMPLES: usize = 120 * 16_000;
+
+// utterances are never longer than this, so every one of them can be retried
+const _: () =
+	assert!(scripty_config::MAX_MAX_UTTERANCE_LENGTH as usize * 16_000 <= MAX_RETAINED_SAMPLES);
+
+/// How much of the most recent audio partial results are transcribed from, in samples:
+/// 10 seconds at 16kHz.
+///
+/// This keeps the cost of each partial result the same, however long the stream gets.
+pub(crate) const PARTIAL_RESULT_WINDOW_SAMPLES: usize = 10 * 16_000;
+
+/// How long a server has to acknowledge a new stream.
+pub(crate) const INITIALIZATION_TIMEOUT: Duration = Duration::from_secs(5);
 
-pub struct Stream {
+/// How long a server has to return the result of a finalized stream.
+pub(crate) const RESULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A stream open on a remote STT server.
+pub(crate) struct RemoteStream {
 	tx:           Sender<ClientToServerMessage>,
 	rx:           Receiver<ServerToClientMessage>,
 	peer_address: SocketAddr,
 	session_id:   Uuid,
 
 	purge_tx: flume::Sender<()>,
+	/// Load on the server this stream is open on.
+	stats:    Arc<ServerStats>,
+
+	/// All audio fed so far, to replay on another server if this one fails.
+	///
+	/// Dropped once it grows past [`MAX_RETAINED_SAMPLES`].
+	fed_audio:    Mutex<Option<Vec<i16>>>,
+	/// The last [`PARTIAL_RESULT_WINDOW_SAMPLES`] of audio fed, once partial results are enabled.
+	recent_audio: Mutex<Option<VecDeque<i16>>>,
 }
 
-impl Stream {
+impl RemoteStream {
 	pub(crate) async fn new(
 		peer_address: SocketAddr,
 		tx: Sender<ClientToServerMessage>,
 		mut rx: Receiver<ServerToClientMessage>,
 		purge_tx: flume::Sender<()>,
+		stats: Arc<ServerStats>,
 	) -> Result<Self, ModelError> {
 		let session_id = Uuid::new_v4();
 		debug!(%session_id, %peer_address, "initializing stts stream to peer");
@@ -56,15 +94,19 @@ impl Stream {
 			false
 		};
 
-		match tokio::time::timeout(Duration::from_secs(5), stream_fut).await {
+		match tokio::time::timeout(INITIALIZATION_TIMEOUT, stream_fut).await {
 			Ok(true) => {
 				debug!(%session_id, %peer_address, "stts stream initialized");
+				stats.stream_opened();
 				Ok(Self {
 					tx,
 					rx,
 					peer_address,
 					session_id,
 					purge_tx,
+					stats,
+					fed_audio: Mutex::new(Some(Vec::new())),
+					recent_audio: Mutex::new(None),
 				})
 			}
 			Ok(false) => {
@@ -78,8 +120,44 @@ impl Stream {
 		}
 	}
 
+	/// Keep a copy of the most recent audio fed to this stream, so [`Self::partial_audio`] can be used.
+	///
+	/// Call it before feeding any audio, as only audio fed afterwards is kept.
+	pub fn enable_partial_results(&self) {
+		self.recent_audio
+			.lock()
+			.get_or_insert_with(|| VecDeque::with_capacity(PARTIAL_RESULT_WINDOW_SAMPLES));
+	}
+
+	pub(crate) fn peer_address(&self) -> SocketAddr {
+		self.peer_address
+	}
+
+	/// Take the copy of all audio fed to this stream, if it still has one.
+	pub(crate) fn take_fed_audio(&self) -> Option<Vec<i16>> {
+		self.fed_audio.lock().take()
+	}
+
 	pub fn feed_audio(&self, data: Vec<i16>) -> Result<(), ModelError> {
 		debug!(%self.session_id, %self.peer_address, "feeding audio to stts");
+		{
+			let mut fed_audio = self.fed_audio.lock();
+			if let Some(audio) = fed_audio.as_mut() {
+				if audio.len() + data.len() > MAX_RETAINED_SAMPLES {
+					// too long to keep around just in case, so this stream can't be retried
+					*fed_audio = None;
+				} else {
+					audio.extend_from_slice(&data);
+				}
+			}
+		}
+		if let Some(recent_audio) = self.recent_audio.lock().as_mut() {
+			recent_audio.extend(&data);
+			let excess = recent_audio
+				.len()
+				.saturating_sub(PARTIAL_RESULT_WINDOW_SAMPLES);
+			recent_audio.drain(..excess);
+		}
 		self.tx
 			.send(ClientToServerMessage::AudioData(AudioData {
 				data,
@@ -88,12 +166,31 @@ impl Stream {
 			.map_or(Err(ModelError::RemoteDisconnected), |_| Ok(()))
 	}
 
+	/// A copy of the last [`PARTIAL_RESULT_WINDOW_SAMPLES`] of audio fed to this stream,
+	/// if partial results are enabled.
+	pub(crate) fn partial_audio(&self) -> Option<Vec<i16>> {
+		self.recent_audio
+			.lock()
+			.as_ref()
+			.map(|audio| audio.iter().copied().collect())
+	}
+
 	pub async fn get_result(
 		mut self,
 		language: String,
 		verbose: bool,
 		translate: bool,
-	) -> Result<String, ModelError> {
+	) -> Result<Transcript, ModelError> {
+		self.try_get_result(language, verbose, translate).await
+	}
+
+	/// Like [`Self::get_result`], but keeps the stream, so its audio can be replayed if this fails.
+	pub(crate) async fn try_get_result(
+		&mut self,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError> {
 		debug!(%self.session_id, %self.peer_address, "getting result from stts");
 		// send the finalize message
 		self.tx
@@ -106,12 +203,18 @@ impl Stream {
 				},
 			))
 			.map_err(|_| ModelError::RemoteDisconnected)?;
+		let finalize_start = Instant::now();
 		let stream_fut = async {
 			while let Ok(next) = self.rx.recv().await {
 				if let ServerToClientMessage::SttResult(SttSuccess { id, result }) = next {
 					if id == self.session_id {
 						debug!(%self.session_id, %self.peer_address, "got result from stts");
-						return Ok(result);
+						self.stats.record_latency(finalize_start.elapsed());
+						// the transport has no timings or confidence, see `Transcript::segments`
+						return Ok(Transcript {
+							text:     result,
+							segments: Vec::new(),
+						});
 					}
 				} else if let ServerToClientMessage::SttError(SttError { id, error }) = next {
 					if id == self.session_id {
@@ -123,7 +226,7 @@ impl Stream {
 			}
 			Err(ModelError::RemoteDisconnected)
 		};
-		match tokio::time::timeout(Duration::from_secs(30), stream_fut).await {
+		match tokio::time::timeout(RESULT_TIMEOUT, stream_fut).await {
 			Ok(Ok(res)) => Ok(res),
 			Ok(Err(e)) => Err(e),
 			Err(_) => {
@@ -135,6 +238,12 @@ impl Stream {
 	}
 }
 
+impl Drop for RemoteStream {
+	fn drop(&mut self) {
+		self.stats.stream_closed();
+	}
+}
+
 #[derive(Debug)]
 pub enum ModelError {
 	Io(io::Error),
@@ -149,12 +258,26 @@ pub enum ModelError {
 	InitializationTimedOut,
 	TimedOutWaitingForResult,
 	RemoteDisconnected,
+	/// A partial result was requested from a stream without partial results enabled
+	PartialResultsDisabled,
+	/// The in-process whisper engine failed
+	Whisper(String),
 	InvalidPayload {
 		expected: Vec<u8>,
 		got:      Vec<u8>,
 	},
 }
 
+impl ModelError {
+	/// Whether the audio could still be transcribed by replaying it on another server.
+	pub fn is_retryable(&self) -> bool {
+		matches!(
+			self,
+			ModelError::RemoteDisconnected | ModelError::TimedOutWaitingForResult
+		)
+	}
+}
+
 impl std::error::Error for ModelError {}
 
 impl From<io::Error> for ModelError {
@@ -220,16 +343,183 @@ impl std::fmt::Display for ModelError {
 			ModelError::TimedOutWaitingForResult => {
 				write!(f, "timed out waiting for result")
 			}
+			ModelError::PartialResultsDisabled => {
+				write!(f, "partial results are not enabled on this stream")
+			}
+			ModelError::Whisper(e) => write!(f, "whisper error: {}", e),
 		}
 	}
 }
 
+/// A finished transcript of all the audio fed to a stream.
+#[derive(Debug, Clone, Default)]
 pub struct Transcript {
-	pub result: String,
+	/// The full text of the transcript.
+	pub text:     String,
+	/// The segments the backend split the transcript into, in order.
+	///
+	/// Always empty for remote STT servers, as the STT transport only carries the text.
+	/// Only the whisper backend reports segments.
+	pub segments: Vec<TranscriptSegment>,
 }
 
-pub struct VerboseTranscript {
-	pub num_transcripts: u32,
-	pub main_transcript: Option<String>,
-	pub main_confidence: Option<f64>,
+impl Transcript {
+	/// How confident the model was in this transcript overall, from 0 to 1.
+	///
+	/// This is the average of each segment's confidence, weighted by how long the segment is.
+	/// Returns `None` if there are no segments.
+	pub fn confidence(&self) -> Option<f64> {
+		if self.segments.is_empty() {
+			return None;
+		}
+
+		let total_length: f64 = self.segments.iter().map(TranscriptSegment::length).sum();
+		if total_length <= 0.0 {
+			// no timing info to weight by, so fall back to a plain average
+			return Some(
+				self.segments.iter().map(|s| s.confidence).sum::<f64>()
+					/ self.segments.len() as f64,
+			);
+		}
+
+		Some(
+			self.segments
+				.iter()
+				.map(|s| s.confidence * s.length())
+				.sum::<f64>()
+				/ total_length,
+		)
+	}
+}
+
+/// One segment of a transcript, usually a sentence or so long.
+#[derive(Debug, Clone)]
+pub struct TranscriptSegment {
+	/// Seconds from the start of the stream this segment starts at.
+	pub start:      f64,
+	/// Seconds from the start of the stream this segment ends at.
+	pub end:        f64,
+	pub text:       String,
+	/// How confident the model was in this segment, from 0 to 1.
+	pub confidence: f64,
+	pub words:      Vec<TranscriptWord>,
+}
+
+impl TranscriptSegment {
+	/// Length of this segment, in seconds.
+	pub fn length(&self) -> f64 {
+		(self.end - self.start).max(0.0)
+	}
+}
+
+/// A single word of a transcript.
+#[derive(Debug, Clone)]
+pub struct TranscriptWord {
+	pub word:       String,
+	/// Seconds from the start of the stream this word starts at.
+	pub start:      f64,
+	/// Seconds from the start of the stream this word ends at.
+	pub end:        f64,
+	/// How confident the model was in this word, from 0 to 1.
+	pub confidence: f64,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::PARTIAL_RESULT_WINDOW_SAMPLES;
+	use crate::{
+		load_balancer::LoadBalancedStream,
+		mock_server::{MockBehavior, MockSttServer},
+		ModelError,
+	};
+
+	async fn connect(server: &MockSttServer) -> (LoadBalancedStream, flume::Receiver<()>) {
+		let (purge_tx, purge_rx) = flume::unbounded();
+		let worker = LoadBalancedStream::new(server.address(), server.service(), purge_tx)
+			.await
+			.expect("failed to connect to mock server");
+		(worker, purge_rx)
+	}
+
+	#[tokio::test]
+	async fn test_get_result() {
+		let server = MockSttServer::start(false).await.unwrap();
+		server.set_behavior(MockBehavior::Transcribe("hello world".to_string()));
+		let (worker, purge_rx) = connect(&server).await;
+
+		let stream = worker.open_connection().await.unwrap();
+		stream.feed_audio(vec![0; 320]).unwrap();
+		let transcript = stream
+			.get_result("en".to_string(), false, false)
+			.await
+			.unwrap();
+
+		assert_eq!(transcript.text, "hello world");
+		assert_eq!(server.streams_opened(), 1);
+		assert_eq!(server.audio_samples(), 320);
+		assert!(purge_rx.is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_get_result_error() {
+		let server = MockSttServer::start(false).await.unwrap();
+		server.set_behavior(MockBehavior::Error("model exploded".to_string()));
+		let (worker, purge_rx) = connect(&server).await;
+
+		let stream = worker.open_connection().await.unwrap();
+		let result = stream.get_result("en".to_string(), false, false).await;
+
+		assert!(matches!(result, Err(ModelError::SttsServer(e)) if e == "model exploded"));
+		// streams queued on this server may be in a bad state too
+		assert!(purge_rx.try_recv().is_ok());
+	}
+
+	#[tokio::test]
+	async fn test_get_result_timeout() {
+		let server = MockSttServer::start(false).await.unwrap();
+		server.set_behavior(MockBehavior::IgnoreFinalize);
+		let (worker, purge_rx) = connect(&server).await;
+		let stream = worker.open_connection().await.unwrap();
+
+		// skip ahead to the timeout instead of waiting for it
+		tokio::time::pause();
+		let result = stream.get_result("en".to_string(), false, false).await;
+
+		assert!(matches!(result, Err(ModelError::TimedOutWaitingForResult)));
+		assert!(purge_rx.try_recv().is_ok());
+	}
+
+	#[tokio::test]
+	async fn test_partial_audio_window() {
+		let server = MockSttServer::start(false).await.unwrap();
+		let (worker, _purge_rx) = connect(&server).await;
+		let stream = worker.open_connection().await.unwrap();
+		assert!(stream.partial_audio().is_none());
+
+		stream.enable_partial_results();
+		stream
+			.feed_audio(vec![1; PARTIAL_RESULT_WINDOW_SAMPLES])
+			.unwrap();
+		stream.feed_audio(vec![2; 320]).unwrap();
+
+		// only the most recent audio is kept for partial results
+		let audio = stream.partial_audio().unwrap();
+		assert_eq!(audio.len(), PARTIAL_RESULT_WINDOW_SAMPLES);
+		assert!(audio[audio.len() - 320..].iter().all(|&s| s == 2));
+		assert!(audio[..audio.len() - 320].iter().all(|&s| s == 1));
+	}
+
+	#[tokio::test]
+	async fn test_initialization_timeout() {
+		let server = MockSttServer::start(false).await.unwrap();
+		server.set_behavior(MockBehavior::IgnoreInitialize);
+		let (worker, _purge_rx) = connect(&server).await;
+
+		tokio::time::pause();
+		let result = worker.open_connection().await;
+
+		assert!(matches!(result, Err(ModelError::InitializationTimedOut)));
+		// no new streams are opened on the server until it acknowledges one again
+		assert!(worker.is_in_error());
+	}
 }
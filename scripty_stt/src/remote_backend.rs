@@ -0,0 +1,91 @@
+//! The remote backend: streams are spread over a pool of STT servers, spoken to over TCP.
+
+use futures::future::BoxFuture;
+
+use crate::{
+	backend::{SttBackend, SttStream},
+	load_balancer::LoadBalancer,
+	ModelError,
+	RemoteStream,
+	Transcript,
+};
+
+pub struct RemoteBackend {
+	balancer: LoadBalancer,
+}
+
+impl RemoteBackend {
+	pub fn new(balancer: LoadBalancer) -> Self {
+		Self { balancer }
+	}
+}
+
+#[async_trait]
+impl SttBackend for RemoteBackend {
+	async fn open_stream(&self) -> Result<Box<dyn SttStream>, ModelError> {
+		let stream = self.balancer.get_stream().await?;
+		Ok(Box::new(LoadBalancedSttStream {
+			stream,
+			balancer: self.balancer.clone(),
+		}))
+	}
+}
+
+/// A stream on one server of the pool, which can be moved to another server if that one fails.
+struct LoadBalancedSttStream {
+	stream:   RemoteStream,
+	balancer: LoadBalancer,
+}
+
+#[async_trait]
+impl SttStream for LoadBalancedSttStream {
+	fn feed_audio(&self, data: Vec<i16>) -> Result<(), ModelError> {
+		self.stream.feed_audio(data)
+	}
+
+	fn enable_partial_results(&self) {
+		self.stream.enable_partial_results()
+	}
+
+	fn get_partial_result(
+		&self,
+		language: String,
+		translate: bool,
+	) -> BoxFuture<'static, Result<String, ModelError>> {
+		// transcribe a copy of the recent audio on a separate stream, leaving this one open.
+		// that stream is opened fresh, so partial results don't use up the queue of warm streams
+		let audio = self.stream.partial_audio();
+		let balancer = self.balancer.clone();
+		Box::pin(async move {
+			let audio = audio.ok_or(ModelError::PartialResultsDisabled)?;
+			let stream = balancer.spawn_new_stream().await?;
+			stream.feed_audio(audio)?;
+			stream
+				.get_result(language, false, translate)
+				.await
+				.map(|transcript| transcript.text)
+		})
+	}
+
+	async fn get_result(
+		self: Box<Self>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError> {
+		self.stream.get_result(language, verbose, translate).await
+	}
+
+	async fn get_result_with_retries(
+		self: Box<Self>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+		attempts: usize,
+	) -> Result<Transcript, ModelError> {
+		let Self { stream, balancer } = *self;
+		balancer
+			.get_result_with_retries(stream, language, verbose, translate, attempts)
+			.await
+	}
+}
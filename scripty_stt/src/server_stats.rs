@@ -0,0 +1,120 @@
+//! Load and health of each STT server, used to pick which one new streams are opened on.
+
+use std::{
+	net::SocketAddr,
+	sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+	time::Duration,
+};
+
+/// How much each new result moves the latency average, from 0 to 1.
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+
+/// Latency a server is assumed to have before it has returned any results.
+///
+/// Low enough that new servers are tried quickly, so they get a real latency of their own.
+const UNKNOWN_LATENCY: f64 = 0.01;
+
+pub struct ServerStats {
+	/// Label this server's metrics are exported under.
+	label:     String,
+	/// Streams currently open on this server, including queued ones that haven't been used yet.
+	in_flight: AtomicUsize,
+	/// Exponentially weighted moving average of seconds taken to return a result,
+	/// stored as the bits of an `f64`. 0 if no results have been returned yet.
+	latency:   AtomicU64,
+}
+
+impl ServerStats {
+	pub fn new(peer_address: SocketAddr) -> Self {
+		Self {
+			label:     peer_address.to_string(),
+			in_flight: AtomicUsize::new(0),
+			latency:   AtomicU64::new(0.0_f64.to_bits()),
+		}
+	}
+
+	#[inline]
+	pub fn in_flight(&self) -> usize {
+		self.in_flight.load(Ordering::Relaxed)
+	}
+
+	/// Average seconds this server takes to return a result, or `None` if it hasn't returned any.
+	pub fn latency(&self) -> Option<f64> {
+		let latency = f64::from_bits(self.latency.load(Ordering::Relaxed));
+		(latency > 0.0).then_some(latency)
+	}
+
+	/// How busy this server is, taking both its speed and open streams into account.
+	/// Lower is better.
+	pub fn latency_score(&self) -> f64 {
+		self.latency().unwrap_or(UNKNOWN_LATENCY) * (self.in_flight() + 1) as f64
+	}
+
+	pub fn stream_opened(&self) {
+		let in_flight = self.in_flight.fetch_add(1, Ordering::Relaxed) + 1;
+		self.export_in_flight(in_flight);
+	}
+
+	pub fn stream_closed(&self) {
+		let in_flight = self.in_flight.fetch_sub(1, Ordering::Relaxed) - 1;
+		self.export_in_flight(in_flight);
+	}
+
+	/// Add how long a result took to the latency average.
+	pub fn record_latency(&self, latency: Duration) {
+		let sample = latency.as_secs_f64();
+		let mut new = sample;
+		// can't fail, as the closure always returns Some
+		let _ = self
+			.latency
+			.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |old| {
+				new = next_ewma(f64::from_bits(old), sample);
+				Some(new.to_bits())
+			});
+
+		scripty_metrics::get_metrics()
+			.stt_server_latency
+			.with_label_values(&[&self.label])
+			.set(new);
+	}
+
+	/// Export whether the server passed its last health check.
+	pub fn record_health(&self, healthy: bool) {
+		scripty_metrics::get_metrics()
+			.stt_server_healthy
+			.with_label_values(&[&self.label])
+			.set(healthy as i64);
+	}
+
+	fn export_in_flight(&self, in_flight: usize) {
+		scripty_metrics::get_metrics()
+			.stt_server_in_flight
+			.with_label_values(&[&self.label])
+			.set(in_flight as i64);
+	}
+}
+
+impl Drop for ServerStats {
+	fn drop(&mut self) {
+		// the server has left the pool, so stop exporting stale numbers for it
+		let metrics = scripty_metrics::get_metrics();
+		let _ = metrics
+			.stt_server_in_flight
+			.remove_label_values(&[&self.label]);
+		let _ = metrics
+			.stt_server_latency
+			.remove_label_values(&[&self.label]);
+		let _ = metrics
+			.stt_server_healthy
+			.remove_label_values(&[&self.label]);
+	}
+}
+
+/// Move a latency average towards a new sample. An average of 0 has no samples yet.
+fn next_ewma(average: f64, sample: f64) -> f64 {
+	if average <= 0.0 {
+		sample
+	} else {
+		average + LATENCY_EWMA_ALPHA * (sample - average)
+	}
+}
@@ -0,0 +1,222 @@
+//! The whisper backend: whisper.cpp runs inside the bot, on the CPU.
+//!
+//! Meant for small deployments that don't want to run a separate STT service.
+//! whisper.cpp transcribes all of a stream's audio at once, so nothing is sent anywhere
+//! until the stream is finalized.
+//!
+//! Only one transcription runs at a time, so live partial transcripts aren't supported:
+//! they would hold up the final transcripts queued behind them.
+
+use std::{num::NonZeroUsize, sync::Arc};
+
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use tokio::sync::Semaphore;
+use whisper_rs::{
+	FullParams,
+	SamplingStrategy,
+	WhisperContext,
+	WhisperContextParameters,
+	WhisperError,
+};
+
+use crate::{
+	backend::{SttBackend, SttStream},
+	ModelError,
+	Transcript,
+	TranscriptSegment,
+	TranscriptWord,
+};
+
+pub struct WhisperBackend {
+	engine: Arc<WhisperEngine>,
+}
+
+impl WhisperBackend {
+	/// Load a GGML whisper model.
+	///
+	/// If `threads` isn't set, every available core is used.
+	pub fn new(model_path: &str, threads: Option<usize>) -> Result<Self, ModelError> {
+		info!(%model_path, "loading whisper model");
+		let context =
+			WhisperContext::new_with_params(model_path, WhisperContextParameters::default())?;
+		let threads = threads
+			.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, NonZeroUsize::get));
+		info!(threads, "loaded whisper model");
+
+		Ok(Self {
+			engine: Arc::new(WhisperEngine {
+				context,
+				threads,
+				permit: Semaphore::new(1),
+			}),
+		})
+	}
+}
+
+#[async_trait]
+impl SttBackend for WhisperBackend {
+	async fn open_stream(&self) -> Result<Box<dyn SttStream>, ModelError> {
+		Ok(Box::new(WhisperStream {
+			engine: Arc::clone(&self.engine),
+			audio:  Mutex::new(Vec::new()),
+		}))
+	}
+}
+
+struct WhisperEngine {
+	context: WhisperContext,
+	threads: usize,
+	/// Only one transcription runs at a time, as each already uses every thread it's given.
+	permit:  Semaphore,
+}
+
+impl WhisperEngine {
+	/// Transcribe audio on a blocking thread, waiting for any other transcription to finish first.
+	async fn transcribe(
+		self: Arc<Self>,
+		audio: Vec<i16>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError> {
+		let _permit = self
+			.permit
+			.acquire()
+			.await
+			.expect("whisper semaphore is never closed");
+		let engine = Arc::clone(&self);
+		tokio::task::spawn_blocking(move || {
+			engine.transcribe_blocking(&audio, &language, verbose, translate)
+		})
+		.await
+		.map_err(|e| ModelError::Whisper(format!("transcription task failed: {}", e)))?
+		.map_err(ModelError::from)
+	}
+
+	fn transcribe_blocking(
+		&self,
+		audio: &[i16],
+		language: &str,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, WhisperError> {
+		let audio: Vec<f32> = audio.iter().map(|&s| s as f32 / 32768.0).collect();
+
+		let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+		params.set_n_threads(self.threads as i32);
+		params.set_language(Some(language));
+		params.set_translate(translate);
+		// word timings are only shown in verbose transcripts
+		params.set_token_timestamps(verbose);
+		params.set_print_special(false);
+		params.set_print_progress(false);
+		params.set_print_realtime(false);
+		params.set_print_timestamps(false);
+
+		let mut state = self.context.create_state()?;
+		state.full(params, &audio)?;
+
+		let mut segments = Vec::new();
+		for i in 0..state.full_n_segments()? {
+			let mut words: Vec<TranscriptWord> = Vec::new();
+			let mut probabilities = Vec::new();
+			for j in 0..state.full_n_tokens(i)? {
+				let token = state.full_get_token_text(i, j)?;
+				// skip special tokens, like [_BEG_] and <|endoftext|>
+				if token.starts_with("[_") || token.starts_with("<|") {
+					continue;
+				}
+				let data = state.full_get_token_data(i, j)?;
+				let probability = data.p as f64;
+				probabilities.push(probability);
+				if !verbose {
+					continue;
+				}
+
+				// timestamps are in hundredths of a second
+				let (start, end) = (data.t0 as f64 / 100.0, data.t1 as f64 / 100.0);
+				match words.last_mut() {
+					// words can be split over several tokens, only the first of which starts with a space
+					Some(word) if !token.starts_with(' ') => {
+						word.word.push_str(&token);
+						word.end = end;
+						word.confidence = word.confidence.min(probability);
+					}
+					_ => words.push(TranscriptWord {
+						word: token.trim_start().to_string(),
+						start,
+						end,
+						confidence: probability,
+					}),
+				}
+			}
+
+			segments.push(TranscriptSegment {
+				start: state.full_get_segment_t0(i)? as f64 / 100.0,
+				end: state.full_get_segment_t1(i)? as f64 / 100.0,
+				text: state.full_get_segment_text(i)?,
+				confidence: if probabilities.is_empty() {
+					0.0
+				} else {
+					probabilities.iter().sum::<f64>() / probabilities.len() as f64
+				},
+				words,
+			});
+		}
+
+		Ok(Transcript {
+			text: segments
+				.iter()
+				.map(|segment| segment.text.as_str())
+				.collect::<String>()
+				.trim()
+				.to_string(),
+			segments,
+		})
+	}
+}
+
+struct WhisperStream {
+	engine: Arc<WhisperEngine>,
+	/// All audio fed so far, as it's only transcribed once the stream is finalized.
+	audio:  Mutex<Vec<i16>>,
+}
+
+#[async_trait]
+impl SttStream for WhisperStream {
+	fn feed_audio(&self, data: Vec<i16>) -> Result<(), ModelError> {
+		self.audio.lock().extend_from_slice(&data);
+		Ok(())
+	}
+
+	fn enable_partial_results(&self) {
+		// partial results are never available, see the module docs
+	}
+
+	fn get_partial_result(
+		&self,
+		_language: String,
+		_translate: bool,
+	) -> BoxFuture<'static, Result<String, ModelError>> {
+		Box::pin(async { Err(ModelError::PartialResultsDisabled) })
+	}
+
+	async fn get_result(
+		self: Box<Self>,
+		language: String,
+		verbose: bool,
+		translate: bool,
+	) -> Result<Transcript, ModelError> {
+		let Self { engine, audio } = *self;
+		engine
+			.transcribe(audio.into_inner(), language, verbose, translate)
+			.await
+	}
+}
+
+impl From<WhisperError> for ModelError {
+	fn from(err: WhisperError) -> Self {
+		ModelError::Whisper(err.to_string())
+	}
+}
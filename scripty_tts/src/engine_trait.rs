@@ -9,6 +9,9 @@ pub trait TtsEngine {
 		text: &str,
 		params: &EngineParameters,
 	) -> Result<TtsEngineOutput, Self::Error>;
+
+	/// Sample rate of any raw PCM returned by this engine, in Hz.
+	fn sample_rate(&self) -> u32;
 }
 
 pub enum TtsEngineOutput {
@@ -16,6 +19,7 @@ pub enum TtsEngineOutput {
 	RawPcm(Vec<i16>),
 }
 
+#[derive(Debug, Clone)]
 pub struct EngineParameters {
 	pub voice:     String,
 	pub amplitude: u8,
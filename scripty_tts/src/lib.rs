@@ -1,3 +1,6 @@
 mod engine_trait;
 
 mod tts_engines;
+
+pub use engine_trait::{EngineParameters, TtsEngine, TtsEngineOutput};
+pub use tts_engines::*;
@@ -1,8 +1,159 @@
+use std::{
+	ffi::{c_char, c_int, c_short, c_uint, c_void, CString},
+	fmt,
+	sync::{Mutex, OnceLock},
+};
+
 use crate::engine_trait::{EngineParameters, TtsEngine, TtsEngineOutput};
 
-pub struct EspeakNgEngine;
+/// Raw bindings to the parts of libespeak-ng's `speak_lib.h` that we use.
+mod ffi {
+	use super::*;
+
+	/// `AUDIO_OUTPUT_SYNCHRONOUS`: samples are handed to the synth callback,
+	/// and `espeak_Synth` does not return until synthesis is complete.
+	pub const AUDIO_OUTPUT_SYNCHRONOUS: c_int = 2;
+
+	/// `espeakINITIALIZE_DONT_EXIT`: don't let the library call `exit()` if it can't find its data.
+	pub const ESPEAK_INITIALIZE_DONT_EXIT: c_int = 0x8000;
+
+	/// `espeakCHARS_UTF8`
+	pub const ESPEAK_CHARS_UTF8: c_uint = 1;
+	/// `POS_CHARACTER`
+	pub const POS_CHARACTER: c_int = 1;
+
+	pub const EE_OK: c_int = 0;
+	pub const EE_INTERNAL_ERROR: c_int = -1;
+	pub const EE_BUFFER_FULL: c_int = 1;
+	pub const EE_NOT_FOUND: c_int = 2;
 
-pub struct EspeakNgError(u8);
+	pub const ESPEAK_RATE: c_int = 1;
+	pub const ESPEAK_VOLUME: c_int = 2;
+	pub const ESPEAK_PITCH: c_int = 3;
+	pub const ESPEAK_WORDGAP: c_int = 7;
+
+	#[repr(C)]
+	pub union EspeakEventId {
+		pub number: c_int,
+		pub name:   *const c_char,
+		pub string: [c_char; 8],
+	}
+
+	#[repr(C)]
+	pub struct EspeakEvent {
+		pub event_type:        c_int,
+		pub unique_identifier: c_uint,
+		pub text_position:     c_int,
+		pub length:            c_int,
+		pub audio_position:    c_int,
+		pub sample:            c_int,
+		pub user_data:         *mut c_void,
+		pub id:                EspeakEventId,
+	}
+
+	pub type SynthCallback = unsafe extern "C" fn(
+		wav: *mut c_short,
+		num_samples: c_int,
+		events: *mut EspeakEvent,
+	) -> c_int;
+
+	#[link(name = "espeak-ng")]
+	extern "C" {
+		pub fn espeak_Initialize(
+			output: c_int,
+			buflength: c_int,
+			path: *const c_char,
+			options: c_int,
+		) -> c_int;
+		pub fn espeak_SetSynthCallback(callback: SynthCallback);
+		pub fn espeak_SetVoiceByName(name: *const c_char) -> c_int;
+		pub fn espeak_SetParameter(parameter: c_int, value: c_int, relative: c_int) -> c_int;
+		pub fn espeak_Synth(
+			text: *const c_void,
+			size: usize,
+			position: c_uint,
+			position_type: c_int,
+			end_position: c_uint,
+			flags: c_uint,
+			unique_identifier: *mut c_uint,
+			user_data: *mut c_void,
+		) -> c_int;
+		pub fn espeak_Synchronize() -> c_int;
+	}
+}
+
+/// libespeak-ng keeps all of its state in globals, so only one synthesis can run at a time.
+///
+/// The value inside is the sample rate the library was initialized with,
+/// or `None` if initialization failed.
+static ESPEAK_STATE: OnceLock<Mutex<Option<u32>>> = OnceLock::new();
+
+fn get_state() -> &'static Mutex<Option<u32>> {
+	ESPEAK_STATE.get_or_init(|| {
+		// SAFETY: we're inside a OnceLock initializer, so this can only ever run once,
+		// and nothing else can touch the library until it's complete.
+		let sample_rate = unsafe {
+			ffi::espeak_Initialize(
+				ffi::AUDIO_OUTPUT_SYNCHRONOUS,
+				0,
+				std::ptr::null(),
+				ffi::ESPEAK_INITIALIZE_DONT_EXIT,
+			)
+		};
+		if sample_rate <= 0 {
+			return Mutex::new(None);
+		}
+
+		// SAFETY: see above
+		unsafe { ffi::espeak_SetSynthCallback(synth_callback) };
+
+		Mutex::new(Some(sample_rate as u32))
+	})
+}
+
+/// Called by libespeak-ng with each chunk of synthesized audio.
+///
+/// `user_data` on every event is the `Vec<i16>` passed to `espeak_Synth`.
+unsafe extern "C" fn synth_callback(
+	wav: *mut c_short,
+	num_samples: c_int,
+	events: *mut ffi::EspeakEvent,
+) -> c_int {
+	// a null wav pointer means synthesis is complete
+	if wav.is_null() || events.is_null() || num_samples <= 0 {
+		return 0;
+	}
+
+	let user_data = (*events).user_data;
+	if user_data.is_null() {
+		return 0;
+	}
+
+	let output = &mut *(user_data as *mut Vec<i16>);
+	output.extend_from_slice(std::slice::from_raw_parts(wav, num_samples as usize));
+
+	// 0 means continue synthesis
+	0
+}
+
+/// TTS engine backed by libespeak-ng.
+///
+/// Output is raw, mono, signed 16-bit PCM at [`TtsEngine::sample_rate`].
+pub struct EspeakNgEngine {
+	sample_rate: u32,
+}
+
+impl EspeakNgEngine {
+	/// Initialize libespeak-ng if it hasn't been already, and return a handle to it.
+	pub fn new() -> Result<Self, EspeakNgError> {
+		let sample_rate = get_state()
+			.lock()
+			.unwrap_or_else(|e| e.into_inner())
+			.ok_or(EspeakNgError::InitializationFailed)?;
+
+		Ok(Self { sample_rate })
+	}
+}
 
 impl TtsEngine for EspeakNgEngine {
 	type Error = EspeakNgError;
@@ -12,5 +163,196 @@ impl TtsEngine for EspeakNgEngine {
 		text: &str,
 		params: &EngineParameters,
 	) -> Result<TtsEngineOutput, Self::Error> {
+		let text = CString::new(text).map_err(|_| EspeakNgError::TextContainsNul)?;
+		let voice = CString::new(params.voice.as_str())
+			.map_err(|_| EspeakNgError::VoiceNotFound(params.voice.clone()))?;
+
+		// hold this for the entire synthesis: all the calls below modify global state
+		let guard = get_state().lock().unwrap_or_else(|e| e.into_inner());
+		if guard.is_none() {
+			return Err(EspeakNgError::InitializationFailed);
+		}
+
+		// SAFETY: the global lock is held, and `voice` outlives the call
+		let res = unsafe { ffi::espeak_SetVoiceByName(voice.as_ptr()) };
+		if res != ffi::EE_OK {
+			return Err(match EspeakNgError::from_code(res) {
+				EspeakNgError::NotFound => EspeakNgError::VoiceNotFound(params.voice.clone()),
+				e => e,
+			});
+		}
+
+		for (parameter, value) in [
+			(ffi::ESPEAK_RATE, params.speed as c_int),
+			(ffi::ESPEAK_VOLUME, params.amplitude as c_int),
+			(ffi::ESPEAK_PITCH, params.pitch as c_int),
+			(ffi::ESPEAK_WORDGAP, params.gap as c_int),
+		] {
+			// SAFETY: the global lock is held
+			let res = unsafe { ffi::espeak_SetParameter(parameter, value, 0) };
+			if res != ffi::EE_OK {
+				return Err(EspeakNgError::from_code(res));
+			}
+		}
+
+		let mut output: Vec<i16> = Vec::new();
+		let text_bytes = text.as_bytes_with_nul();
+		// SAFETY: the global lock is held, `text` and `output` both outlive the call,
+		// and in synchronous mode the callback never runs after `espeak_Synth` returns
+		let res = unsafe {
+			ffi::espeak_Synth(
+				text_bytes.as_ptr() as *const c_void,
+				text_bytes.len(),
+				0,
+				ffi::POS_CHARACTER,
+				0,
+				ffi::ESPEAK_CHARS_UTF8,
+				std::ptr::null_mut(),
+				&mut output as *mut Vec<i16> as *mut c_void,
+			)
+		};
+		if res != ffi::EE_OK {
+			return Err(EspeakNgError::from_code(res));
+		}
+		// SAFETY: the global lock is held
+		let res = unsafe { ffi::espeak_Synchronize() };
+		if res != ffi::EE_OK {
+			return Err(EspeakNgError::from_code(res));
+		}
+		drop(guard);
+
+		Ok(TtsEngineOutput::RawPcm(output))
+	}
+
+	fn sample_rate(&self) -> u32 {
+		self.sample_rate
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EspeakNgError {
+	/// libespeak-ng failed to initialize, likely because its data files could not be found.
+	InitializationFailed,
+	/// The requested voice does not exist.
+	VoiceNotFound(String),
+	/// The text passed in contained a NUL byte, which can't be passed to C.
+	TextContainsNul,
+	/// `EE_INTERNAL_ERROR`
+	InternalError,
+	/// `EE_BUFFER_FULL`: should never happen in synchronous mode.
+	BufferFull,
+	/// `EE_NOT_FOUND`
+	NotFound,
+	/// Any return code not documented in `speak_lib.h`.
+	Unknown(i32),
+}
+
+impl EspeakNgError {
+	fn from_code(code: c_int) -> Self {
+		match code {
+			ffi::EE_INTERNAL_ERROR => Self::InternalError,
+			ffi::EE_BUFFER_FULL => Self::BufferFull,
+			ffi::EE_NOT_FOUND => Self::NotFound,
+			other => Self::Unknown(other),
+		}
+	}
+}
+
+impl fmt::Display for EspeakNgError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InitializationFailed => f.write_str("failed to initialize libespeak-ng"),
+			Self::VoiceNotFound(voice) => write!(f, "espeak-ng voice not found: {}", voice),
+			Self::TextContainsNul => f.write_str("text contained a NUL byte"),
+			Self::InternalError => f.write_str("espeak-ng internal error"),
+			Self::BufferFull => f.write_str("espeak-ng buffer full"),
+			Self::NotFound => f.write_str("espeak-ng returned not found"),
+			Self::Unknown(code) => write!(f, "espeak-ng returned unknown error code {}", code),
+		}
+	}
+}
+
+impl std::error::Error for EspeakNgError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn default_params() -> EngineParameters {
+		EngineParameters {
+			voice:     "en".to_string(),
+			amplitude: 100,
+			gap:       0,
+			pitch:     50,
+			speed:     175,
+		}
+	}
+
+	fn synthesize(engine: &EspeakNgEngine, text: &str, params: &EngineParameters) -> Vec<i16> {
+		match engine.get_waveform(text, params).unwrap() {
+			TtsEngineOutput::RawPcm(pcm) => pcm,
+			TtsEngineOutput::Wav(_) => panic!("espeak-ng should always return raw PCM"),
+		}
+	}
+
+	#[test]
+	fn test_synthesize_short_phrase() {
+		let engine = EspeakNgEngine::new().unwrap();
+		let sample_rate = engine.sample_rate();
+		assert_eq!(sample_rate, 22050);
+
+		let pcm = synthesize(&engine, "hello world", &default_params());
+
+		// "hello world" at 175 WPM should take somewhere between a quarter second and three seconds
+		let min_samples = sample_rate as usize / 4;
+		let max_samples = sample_rate as usize * 3;
+		assert!(
+			(min_samples..max_samples).contains(&pcm.len()),
+			"got {} samples",
+			pcm.len()
+		);
+		// and not be silence
+		assert!(pcm.iter().any(|s| s.unsigned_abs() > 1000));
+	}
+
+	#[test]
+	fn test_speed_changes_length() {
+		let engine = EspeakNgEngine::new().unwrap();
+
+		let normal = synthesize(&engine, "the quick brown fox", &default_params());
+		let slow = synthesize(
+			&engine,
+			"the quick brown fox",
+			&EngineParameters {
+				speed: 80,
+				..default_params()
+			},
+		);
+		assert!(slow.len() > normal.len());
+	}
+
+	#[test]
+	fn test_invalid_voice() {
+		let engine = EspeakNgEngine::new().unwrap();
+
+		let res = engine.get_waveform(
+			"hello",
+			&EngineParameters {
+				voice: "not-a-real-voice".to_string(),
+				..default_params()
+			},
+		);
+		assert_eq!(
+			res.err(),
+			Some(EspeakNgError::VoiceNotFound("not-a-real-voice".to_string()))
+		);
+	}
+
+	#[test]
+	fn test_nul_in_text() {
+		let engine = EspeakNgEngine::new().unwrap();
+
+		let res = engine.get_waveform("hello\0world", &default_params());
+		assert_eq!(res.err(), Some(EspeakNgError::TextContainsNul));
 	}
 }
@@ -1 +1,3 @@
 mod espeak_ng;
+
+pub use espeak_ng::{EspeakNgEngine, EspeakNgError};
@@ -1,4 +1,4 @@
-use std::{str::FromStr, time::Duration};
+use std::{future::Future, str::FromStr, time::Duration};
 
 use serenity::{
 	all::{
@@ -20,7 +20,7 @@ use serenity::{
 	},
 	collector::ComponentInteractionCollector,
 	futures::StreamExt,
-	model::id::{ChannelId, UserId},
+	model::id::{ChannelId, MessageId, UserId},
 };
 
 pub async fn do_paginate(
@@ -32,6 +32,43 @@ pub async fn do_paginate(
 	max_per_page: Option<usize>,
 	allowed_user: Option<UserId>,
 ) -> Result<(), serenity::Error> {
+	do_paginate_with(
+		ctx,
+		|embed, components| async move {
+			let m = target_channel
+				.send_message(
+					ctx,
+					CreateMessage::default().embed(embed).components(components),
+				)
+				.await?;
+			Ok(m.id)
+		},
+		items,
+		title,
+		footer_additional,
+		max_per_page,
+		allowed_user,
+	)
+	.await
+}
+
+/// Like [`do_paginate`], but the first page is sent by `send_first_page`,
+/// which must return the ID of the message it sent.
+///
+/// Use this to paginate over an interaction response, ie an ephemeral reply.
+pub async fn do_paginate_with<F, Fut>(
+	ctx: &serenity::client::Context,
+	send_first_page: F,
+	items: Vec<(String, String)>,
+	title: String,
+	footer_additional: Option<String>,
+	max_per_page: Option<usize>,
+	allowed_user: Option<UserId>,
+) -> Result<(), serenity::Error>
+where
+	F: FnOnce(CreateEmbed, Vec<CreateActionRow>) -> Fut,
+	Fut: Future<Output = Result<MessageId, serenity::Error>>,
+{
 	let max_per_page = max_per_page.unwrap_or(10);
 	assert!(max_per_page > 0);
 	assert!(max_per_page <= 20);
@@ -45,23 +82,20 @@ pub async fn do_paginate(
 	let base_embed = CreateEmbed::default().title(title);
 	let mut current_page = 0;
 
-	let m = target_channel
-		.send_message(
-			ctx,
-			CreateMessage::default()
-				.embed(format_embed_from_page(
-					base_embed.clone(),
-					&pages[0],
-					current_page,
-					pages.len(),
-					footer_additional.clone(),
-				))
-				.components(build_components()),
-		)
-		.await?;
+	let message_id = send_first_page(
+		format_embed_from_page(
+			base_embed.clone(),
+			&pages[0],
+			current_page,
+			pages.len(),
+			footer_additional.clone(),
+		),
+		build_components(),
+	)
+	.await?;
 
 	let mut collector = ComponentInteractionCollector::new(&ctx.shard)
-		.message_id(m.id)
+		.message_id(message_id)
 		.timeout(Duration::from_secs(120));
 	if let Some(user) = allowed_user {
 		collector = collector.author_id(user);
@@ -8,7 +8,7 @@ mod hex_vec;
 pub mod latency;
 mod separate_num;
 
-pub use embed_pagination::do_paginate;
+pub use embed_pagination::{do_paginate, do_paginate_with};
 pub use hash_user_id::hash_user_id;
 pub use hex_vec::vec_to_hex;
 pub use separate_num::separate_num;
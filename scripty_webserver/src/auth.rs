@@ -47,3 +47,35 @@ where
 		Err(WebServerError::AuthenticationFailed(3))
 	}
 }
+
+/// Authentication for speech command integrations.
+///
+/// Set it as the type of an argument to a server endpoint handler to only allow integrations to call it.
+pub struct IntegrationAuthentication {
+	/// The ID of the integration that was authenticated.
+	pub integration_id: i32,
+}
+
+#[axum::async_trait]
+impl<S> FromRequestParts<S> for IntegrationAuthentication
+where
+	S: Send + Sync,
+{
+	type Rejection = WebServerError;
+
+	async fn from_request_parts(req: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+		// get token from header
+		let token = req
+			.headers
+			.get("Authorization")
+			.ok_or(WebServerError::AuthenticationFailed(1))?
+			.to_str()
+			.map_err(|_| WebServerError::AuthenticationFailed(2))?;
+
+		// check token
+		match scripty_speech_commands::authenticate(token).await? {
+			Some(integration_id) => Ok(IntegrationAuthentication { integration_id }),
+			None => Err(WebServerError::AuthenticationFailed(3)),
+		}
+	}
+}
@@ -2,6 +2,8 @@ pub mod bot_stats;
 pub mod languages;
 pub mod metrics;
 pub mod premium;
+pub mod speech_commands;
+pub mod transcripts;
 pub mod webhooks;
 
 pub fn router() -> axum::Router {
@@ -10,5 +12,7 @@ pub fn router() -> axum::Router {
 		.merge(metrics::router())
 		.merge(premium::router())
 		.merge(languages::router())
+		.merge(speech_commands::router())
+		.merge(transcripts::router())
 		.merge(webhooks::router())
 }
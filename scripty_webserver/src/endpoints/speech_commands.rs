@@ -0,0 +1,41 @@
+//! GET/PUT `/speech_commands/commands`
+//!
+//! Fetch or replace the speech commands handled by the authenticated integration.
+
+use axum::{routing::get, Json};
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::IntegrationAuthentication, errors::WebServerError};
+
+#[derive(Serialize, Deserialize)]
+pub struct SpeechCommandList {
+	pub commands: Vec<String>,
+}
+
+pub async fn get_commands(
+	IntegrationAuthentication { integration_id }: IntegrationAuthentication,
+) -> Json<SpeechCommandList> {
+	let commands = scripty_speech_commands::get_integrations()
+		.iter()
+		.find(|i| i.id == integration_id)
+		.map_or_else(Vec::new, |i| i.commands.clone());
+
+	Json(SpeechCommandList { commands })
+}
+
+pub async fn put_commands(
+	IntegrationAuthentication { integration_id }: IntegrationAuthentication,
+	Json(SpeechCommandList { commands }): Json<SpeechCommandList>,
+) -> Result<Json<SpeechCommandList>, WebServerError> {
+	scripty_speech_commands::validate_commands(&commands)?;
+	scripty_speech_commands::set_commands(integration_id, commands.clone()).await?;
+
+	Ok(Json(SpeechCommandList { commands }))
+}
+
+pub fn router() -> axum::Router {
+	axum::Router::new().route(
+		"/speech_commands/commands",
+		get(get_commands).put(put_commands),
+	)
+}
@@ -0,0 +1,67 @@
+//! GET `/transcripts/:guild_id/search`
+//!
+//! Search what was said in a guild's archived transcript sessions.
+
+use axum::{
+	extract::{Path, Query},
+	routing::get,
+	Json,
+};
+use scripty_audio_handler::TranscriptSearch;
+use scripty_bot_utils::extern_utils::GuildId;
+use serde::Serialize;
+
+use crate::{auth::Authentication, errors::WebServerError};
+
+#[derive(Serialize)]
+pub struct TranscriptSearchHit {
+	pub session_id:       i32,
+	pub voice_channel_id: u64,
+	/// Unix timestamp of when the utterance was said.
+	pub timestamp:        i64,
+	pub user_id:          u64,
+	pub username:         String,
+	pub text:             String,
+}
+
+#[derive(Serialize)]
+pub struct TranscriptSearchResults {
+	/// Matching utterances, newest first.
+	pub hits:     Vec<TranscriptSearchHit>,
+	/// `false` if the search gave up before looking through every session,
+	/// so there may be more hits than these.
+	pub complete: bool,
+}
+
+pub async fn search_transcripts(
+	Authentication { user_id, .. }: Authentication,
+	Path(guild_id): Path<u64>,
+	Query(search): Query<TranscriptSearch>,
+) -> Result<Json<TranscriptSearchResults>, WebServerError> {
+	if user_id != 0 {
+		return Err(WebServerError::AuthenticationFailed(3));
+	}
+
+	let results = scripty_audio_handler::search_sessions(GuildId::new(guild_id), &search).await?;
+	let hits = results
+		.hits
+		.into_iter()
+		.map(|hit| TranscriptSearchHit {
+			session_id:       hit.session_id,
+			voice_channel_id: hit.voice_channel_id.get(),
+			timestamp:        hit.timestamp,
+			user_id:          hit.user_id,
+			username:         hit.username,
+			text:             hit.text,
+		})
+		.collect();
+
+	Ok(Json(TranscriptSearchResults {
+		hits,
+		complete: results.complete,
+	}))
+}
+
+pub fn router() -> axum::Router {
+	axum::Router::new().route("/transcripts/:guild_id/search", get(search_transcripts))
+}
@@ -39,6 +39,15 @@ pub enum WebServerError {
 	///
 	/// Code `6`, no sub-code.
 	SerenityError,
+
+	/// The list of speech commands in the request was invalid.
+	///
+	/// Code `7`, sub-code is the inner integer of this variant.
+	///
+	/// Sub-code `1`: Too many commands were given.
+	/// Sub-code `2`: A command had no letters or numbers in it.
+	/// Sub-code `3`: A command was too long.
+	InvalidSpeechCommands(i32),
 }
 
 impl From<scripty_bot_utils::extern_utils::CacheNotInitializedError> for WebServerError {
@@ -53,6 +62,16 @@ impl From<scripty_bot_utils::extern_utils::SerenityError> for WebServerError {
 	}
 }
 
+impl From<scripty_speech_commands::InvalidCommands> for WebServerError {
+	fn from(e: scripty_speech_commands::InvalidCommands) -> Self {
+		WebServerError::InvalidSpeechCommands(match e {
+			scripty_speech_commands::InvalidCommands::TooMany => 1,
+			scripty_speech_commands::InvalidCommands::Empty => 2,
+			scripty_speech_commands::InvalidCommands::TooLong => 3,
+		})
+	}
+}
+
 impl From<sqlx::Error> for WebServerError {
 	fn from(e: sqlx::Error) -> Self {
 		WebServerError::DatabaseError(Some(e))
@@ -80,6 +99,7 @@ impl Display for WebServerError {
 			WebServerError::DatabaseError(None) => write!(f, "Database error"),
 			WebServerError::ParseIntError => write!(f, "Parse int error"),
 			WebServerError::SerenityError => write!(f, "Serenity error"),
+			WebServerError::InvalidSpeechCommands(_) => write!(f, "Invalid speech commands"),
 		}
 	}
 }
@@ -128,6 +148,9 @@ impl IntoResponse for WebServerError {
 				},
 				StatusCode::INTERNAL_SERVER_ERROR,
 			),
+			WebServerError::InvalidSpeechCommands(sub_code) => {
+				(ErrorJson { code: 7, sub_code }, StatusCode::BAD_REQUEST)
+			}
 		};
 
 		let bytes = match serde_json::to_vec(&body) {